@@ -1,18 +1,83 @@
-use web_sys::{AudioContext, GainNode};
+use web_sys::{AnalyserNode, AudioContext, AudioContextState, GainNode, MediaDeviceInfo, MediaDeviceKind, StereoPannerNode};
 use yew::services::{ConsoleService};
+use yew::services::interval::{IntervalService, IntervalTask};
+use yew::{ComponentLink};
 use wasm_bindgen::JsCast;
+use wasm_bindgen::JsValue;
+use wasm_bindgen::closure::Closure;
+use std::collections::HashMap;
+use std::time::Duration;
 use wasm_bindgen_futures::{spawn_local};
 use wasm_bindgen_futures::JsFuture;
-use web_sys::{AudioBuffer};
+use web_sys::{AudioBuffer,OscillatorType};
+
+use crate::model::{Model,Msg};
+
+// How often the squelch checks the signal level
+const SQUELCH_INTERVAL_MS: u64 = 50;
+// Level must drop this far below the threshold before the
+// squelch closes again, avoids chattering near the threshold
+const SQUELCH_HYSTERESIS_DB: f32 = 3.0;
+// Duration of one audio frame from SparkSDR (512 samples @ 48kHz)
+const AUDIO_FRAME_SECONDS: f64 = 512.0 / 48000.0;
+// Underruns within this window (seconds) count towards the
+// latency warning
+const UNDERRUN_WINDOW_SECONDS: f64 = 60.0;
+const UNDERRUN_WARNING_COUNT: usize = 5;
+// Queued audio beyond this is dropped so playback stays live
+const MAX_AUDIO_LATENCY_SECONDS: f64 = 2.0;
+
+// An audio output reported by navigator.mediaDevices
+#[derive(Clone, Debug)]
+pub struct AudioOutputDevice {
+    pub id: String,
+    pub label: String,
+}
 
 pub struct AudioProvider {
     // audio playback
     audio_ctx: Option<AudioContext>,
     gain: Option<GainNode>,
-    //pub analyser: AnalyserNode,
-    audio_pos: u64,
-    audio_start_time: f64,
+    // Per receiver stereo panner feeding the gain node
+    panners: HashMap<u32, StereoPannerNode>,
+    // Per receiver pan position (-1.0 left, 0.0 center, 1.0 right)
+    pan: HashMap<u32, f32>,
+    // Level meter tapped off the gain node (used by the squelch)
+    analyser: Option<AnalyserNode>,
+    // Gain stage between the gain node and output muted by the squelch
+    squelch: Option<GainNode>,
+    // Squelch threshold in dBFS (None when squelch is disabled)
+    squelch_level: Option<f32>,
+    squelch_open: bool,
+    squelch_task: Option<IntervalTask>,
+    // Context time the next audio frame will be played at (0.0 before
+    // the first frame)
+    next_start_time: f64,
+    // All scheduled frames play through this node, replaced when
+    // queued audio is dropped to silence anything already scheduled
+    queue: Option<GainNode>,
+    // Context times queued audio was dropped to catch up in the last
+    // UNDERRUN_WINDOW_SECONDS
+    overruns: Vec<f64>,
+    // How far ahead of the current time playback is scheduled
+    // (seconds), higher is smoother but less responsive
+    target_latency: f64,
+    // Context times playback ran dry in the last
+    // UNDERRUN_WINDOW_SECONDS
+    underruns: Vec<f64>,
     subscribed_audio: Option<u32>,
+    // Available audio outputs (empty until enumerated)
+    output_devices: Vec<AudioOutputDevice>,
+    // False while the browser's autoplay policy keeps the
+    // audio context suspended
+    audio_enabled: bool,
+    // Set if the browser blocked or lacks Web Audio, audio
+    // features are skipped
+    unavailable: Option<String>,
+    // Audio context statechange and page visibilitychange handlers,
+    // kept alive for the life of the provider
+    onstatechange: Option<Closure<dyn FnMut(JsValue)>>,
+    onvisibilitychange: Option<Closure<dyn FnMut(JsValue)>>,
 }
 
 impl AudioProvider {
@@ -20,9 +85,68 @@ impl AudioProvider {
         AudioProvider {
             audio_ctx: None,
             gain: None,
-            audio_pos: 0,
-            audio_start_time: 0.0,
+            panners: HashMap::new(),
+            pan: HashMap::new(),
+            analyser: None,
+            squelch: None,
+            squelch_level: None,
+            squelch_open: true,
+            squelch_task: None,
+            next_start_time: 0.0,
+            queue: None,
+            overruns: Vec::new(),
+            target_latency: 0.1,
+            underruns: Vec::new(),
             subscribed_audio: None,
+            output_devices: Vec::new(),
+            audio_enabled: false,
+            unavailable: None,
+            onstatechange: None,
+            onvisibilitychange: None,
+        }
+    }
+
+    // Browsers suspend the audio context when the tab is in the
+    // background or the machine sleeps. Emits Msg::AudioStateChanged
+    // when the context changes state and Msg::PageVisible when the
+    // tab is shown again.
+    pub fn watch_state(&mut self, link: &ComponentLink<Model>) {
+        if let Some(audio_ctx) = &self.audio_ctx {
+            let notify = link.callback(|_| Msg::AudioStateChanged);
+            let onstatechange = Closure::wrap(Box::new(move |_| {
+                notify.emit(());
+            }) as Box<dyn FnMut(JsValue)>);
+            audio_ctx.set_onstatechange(Some(onstatechange.as_ref().unchecked_ref()));
+            self.onstatechange = Some(onstatechange);
+        }
+        if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+            let notify = link.callback(|_| Msg::PageVisible);
+            let visible = document.clone();
+            let onvisibilitychange = Closure::wrap(Box::new(move |_| {
+                if !visible.hidden() {
+                    notify.emit(());
+                }
+            }) as Box<dyn FnMut(JsValue)>);
+            document.set_onvisibilitychange(Some(onvisibilitychange.as_ref().unchecked_ref()));
+            self.onvisibilitychange = Some(onvisibilitychange);
+        }
+    }
+
+    // Anything scheduled before a suspend is stale, start the queue
+    // again from the current context time once running
+    pub fn state_changed(&mut self) {
+        let state =
+            match &self.audio_ctx {
+                Some(audio_ctx) => audio_ctx.state(),
+                None => return,
+            };
+        ConsoleService::log(&format!("audio context state: {:?}", state));
+        match state {
+            AudioContextState::Running => {
+                self.audio_enabled = true;
+                self.reset_queue();
+            },
+            _ => self.audio_enabled = false,
         }
     }
 
@@ -32,54 +156,247 @@ impl AudioProvider {
 
     pub fn set_subscribed(&mut self, receiver: Option<u32>) {
         self.subscribed_audio = receiver;
-        match self.subscribed_audio {
-            Some(_) => (),
-            None => {
-                self.subscribed_audio = None;
-                self.audio_pos = 0;
-                self.audio_start_time = 0.0;
-            }
-        }
+        // start scheduling fresh for the new (or no) audio channel
+        self.reset_queue();
     }
 
+    // Set up audio playback. If the browser blocks audio or lacks
+    // Web Audio the app keeps running without audio.
     pub fn create_audio_context(&mut self) {
+        if let Err(err) = self.try_create_audio_context() {
+            ConsoleService::error(&format!("audio unavailable: {:?}", err));
+            self.unavailable = Some(format!("{:?}", err));
+        }
+    }
+
+    fn try_create_audio_context(&mut self) -> Result<(), JsValue> {
         // audio channel
-        let audio_ctx = web_sys::AudioContext::new().unwrap();
+        let audio_ctx = web_sys::AudioContext::new()?;
         let destination = audio_ctx.destination();
 
-        //let analyser = audio_ctx.create_analyser().unwrap();
-        //analyser.connect_with_audio_node(&destination).unwrap();
+        // gain -> squelch -> destination
+        //      \-> analyser
+        let squelch = audio_ctx.create_gain()?;
+        squelch.gain().set_value(1.0);
+        squelch.connect_with_audio_node(&destination)?;
 
-        let gain = audio_ctx.create_gain().unwrap();
+        let gain = audio_ctx.create_gain()?;
         gain.gain().set_value(1.0);
-        gain.connect_with_audio_node(&destination).unwrap();
+        gain.connect_with_audio_node(&squelch)?;
+
+        let analyser = audio_ctx.create_analyser()?;
+        analyser.set_fft_size(2048);
+        gain.connect_with_audio_node(&analyser)?;
+
+        self.analyser = Some(analyser);
+        self.squelch = Some(squelch);
+
+        // browsers start the context suspended until a user gesture
+        self.audio_enabled = audio_ctx.state() == AudioContextState::Running;
 
         self.audio_ctx = Some(audio_ctx);
         self.gain = Some(gain);
+        Ok(())
+    }
+
+    // Reason audio could not be set up, if it failed
+    pub fn unavailable(&self) -> Option<&String> {
+        self.unavailable.as_ref()
+    }
+
+    pub fn audio_enabled(&self) -> bool {
+        self.audio_enabled
+    }
+
+    pub fn set_audio_enabled(&mut self, enabled: bool) {
+        self.audio_enabled = enabled;
+    }
+
+    // Resume a suspended audio context, must be called from a user
+    // gesture (click). Emits Msg::AudioResumed once running.
+    pub fn resume(&self, link: &ComponentLink<Model>) {
+        if let Some(audio_ctx) = &self.audio_ctx {
+            if audio_ctx.state() != AudioContextState::Suspended {
+                return;
+            }
+            match audio_ctx.resume() {
+                Ok(promise) => {
+                    let callback = link.callback(|_| Msg::AudioResumed);
+                    spawn_local(async move {
+                        match JsFuture::from(promise).await {
+                            Ok(_) => callback.emit(()),
+                            Err(err) => {
+                                ConsoleService::error(&format!("unable to resume audio: {:?}", err));
+                            }
+                        }
+                    });
+                },
+                Err(err) => {
+                    ConsoleService::error(&format!("unable to resume audio: {:?}", err));
+                }
+            }
+        }
+    }
+
+    pub fn pan(&self, receiver_id: u32) -> f32 {
+        *self.pan.get(&receiver_id).unwrap_or(&0.0)
     }
 
-    pub fn import_audio_data(&mut self, data: js_sys::ArrayBuffer) {
-        match (self.audio_ctx(), self.gain()) {
+    pub fn set_pan(&mut self, receiver_id: u32, pan: f32) {
+        let pan = pan.max(-1.0).min(1.0);
+        self.pan.insert(receiver_id, pan);
+        if let Some(panner) = self.panners.get(&receiver_id) {
+            panner.pan().set_value(pan);
+        }
+    }
+
+    // Panner node for a receiver, created and connected to
+    // the gain node on first use
+    fn panner(&mut self, receiver_id: u32) -> Option<StereoPannerNode> {
+        if let Some(panner) = self.panners.get(&receiver_id) {
+            return Some(panner.clone());
+        }
+        match (&self.audio_ctx, &self.gain) {
             (Some(audio_ctx), Some(gain)) => {
-                if self.audio_pos == 0 {
-                    self.audio_start_time = audio_ctx.current_time();
+                match audio_ctx.create_stereo_panner() {
+                    Ok(panner) => {
+                        panner.pan().set_value(self.pan(receiver_id));
+                        if let Err(err) = panner.connect_with_audio_node(gain) {
+                            ConsoleService::error(&format!("unable to connect stereo panner: {:?}", err));
+                            return None;
+                        }
+                        self.panners.insert(receiver_id, panner.clone());
+                        Some(panner)
+                    },
+                    Err(err) => {
+                        ConsoleService::error(&format!("unable to create stereo panner: {:?}", err));
+                        None
+                    }
+                }
+            },
+            _ => None,
+        }
+    }
+
+    // Times queued audio was dropped in the last UNDERRUN_WINDOW_SECONDS
+    pub fn recent_overruns(&self) -> usize {
+        let now = self.audio_ctx.as_ref().map(|ctx| ctx.current_time()).unwrap_or(0.0);
+        self.overruns.iter().filter(|t| now - **t <= UNDERRUN_WINDOW_SECONDS).count()
+    }
+
+    pub fn set_target_latency(&mut self, seconds: f64) {
+        self.target_latency = seconds;
+    }
+
+    pub fn target_latency(&self) -> f64 {
+        self.target_latency
+    }
+
+    // Seconds of audio scheduled but not yet played, None when no
+    // audio is being received
+    pub fn buffered(&self) -> Option<f64> {
+        match (&self.audio_ctx, self.subscribed_audio) {
+            (Some(audio_ctx), Some(_)) if self.next_start_time > 0.0 => {
+                Some((self.next_start_time - audio_ctx.current_time()).max(0.0))
+            },
+            _ => None,
+        }
+    }
+
+    // Times playback ran dry in the last UNDERRUN_WINDOW_SECONDS
+    pub fn recent_underruns(&self) -> usize {
+        let now = self.audio_ctx.as_ref().map(|ctx| ctx.current_time()).unwrap_or(0.0);
+        self.underruns.iter().filter(|t| now - **t <= UNDERRUN_WINDOW_SECONDS).count()
+    }
+
+    // Playback has run dry often enough at the current target
+    // latency to suggest raising it
+    pub fn frequent_underruns(&self) -> bool {
+        self.recent_underruns() >= UNDERRUN_WARNING_COUNT
+    }
+
+    // Silence anything already scheduled and start scheduling
+    // from scratch on the next frame
+    fn reset_queue(&mut self) {
+        if let Some(queue) = self.queue.take() {
+            queue.disconnect().unwrap_or(());
+        }
+        self.next_start_time = 0.0;
+    }
+
+    // Node frames for `receiver_id` are scheduled through
+    fn queue(&mut self, receiver_id: u32) -> Option<GainNode> {
+        if let Some(queue) = &self.queue {
+            return Some(queue.clone());
+        }
+        match (self.audio_ctx(), self.panner(receiver_id)) {
+            (Some(audio_ctx), Some(panner)) => {
+                let queue = audio_ctx.create_gain()
+                    .and_then(|queue| queue.connect_with_audio_node(&panner).map(|_| queue));
+                match queue {
+                    Ok(queue) => {
+                        self.queue = Some(queue.clone());
+                        Some(queue)
+                    },
+                    Err(err) => {
+                        ConsoleService::error(&format!("unable to create audio queue: {:?}", err));
+                        None
+                    }
                 }
-                self.audio_pos += 1;
+            },
+            _ => None,
+        }
+    }
 
-                let audio_pos = self.audio_pos;
-                let start_time = self.audio_start_time;
+    pub fn import_audio_data(&mut self, receiver_id: u32, data: js_sys::ArrayBuffer) {
+        if let Some(audio_ctx) = self.audio_ctx() {
+            let now = audio_ctx.current_time();
+            let latency = self.next_start_time - now;
+            if self.next_start_time > 0.0 && latency > MAX_AUDIO_LATENCY_SECONDS {
+                self.overruns.retain(|t| now - t <= UNDERRUN_WINDOW_SECONDS);
+                self.overruns.push(now);
+                ConsoleService::log(&format!("audio {:.1}s behind real time, dropping queued audio", latency));
+                self.reset_queue();
+            }
+        }
 
+        match (self.audio_ctx(), self.queue(receiver_id)) {
+            (Some(audio_ctx), Some(gain)) => {
+                // first frame or we ran dry, schedule slightly ahead
+                let now = audio_ctx.current_time();
+                if self.next_start_time < now {
+                    if self.next_start_time > 0.0 {
+                        self.underruns.retain(|t| now - t <= UNDERRUN_WINDOW_SECONDS);
+                        self.underruns.push(now);
+                    }
+                    self.next_start_time = now + self.target_latency;
+                }
+                let play_time = self.next_start_time;
+                self.next_start_time += AUDIO_FRAME_SECONDS;
+
+                let promise =
+                    match audio_ctx.decode_audio_data(&data.slice(5)) {
+                        Ok(promise) => promise,
+                        Err(err) => {
+                            ConsoleService::error(&format!("unable to decode audio data: {:?}", err));
+                            return;
+                        }
+                    };
                 spawn_local(async move {
-                    let future = JsFuture::from(audio_ctx.decode_audio_data(&data.slice(5)).unwrap());
-                    match future.await {
+                    match JsFuture::from(promise).await {
                         Ok(value) => {
                             if let Ok(decoded) = value.dyn_into::<AudioBuffer>() {
-                                let source = audio_ctx.create_buffer_source().unwrap();
-                                source.set_buffer(Some(&decoded));
-                                source.connect_with_audio_node(&gain).unwrap();
-                                source.set_loop(false);
-                                let play_time = start_time as f64 + (audio_pos as f64 * 512.0 / 48000.0) + 0.1;
-                                source.start_with_when(play_time).unwrap();
+                                // buffer sources can only be started once, each
+                                // frame gets its own and is dropped when it ends
+                                let played = audio_ctx.create_buffer_source().and_then(|source| {
+                                    source.set_buffer(Some(&decoded));
+                                    source.connect_with_audio_node(&gain)?;
+                                    source.set_loop(false);
+                                    source.start_with_when(play_time)
+                                });
+                                if let Err(err) = played {
+                                    ConsoleService::error(&format!("unable to play audio data: {:?}", err));
+                                }
                             } else {
                                 ConsoleService::error("decoded audio not a valid audio buffer");
                             }
@@ -114,6 +431,153 @@ impl AudioProvider {
         }
     }
 
+    // Short beep played directly to the output so it is heard
+    // even when the receiver audio is muted
+    pub fn play_alert_tone(&self) {
+        if let Some(audio_ctx) = &self.audio_ctx {
+            match audio_ctx.create_oscillator() {
+                Ok(oscillator) => {
+                    let now = audio_ctx.current_time();
+                    oscillator.set_type(OscillatorType::Sine);
+                    oscillator.frequency().set_value(880.0);
+                    let played = oscillator.connect_with_audio_node(&audio_ctx.destination())
+                        .and_then(|_| oscillator.start_with_when(now))
+                        .and_then(|_| oscillator.stop_with_when(now + 0.3));
+                    if let Err(err) = played {
+                        ConsoleService::error(&format!("unable to play alert tone: {:?}", err));
+                    }
+                },
+                Err(err) => {
+                    ConsoleService::error(&format!("unable to create alert tone: {:?}", err));
+                }
+            }
+        }
+    }
+
+    pub fn output_devices(&self) -> &Vec<AudioOutputDevice> {
+        &self.output_devices
+    }
+
+    pub fn set_output_devices(&mut self, devices: Vec<AudioOutputDevice>) {
+        self.output_devices = devices;
+    }
+
+    // Request the list of audio outputs, result is delivered
+    // as Msg::AudioOutputDevices
+    pub fn enumerate_output_devices(&self, link: &ComponentLink<Model>) {
+        let promise =
+            match web_sys::window().map(|w| w.navigator().media_devices()) {
+                Some(Ok(media_devices)) => media_devices.enumerate_devices(),
+                _ => {
+                    ConsoleService::log("audio output selection not supported by this browser");
+                    return;
+                }
+            };
+
+        let callback = link.callback(Msg::AudioOutputDevices);
+        match promise {
+            Ok(promise) => {
+                spawn_local(async move {
+                    match JsFuture::from(promise).await {
+                        Ok(list) => {
+                            let devices = js_sys::Array::from(&list).iter()
+                                .filter_map(|d| d.dyn_into::<MediaDeviceInfo>().ok())
+                                .filter(|d| d.kind() == MediaDeviceKind::Audiooutput)
+                                .enumerate()
+                                .map(|(i, d)| {
+                                    let label = if d.label().is_empty() { format!("Output {}", i + 1) } else { d.label() };
+                                    AudioOutputDevice { id: d.device_id(), label }
+                                })
+                                .collect();
+                            callback.emit(devices);
+                        },
+                        Err(err) => {
+                            ConsoleService::error(&format!("unable to enumerate audio devices: {:?}", err));
+                        }
+                    }
+                });
+            },
+            Err(err) => {
+                ConsoleService::error(&format!("unable to enumerate audio devices: {:?}", err));
+            }
+        }
+    }
+
+    // Route the audio context to the given output device (empty string
+    // for the system default). AudioContext.setSinkId is not available
+    // in all browsers so it is looked up dynamically.
+    pub fn set_output_device(&self, device_id: &str) {
+        if let Some(audio_ctx) = &self.audio_ctx {
+            match js_sys::Reflect::get(audio_ctx, &JsValue::from_str("setSinkId")) {
+                Ok(set_sink_id) if set_sink_id.is_function() => {
+                    let set_sink_id: js_sys::Function = set_sink_id.unchecked_into();
+                    match set_sink_id.call1(audio_ctx, &JsValue::from_str(device_id)) {
+                        Ok(_) => ConsoleService::log(&format!("audio output device: {}", device_id)),
+                        Err(err) => ConsoleService::error(&format!("unable to set audio output device: {:?}", err)),
+                    }
+                },
+                _ => {
+                    ConsoleService::log("audio output selection not supported by this browser");
+                }
+            }
+        }
+    }
+
+    // Enable the squelch at `level` dBFS, or disable it with None
+    pub fn set_squelch(&mut self, link: &ComponentLink<Model>, level: Option<f32>) {
+        self.squelch_level = level;
+        match level {
+            Some(_) => {
+                if self.squelch_task.is_none() {
+                    let callback = link.callback(|_| Msg::UpdateSquelch);
+                    self.squelch_task = Some(IntervalService::spawn(Duration::from_millis(SQUELCH_INTERVAL_MS), callback));
+                }
+            },
+            None => {
+                self.squelch_task = None;
+                self.set_squelch_open(true);
+            }
+        }
+    }
+
+    // Current RMS level of the audio in dBFS
+    pub fn signal_level(&self) -> Option<f32> {
+        match &self.analyser {
+            Some(analyser) => {
+                let mut samples = vec![0.0; analyser.fft_size() as usize];
+                analyser.get_float_time_domain_data(&mut samples);
+                let sum: f32 = samples.iter().map(|s| s * s).sum();
+                let rms = (sum / samples.len() as f32).sqrt();
+                Some(20.0 * rms.max(1e-10).log10())
+            },
+            None => None,
+        }
+    }
+
+    pub fn update_squelch(&mut self) {
+        match (self.squelch_level, self.signal_level()) {
+            (Some(threshold), Some(level)) => {
+                if !self.squelch_open && level >= threshold {
+                    self.set_squelch_open(true);
+                } else if self.squelch_open && level < threshold - SQUELCH_HYSTERESIS_DB {
+                    self.set_squelch_open(false);
+                }
+            },
+            _ => ()
+        }
+    }
+
+    fn set_squelch_open(&mut self, open: bool) {
+        self.squelch_open = open;
+        if let Some(squelch) = &self.squelch {
+            squelch.gain().set_value(if open { 1.0 } else { 0.0 });
+        }
+    }
+
+    pub fn squelch_open(&self) -> bool {
+        self.squelch_open
+    }
+
     pub fn toggle_mute(&mut self) {
         if let Some(g) = &self.gain {
             let value = g.gain().value();
@@ -0,0 +1,236 @@
+// Band plan segments and license privileges (HF and 6m). The
+// tables are simplified summaries of the IARU region band plans,
+// intended as a guide rather than a legal reference.
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Region {
+    R1,
+    R2,
+    R3,
+}
+
+pub const REGIONS: [Region; 3] = [
+    Region::R1,
+    Region::R2,
+    Region::R3,
+];
+
+impl Region {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Region::R1 => "IARU Region 1",
+            Region::R2 => "IARU Region 2",
+            Region::R3 => "IARU Region 3",
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Region::R1 => "r1",
+            Region::R2 => "r2",
+            Region::R3 => "r3",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Region {
+        match code {
+            "r1" => Region::R1,
+            "r3" => Region::R3,
+            _ => Region::R2,
+        }
+    }
+
+    fn segments(&self) -> &'static [Segment] {
+        match self {
+            Region::R1 => REGION_1,
+            Region::R2 => REGION_2,
+            Region::R3 => REGION_3,
+        }
+    }
+}
+
+// Portion of a band set aside for a kind of operation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Segment {
+    pub start: f32,
+    pub end: f32,
+    pub kind: &'static str,
+}
+
+const fn seg(start: f32, end: f32, kind: &'static str) -> Segment {
+    Segment { start, end, kind }
+}
+
+// The tables stop at 6m, frequencies above this (e.g. through a
+// transverter) aren't checked against license privileges
+const PLAN_TOP: f32 = 54000000.0;
+
+// Segment of `region`'s band plan containing `frequency` (Hz)
+pub fn segment(region: Region, frequency: f32) -> Option<&'static Segment> {
+    region.segments().iter()
+        .chain(COMMON.iter())
+        .find(|s| frequency >= s.start && frequency < s.end)
+}
+
+// True if the tables cover `frequency`, above them nothing can be said
+// about segments or privileges
+pub fn in_plan(frequency: f32) -> bool {
+    frequency < PLAN_TOP
+}
+
+// True if `frequency` is inside any amateur band in the plan
+pub fn in_band(region: Region, frequency: f32) -> bool {
+    segment(region, frequency).is_some()
+}
+
+const REGION_1: &[Segment] = &[
+    seg(1810000.0, 1838000.0, "CW"),
+    seg(1838000.0, 1840000.0, "Digital"),
+    seg(1840000.0, 2000000.0, "Phone"),
+    seg(3500000.0, 3570000.0, "CW"),
+    seg(3570000.0, 3600000.0, "Digital"),
+    seg(3600000.0, 3800000.0, "Phone"),
+    seg(7000000.0, 7040000.0, "CW"),
+    seg(7040000.0, 7060000.0, "Digital"),
+    seg(7060000.0, 7200000.0, "Phone"),
+    seg(50000000.0, 50100000.0, "CW"),
+    seg(50100000.0, 50300000.0, "Phone"),
+    seg(50300000.0, 50500000.0, "Digital"),
+    seg(50500000.0, 52000000.0, "All Modes"),
+];
+
+const REGION_2: &[Segment] = &[
+    seg(1800000.0, 1840000.0, "CW"),
+    seg(1840000.0, 2000000.0, "Phone"),
+    seg(3500000.0, 3570000.0, "CW"),
+    seg(3570000.0, 3600000.0, "Digital"),
+    seg(3600000.0, 4000000.0, "Phone"),
+    seg(7000000.0, 7040000.0, "CW"),
+    seg(7040000.0, 7050000.0, "Digital"),
+    seg(7050000.0, 7300000.0, "Phone"),
+    seg(50000000.0, 50100000.0, "CW"),
+    seg(50100000.0, 50300000.0, "Phone"),
+    seg(50300000.0, 50500000.0, "Digital"),
+    seg(50500000.0, 54000000.0, "All Modes"),
+];
+
+const REGION_3: &[Segment] = &[
+    seg(1800000.0, 1830000.0, "CW"),
+    seg(1830000.0, 1840000.0, "Digital"),
+    seg(1840000.0, 2000000.0, "Phone"),
+    seg(3500000.0, 3535000.0, "CW"),
+    seg(3535000.0, 3600000.0, "Digital"),
+    seg(3600000.0, 3900000.0, "Phone"),
+    seg(7000000.0, 7025000.0, "CW"),
+    seg(7025000.0, 7060000.0, "Digital"),
+    seg(7060000.0, 7300000.0, "Phone"),
+    seg(50000000.0, 50100000.0, "CW"),
+    seg(50100000.0, 50300000.0, "Phone"),
+    seg(50300000.0, 50500000.0, "Digital"),
+    seg(50500000.0, 54000000.0, "All Modes"),
+];
+
+// 30m and up are the same in all regions
+const COMMON: &[Segment] = &[
+    seg(10100000.0, 10130000.0, "CW"),
+    seg(10130000.0, 10150000.0, "Digital"),
+    seg(14000000.0, 14070000.0, "CW"),
+    seg(14070000.0, 14099000.0, "Digital"),
+    seg(14099000.0, 14101000.0, "Beacon"),
+    seg(14101000.0, 14350000.0, "Phone"),
+    seg(18068000.0, 18095000.0, "CW"),
+    seg(18095000.0, 18109000.0, "Digital"),
+    seg(18109000.0, 18111000.0, "Beacon"),
+    seg(18111000.0, 18168000.0, "Phone"),
+    seg(21000000.0, 21070000.0, "CW"),
+    seg(21070000.0, 21149000.0, "Digital"),
+    seg(21149000.0, 21151000.0, "Beacon"),
+    seg(21151000.0, 21450000.0, "Phone"),
+    seg(24890000.0, 24915000.0, "CW"),
+    seg(24915000.0, 24929000.0, "Digital"),
+    seg(24929000.0, 24931000.0, "Beacon"),
+    seg(24931000.0, 24990000.0, "Phone"),
+    seg(28000000.0, 28070000.0, "CW"),
+    seg(28070000.0, 28190000.0, "Digital"),
+    seg(28190000.0, 28225000.0, "Beacon"),
+    seg(28225000.0, 29700000.0, "Phone"),
+];
+
+// Operator license class, used to warn when tuned outside of
+// transmit privileges. Privilege tables are for US licenses.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum LicenseClass {
+    NotSet,
+    Technician,
+    General,
+    Extra,
+}
+
+pub const LICENSE_CLASSES: [LicenseClass; 4] = [
+    LicenseClass::NotSet,
+    LicenseClass::Technician,
+    LicenseClass::General,
+    LicenseClass::Extra,
+];
+
+impl LicenseClass {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LicenseClass::NotSet => "none",
+            LicenseClass::Technician => "technician",
+            LicenseClass::General => "general",
+            LicenseClass::Extra => "extra",
+        }
+    }
+
+    pub fn from_name(name: &str) -> LicenseClass {
+        match name {
+            "technician" => LicenseClass::Technician,
+            "general" => LicenseClass::General,
+            "extra" => LicenseClass::Extra,
+            _ => LicenseClass::NotSet,
+        }
+    }
+
+    // Some(true/false) if transmitting on `frequency` is within the
+    // license privileges, None if no license class is set or the
+    // frequency is above the tables
+    pub fn permits(&self, region: Region, frequency: f32) -> Option<bool> {
+        if !in_plan(frequency) {
+            return None;
+        }
+        let privileges: &[(f32, f32)] =
+            match self {
+                LicenseClass::NotSet => return None,
+                LicenseClass::Extra => return Some(in_band(region, frequency)),
+                LicenseClass::Technician => TECHNICIAN,
+                LicenseClass::General => GENERAL,
+            };
+        Some(privileges.iter().any(|(start, end)| frequency >= *start && frequency < *end))
+    }
+}
+
+const TECHNICIAN: &[(f32, f32)] = &[
+    (3525000.0, 3600000.0),
+    (7025000.0, 7125000.0),
+    (21025000.0, 21200000.0),
+    (28000000.0, 28500000.0),
+    (50000000.0, 54000000.0),
+];
+
+const GENERAL: &[(f32, f32)] = &[
+    (1800000.0, 2000000.0),
+    (3525000.0, 3600000.0),
+    (3800000.0, 4000000.0),
+    (7025000.0, 7125000.0),
+    (7175000.0, 7300000.0),
+    (10100000.0, 10150000.0),
+    (14025000.0, 14150000.0),
+    (14225000.0, 14350000.0),
+    (18068000.0, 18168000.0),
+    (21025000.0, 21200000.0),
+    (21275000.0, 21450000.0),
+    (24890000.0, 24990000.0),
+    (28000000.0, 29700000.0),
+    (50000000.0, 54000000.0),
+];
@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+use yew::format::{Json};
+use yew::services::storage::{StorageService};
+use yew::services::{ConsoleService};
+
+const CALL_NOTES_KEY: &str = "radio.callNotes";
+
+// Short personal notes by callsign (e.g. "needs QSL"), shown on
+// every spot for the call
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CallNotes {
+    notes: HashMap<String, String>,
+}
+
+impl CallNotes {
+    pub fn restore(storage: &StorageService) -> CallNotes {
+        match storage.restore(CALL_NOTES_KEY) {
+            Json(Ok(notes)) => notes,
+            Json(Err(_)) => {
+                ConsoleService::log("no callsign notes found");
+                CallNotes::default()
+            }
+        }
+    }
+
+    pub fn store(&self, storage: &mut StorageService) {
+        storage.store(CALL_NOTES_KEY, Json(self));
+    }
+
+    pub fn get(&self, call: &str) -> Option<&String> {
+        self.notes.get(&call.to_uppercase())
+    }
+
+    // Notes from `other` for calls without a note here
+    pub fn merge(&mut self, other: CallNotes) {
+        for (call, note) in other.notes {
+            self.notes.entry(call).or_insert(note);
+        }
+    }
+
+    // Empty `note` removes the note
+    pub fn set(&mut self, call: &str, note: &str) {
+        let (call, note) = (call.trim().to_uppercase(), note.trim());
+        if note.is_empty() {
+            self.notes.remove(&call);
+        } else {
+            self.notes.insert(call, note.to_string());
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::VecDeque;
+use sparkplug::{Command,CommandResponse};
+
+// Number of round trips kept for the debug panel
+const COMMAND_LOG_LIMIT: usize = 50;
+// Commands unanswered for longer than this aren't waited on
+const RESPONSE_TIMEOUT_MS: f64 = 10000.0;
+
+// Response a command is expected to produce
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Expected {
+    Receivers,
+    Radios,
+    Version,
+    Receiver(u32),
+    // Subscriptions and power changes have no direct response
+    Nothing,
+}
+
+impl Expected {
+    fn from_command(cmd: &Command) -> Expected {
+        match cmd {
+            Command::GetReceivers => Expected::Receivers,
+            Command::GetRadios => Expected::Radios,
+            Command::GetVersion => Expected::Version,
+            Command::SetFrequency { id, .. } => Expected::Receiver(*id),
+            Command::SetMode { id, .. } => Expected::Receiver(*id),
+            _ => Expected::Nothing,
+        }
+    }
+
+    fn from_response(response: &CommandResponse) -> Option<Expected> {
+        match response {
+            CommandResponse::Receivers { .. } => Some(Expected::Receivers),
+            CommandResponse::Radios { .. } => Some(Expected::Radios),
+            CommandResponse::Version(_) => Some(Expected::Version),
+            CommandResponse::ReceiverResponse { id, .. } => Some(Expected::Receiver(*id)),
+            CommandResponse::Spots { .. } => None,
+        }
+    }
+}
+
+// A sent command paired with the response it produced
+pub struct RoundTrip {
+    pub command: String,
+    expected: Expected,
+    sent_at: f64,
+    pub response: Option<String>,
+    // Milliseconds between sending and the matching response
+    pub elapsed: Option<f64>,
+}
+
+impl RoundTrip {
+    pub fn expects_response(&self) -> bool {
+        self.expected != Expected::Nothing
+    }
+}
+
+// Pairs commands sent to SparkSDR with their responses, by
+// response type and receiver id
+pub struct CommandLog {
+    entries: VecDeque<RoundTrip>,
+}
+
+impl CommandLog {
+    pub fn new() -> CommandLog {
+        CommandLog { entries: VecDeque::new() }
+    }
+
+    pub fn entries(&self) -> &VecDeque<RoundTrip> {
+        &self.entries
+    }
+
+    pub fn sent(&mut self, cmd: &Command, json: &str) {
+        self.entries.push_back(RoundTrip {
+            command: json.to_string(),
+            expected: Expected::from_command(cmd),
+            sent_at: js_sys::Date::now(),
+            response: None,
+            elapsed: None,
+        });
+        while self.entries.len() > COMMAND_LOG_LIMIT {
+            self.entries.pop_front();
+        }
+    }
+
+    // Match `response` to the oldest command still waiting on it
+    pub fn received(&mut self, response: &CommandResponse) {
+        let expected =
+            match Expected::from_response(response) {
+                Some(expected) => expected,
+                None => return,
+            };
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.expected == expected && e.response.is_none()) {
+            entry.response = Some(describe(response));
+            entry.elapsed = Some(js_sys::Date::now() - entry.sent_at);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    // True while a getReceivers/getRadios/getVersion sent in the last
+    // RESPONSE_TIMEOUT_MS is still unanswered
+    pub fn is_syncing(&self) -> bool {
+        let now = js_sys::Date::now();
+        self.entries.iter().any(|e| {
+            e.response.is_none()
+                && now - e.sent_at < RESPONSE_TIMEOUT_MS
+                && [Expected::Receivers, Expected::Radios, Expected::Version].contains(&e.expected)
+        })
+    }
+}
+
+fn describe(response: &CommandResponse) -> String {
+    match response {
+        CommandResponse::Receivers { receivers } => format!("getReceiversResponse ({} receivers)", receivers.len()),
+        CommandResponse::Radios { radios } => format!("getRadiosResponse ({} radios)", radios.len()),
+        CommandResponse::Version(version) => format!("getVersionResponse ({} {})", version.host, version.host_version),
+        CommandResponse::ReceiverResponse { id, frequency, mode, .. } => format!("ReceiverResponse (rx {}: {} {})", id, frequency, mode.mode()),
+        CommandResponse::Spots { spots } => format!("spotResponse ({} spots)", spots.len()),
+    }
+}
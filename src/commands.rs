@@ -0,0 +1,95 @@
+// Builders for the commands sent to SparkSDR. Each checks its
+// arguments against what the app knows (receivers, radios, modes)
+// so a malformed command is caught here instead of on the wire.
+use std::fmt;
+
+use ham_rs::Mode;
+use sparkplug::{Command,Receiver,Radio,RECEIVER_MODES};
+
+// Frequencies the 9 digit readout can show (Hz)
+pub const MIN_FREQUENCY: f32 = 1.0;
+pub const MAX_FREQUENCY: f32 = 999_999_999.0;
+
+#[derive(Debug)]
+pub enum CommandError {
+    Frequency(f32),
+    Mode(Mode),
+    Receiver(u32),
+    Radio(u32),
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CommandError::Frequency(frequency) => write!(f, "frequency {} Hz is out of range", frequency),
+            CommandError::Mode(mode) => write!(f, "unknown mode {}", mode.mode()),
+            CommandError::Receiver(id) => write!(f, "no receiver {}", id),
+            CommandError::Radio(id) => write!(f, "no radio {}", id),
+        }
+    }
+}
+
+fn check_receiver(receivers: &[Receiver], id: u32) -> Result<(), CommandError> {
+    match receivers.iter().any(|r| r.id == id) {
+        true => Ok(()),
+        false => Err(CommandError::Receiver(id)),
+    }
+}
+
+fn check_radio(radios: &[Radio], id: u32) -> Result<(), CommandError> {
+    match radios.iter().any(|r| r.id == id) {
+        true => Ok(()),
+        false => Err(CommandError::Radio(id)),
+    }
+}
+
+pub fn check_frequency(frequency: f32) -> Result<(), CommandError> {
+    match (MIN_FREQUENCY..=MAX_FREQUENCY).contains(&frequency) {
+        true => Ok(()),
+        false => Err(CommandError::Frequency(frequency)),
+    }
+}
+
+pub fn set_frequency(receivers: &[Receiver], id: u32, frequency: f32) -> Result<Command, CommandError> {
+    check_receiver(receivers, id)?;
+    check_frequency(frequency)?;
+    Ok(Command::SetFrequency { frequency: (frequency as i32).to_string(), id })
+}
+
+pub fn set_mode(receivers: &[Receiver], id: u32, mode: Mode) -> Result<Command, CommandError> {
+    check_receiver(receivers, id)?;
+    if !RECEIVER_MODES.iter().any(|m| *m == mode) {
+        return Err(CommandError::Mode(mode));
+    }
+    Ok(Command::SetMode { mode, id })
+}
+
+// Unsubscribing is always allowed, the receiver may already be gone
+pub fn subscribe_to_audio(receivers: &[Receiver], rx_id: u32, enable: bool) -> Result<Command, CommandError> {
+    if enable {
+        check_receiver(receivers, rx_id)?;
+    }
+    Ok(Command::SubscribeToAudio { rx_id, enable })
+}
+
+pub fn subscribe_to_spectrum(receivers: &[Receiver], rx_id: u32, enable: bool) -> Result<Command, CommandError> {
+    if enable {
+        check_receiver(receivers, rx_id)?;
+    }
+    Ok(Command::SubscribeToSpectrum { rx_id, enable })
+}
+
+pub fn add_receiver(radios: &[Radio], id: u32) -> Result<Command, CommandError> {
+    check_radio(radios, id)?;
+    Ok(Command::AddReceiver { id })
+}
+
+pub fn remove_receiver(receivers: &[Receiver], id: u32) -> Result<Command, CommandError> {
+    check_receiver(receivers, id)?;
+    Ok(Command::RemoveReceiver { id })
+}
+
+pub fn set_running(radios: &[Radio], id: u32, running: bool) -> Result<Command, CommandError> {
+    check_radio(radios, id)?;
+    Ok(Command::SetRunning { id, running })
+}
@@ -0,0 +1,50 @@
+use ham_rs::{Band,Mode};
+
+// Standard dial frequencies (Hz) for the digital modes
+const DIAL_FREQUENCIES: &[(&str, f32)] = &[
+    ("FT8", 1840000.0),
+    ("FT8", 3573000.0),
+    ("FT8", 5357000.0),
+    ("FT8", 7074000.0),
+    ("FT8", 10136000.0),
+    ("FT8", 14074000.0),
+    ("FT8", 18100000.0),
+    ("FT8", 21074000.0),
+    ("FT8", 24915000.0),
+    ("FT8", 28074000.0),
+    ("FT8", 50313000.0),
+    ("FT4", 3575000.0),
+    ("FT4", 7047500.0),
+    ("FT4", 10140000.0),
+    ("FT4", 14080000.0),
+    ("FT4", 18104000.0),
+    ("FT4", 21140000.0),
+    ("FT4", 24919000.0),
+    ("FT4", 28180000.0),
+    ("FT4", 50318000.0),
+];
+
+// Dial frequency to start from on `band` (e.g. "20m"), the one for
+// `mode` if there is one, else the band's first
+pub fn band_dial(band: &str, mode: &Mode) -> Option<f32> {
+    let on_band: Vec<&(&str, f32)> = DIAL_FREQUENCIES.iter()
+        .filter(|(_, dial)| Band::new(*dial as i32).band().map(|b| b.to_string()).as_deref() == Some(band))
+        .collect();
+    on_band.iter()
+        .find(|(dial_mode, _)| mode.mode().to_string().eq_ignore_ascii_case(dial_mode))
+        .or_else(|| on_band.first())
+        .map(|(_, dial)| *dial)
+}
+
+// Dial frequency for `mode` on the band containing `frequency`
+pub fn dial_frequency(frequency: f32, mode: &Mode) -> Option<f32> {
+    let band = Band::new(frequency as i32).band().map(|b| b.to_string());
+    if band.is_none() {
+        return None;
+    }
+    DIAL_FREQUENCIES.iter()
+        .find(|(dial_mode, dial)| {
+            mode.mode().to_string().eq_ignore_ascii_case(dial_mode) && Band::new(*dial as i32).band().map(|b| b.to_string()) == band
+        })
+        .map(|(_, dial)| *dial)
+}
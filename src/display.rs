@@ -0,0 +1,117 @@
+// Helpers for formatting values shown in the UI
+
+// Digits of the receiver frequency readout, always 9 digits (Hz)
+// grouped by three with `separator`. Each digit is paired with
+// whether it is significant, leading zeros are shown dimmed. The
+// last digit is always significant so 0 Hz still reads as "0".
+pub fn frequency_digits(frequency: f32, separator: char) -> Vec<(char, bool)> {
+    let hz = (frequency.round() as i64).max(0).min(999_999_999);
+    let mut digits = Vec::new();
+    let mut active = false;
+    for (i, c) in format!("{:09}", hz).chars().enumerate() {
+        if i > 0 && (9 - i) % 3 == 0 {
+            digits.push((separator, active));
+        }
+        active = active || c != '0' || i == 8;
+        digits.push((c, active));
+    }
+    digits
+}
+
+// Signal report as a signed whole number of dB, "—" if the value
+// isn't a number
+pub fn format_snr(snr: f64) -> String {
+    match snr.is_finite() {
+        true => format!("{:+}", snr.round() as i64),
+        false => "—".to_string(),
+    }
+}
+
+// Time offset in seconds to one decimal place, "—" if the value
+// isn't a number
+pub fn format_dt(dt: f64) -> String {
+    match dt.is_finite() {
+        true => format!("{:.1}", dt),
+        false => "—".to_string(),
+    }
+}
+
+// Distance buckets, the upper bound (km) of each is set by
+// `thresholds` and anything beyond the last is DX
+pub const DISTANCE_BUCKETS: [&str; 4] = ["local", "regional", "continental", "dx"];
+
+// Sync quality buckets for a decode's time offset, the upper bound
+// (seconds) of each is set by `thresholds` and anything beyond the
+// last is poor (likely a drifting clock)
+pub const DT_BUCKETS: [&str; 3] = ["tight", "loose", "poor"];
+
+// CSS class for how far a decode's time offset is from zero, none
+// if the offset isn't a number
+pub fn dt_class(dt: f64, thresholds: &[f64; 2]) -> &'static str {
+    if !dt.is_finite() {
+        return "";
+    }
+    let bucket = thresholds.iter().position(|limit| dt.abs() <= *limit).unwrap_or(2);
+    match bucket {
+        0 => "dt-tight",
+        1 => "dt-loose",
+        _ => "dt-poor",
+    }
+}
+
+// CSS class for how far away a spot is
+pub fn distance_class(km: f64, thresholds: &[f64; 3]) -> &'static str {
+    let bucket = thresholds.iter().position(|limit| km < *limit).unwrap_or(3);
+    match bucket {
+        0 => "distance-local",
+        1 => "distance-regional",
+        2 => "distance-continental",
+        _ => "distance-dx",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // (digits as shown, active flags as 1/0)
+    fn readout(frequency: f32) -> (String, String) {
+        let digits = frequency_digits(frequency, ',');
+        (
+            digits.iter().map(|(c, _)| *c).collect(),
+            digits.iter().map(|(_, active)| if *active { '1' } else { '0' }).collect(),
+        )
+    }
+
+    #[test]
+    fn zero_keeps_last_digit_active() {
+        assert_eq!(readout(0.0), ("000,000,000".to_string(), "00000000001".to_string()));
+    }
+
+    #[test]
+    fn hundred_megahertz_is_all_active() {
+        assert_eq!(readout(100_000_000.0), ("100,000,000".to_string(), "11111111111".to_string()));
+    }
+
+    #[test]
+    fn leading_zeros_are_dimmed() {
+        assert_eq!(readout(7_074_000.0), ("007,074,000".to_string(), "00111111111".to_string()));
+    }
+
+    #[test]
+    fn fractional_hz_is_rounded() {
+        assert_eq!(readout(14_074.6), ("000,014,075".to_string(), "00000111111".to_string()));
+    }
+
+    #[test]
+    fn out_of_range_is_clamped() {
+        assert_eq!(readout(-5.0), ("000,000,000".to_string(), "00000000001".to_string()));
+        assert_eq!(readout(1_500_000_000.0), ("999,999,999".to_string(), "11111111111".to_string()));
+    }
+
+    #[test]
+    fn separator_is_used_for_grouping() {
+        let digits: String = frequency_digits(7_074_000.0, '.').iter().map(|(c, _)| *c).collect();
+        assert_eq!(digits, "007.074.000");
+    }
+}
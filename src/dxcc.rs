@@ -0,0 +1,50 @@
+// DXCC entity numbers by the country code used for flags (ISO 3166).
+// Countries split into more than one entity (e.g. European and
+// Asiatic Russia) use the entity most stations are in.
+const DXCC_ENTITIES: &[(&str, u16)] = &[
+    ("al", 7), ("ar", 100), ("at", 206), ("au", 150), ("ba", 501),
+    ("be", 209), ("bg", 212), ("bo", 104), ("br", 108), ("by", 27),
+    ("ca", 1), ("ch", 287), ("cl", 112), ("cn", 318), ("co", 116),
+    ("cr", 308), ("cu", 70), ("cy", 215), ("cz", 503), ("de", 230),
+    ("dk", 221), ("do", 72), ("ec", 120), ("ee", 52), ("eg", 478),
+    ("es", 281), ("fi", 224), ("fr", 227), ("gb-eng", 223), ("gb-nir", 265),
+    ("gb-sct", 279), ("gb-wls", 294), ("gr", 236), ("gt", 76), ("hk", 321),
+    ("hn", 80), ("hr", 497), ("hu", 239), ("id", 327), ("ie", 245),
+    ("il", 336), ("in", 324), ("is", 242), ("it", 248), ("jm", 82),
+    ("jp", 339), ("ke", 430), ("kr", 137), ("kz", 130), ("lt", 146),
+    ("lu", 254), ("lv", 145), ("ma", 446), ("md", 179), ("me", 514),
+    ("mk", 502), ("mt", 257), ("mx", 50), ("ng", 450), ("ni", 86),
+    ("nl", 263), ("no", 266), ("nz", 170), ("pa", 88), ("pe", 136),
+    ("ph", 375), ("pl", 269), ("pr", 202), ("pt", 272), ("py", 132),
+    ("ro", 275), ("rs", 296), ("ru", 54), ("sa", 378), ("se", 284),
+    ("sg", 381), ("si", 499), ("sk", 504), ("sv", 74), ("th", 387),
+    ("tr", 390), ("tw", 386), ("ua", 288), ("us", 291), ("uy", 144),
+    ("ve", 148), ("vn", 293), ("za", 462),
+];
+
+// Entity number for a country code, if known
+pub fn dxcc_entity(code: &str) -> Option<u16> {
+    DXCC_ENTITIES.iter()
+        .find(|(c, _)| c.eq_ignore_ascii_case(code))
+        .map(|(_, entity)| *entity)
+}
+
+// Callsign prefix through the first run of digits (KD9QZO => KD9).
+// Portable calls use the shorter part, ignoring /P, /M and the like.
+pub fn call_prefix(call: &str) -> String {
+    let base = call.split('/')
+        .filter(|part| !part.is_empty() && !["P", "M", "MM", "AM", "QRP"].contains(part))
+        .min_by_key(|part| part.len())
+        .unwrap_or(call);
+    let mut prefix = String::new();
+    let mut seen_digit = false;
+    for c in base.chars() {
+        if c.is_ascii_digit() {
+            seen_digit = true;
+        } else if seen_digit && prefix.len() > 1 {
+            break;
+        }
+        prefix.push(c);
+    }
+    prefix
+}
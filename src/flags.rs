@@ -0,0 +1,92 @@
+// Country flags for spot rows. The flag-icon stylesheet and SVGs are
+// bundled under static/, so they work offline; emoji flags are an
+// alternative for browsers where the stylesheet is blocked.
+use yew::{html, Html};
+
+use crate::settings::FlagSource;
+
+// Bundled flags that aren't plain ISO 3166 codes
+const SUBDIVISION_FLAGS: &[&str] = &["es-ca", "es-ga", "gb-eng", "gb-nir", "gb-sct", "gb-wls"];
+
+// Flag codes for DXCC entities (by cty.dat name) that aren't ISO
+// countries, where `country.code()` is wrong or missing. An empty code
+// shows the placeholder (disputed or flagless entities).
+const ENTITY_FLAGS: &[(&str, &str)] = &[
+    ("African Italy", "it"), ("Agalega & St. Brandon", "mu"), ("Aland Islands", "ax"),
+    ("Alaska", "us"), ("Amsterdam & St. Paul Is.", "tf"), ("Andaman & Nicobar Is.", "in"),
+    ("Annobon Island", "gq"), ("Antarctica", "aq"), ("Ascension Island", "sh"),
+    ("Asiatic Russia", "ru"), ("Auckland & Campbell", "nz"), ("Aves Island", "ve"),
+    ("Azores", "pt"), ("Baker & Howland Islands", "um"), ("Balearic Islands", "es"),
+    ("Banaba Island", "ki"), ("Bouvet", "bv"), ("Canary Islands", "es"),
+    ("Central Kiribati", "ki"), ("Ceuta & Melilla", "es"), ("Chatham Islands", "nz"),
+    ("Chesterfield Islands", "nc"), ("Clipperton Island", "fr"), ("Cocos Island", "cr"),
+    ("Conway Reef", "fj"), ("Corsica", "fr"), ("Crete", "gr"),
+    ("Crozet Island", "tf"), ("Desecheo Island", "pr"), ("Dodecanese", "gr"),
+    ("Ducie Island", "pn"), ("Easter Island", "cl"), ("Eastern Kiribati", "ki"),
+    ("European Russia", "ru"), ("Fernando de Noronha", "br"), ("Galapagos Islands", "ec"),
+    ("Glorioso Islands", "tf"), ("Guantanamo Bay", "us"), ("Hawaii", "us"),
+    ("Heard Island", "hm"), ("ITU HQ", "un"), ("Jan Mayen", "sj"),
+    ("Johnston Island", "um"), ("Juan de Nova, Europa", "tf"), ("Juan Fernandez Islands", "cl"),
+    ("Kaliningrad", "ru"), ("Kerguelen Islands", "tf"), ("Kermadec Islands", "nz"),
+    ("Kure Island", "us"), ("Lakshadweep Islands", "in"), ("Lord Howe Island", "au"),
+    ("Macquarie Island", "au"), ("Madeira Islands", "pt"), ("Malpelo Island", "co"),
+    ("Market Reef", "ax"), ("Mellish Reef", "au"), ("Midway Island", "um"),
+    ("Minami Torishima", "jp"), ("Mount Athos", "gr"), ("Navassa Island", "um"),
+    ("Ogasawara", "jp"), ("Palmyra & Jarvis Islands", "um"), ("Peter 1 Island", "aq"),
+    ("Pitcairn Island", "pn"), ("Prince Edward & Marion", "za"), ("Revillagigedo", "mx"),
+    ("Rodriguez Island", "mu"), ("Rotuma Island", "fj"), ("Sable Island", "ca"),
+    ("San Andres & Providencia", "co"), ("San Felix & San Ambrosio", "cl"), ("Sardinia", "it"),
+    ("Scarborough Reef", ""), ("Shetland Islands", "gb-sct"), ("Sicily", "it"),
+    ("South Georgia Island", "gs"), ("South Orkney Islands", "aq"), ("South Sandwich Islands", "gs"),
+    ("South Shetland Islands", "aq"), ("Sov Mil Order of Malta", ""), ("Spratly Islands", ""),
+    ("St. Paul Island", "ca"), ("St. Peter & St. Paul", "br"), ("Svalbard", "sj"),
+    ("Swains Island", "as"), ("Temotu Province", "sb"), ("Trindade & Martim Vaz", "br"),
+    ("Tristan da Cunha & Gough", "sh"), ("Tromelin Island", "tf"), ("United Nations HQ", "un"),
+    ("Wake Island", "um"), ("Western Kiribati", "ki"), ("Willis Island", "au"),
+];
+
+// Flag code for a DXCC entity, `code` unless it's overridden above
+pub fn flag_code(name: &str, code: &str) -> String {
+    ENTITY_FLAGS.iter()
+        .find(|(entity, _)| entity.eq_ignore_ascii_case(name))
+        .map(|(_, flag)| flag.to_string())
+        .unwrap_or_else(|| code.to_lowercase())
+}
+
+// Whether there's a bundled flag for `code`
+fn has_flag(code: &str) -> bool {
+    (code.len() == 2 && code.chars().all(|c| c.is_ascii_lowercase())) || SUBDIVISION_FLAGS.contains(&code)
+}
+
+// Emoji flag: regional indicators for ISO codes, a tag sequence for
+// the UK home nations (gb-eng => 🏴 + "gbeng" tags)
+fn emoji(code: &str) -> String {
+    if code.starts_with("gb-") {
+        let tags: String = code.chars()
+            .filter(|c| *c != '-')
+            .filter_map(|c| std::char::from_u32(0xE0000 + c as u32))
+            .collect();
+        return format!("\u{1F3F4}{}\u{E007F}", tags);
+    }
+    // other subdivisions use the country flag
+    code.chars()
+        .take_while(|c| *c != '-')
+        .filter_map(|c| std::char::from_u32(0x1F1E6 + (c as u32 - 'a' as u32)))
+        .collect()
+}
+
+// Flag for a flag code, a grey placeholder for entities without one
+pub fn flag(source: FlagSource, code: &str) -> Html {
+    let code = code.to_lowercase();
+    if !has_flag(&code) {
+        return match source {
+            FlagSource::Off => html! {},
+            _ => html! { <span class="flag-icon flag-placeholder"></span> },
+        };
+    }
+    match source {
+        FlagSource::Icons => html! { <i class=format!("flag-icon flag-icon-{}", code)></i> },
+        FlagSource::Emoji => html! { <span class="flag-emoji">{ emoji(&code) }</span> },
+        FlagSource::Off => html! {},
+    }
+}
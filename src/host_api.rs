@@ -0,0 +1,141 @@
+// JavaScript API for pages embedding the app. Host pages subscribe
+// to events with `onEvent(name, callback)` and drive receivers with
+// `tune` and `setMode`:
+//
+//   onEvent("spot", spot => console.log(spot.call));
+//   tune(1, 14074000);
+//
+// Events: "spot" (SpotEvent), "frequency" (FrequencyEvent) and
+// "connection" (ConnectionEvent), each passed as a plain object.
+use wasm_bindgen::prelude::*;
+use yew::Callback;
+use yew::services::{ConsoleService};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use chrono::{DateTime,Utc};
+use serde::Serialize;
+
+use ham_rs::Mode;
+use sparkplug::{Spot,Receiver};
+
+use crate::model::{Msg};
+
+thread_local! {
+    // Listeners by event name
+    static LISTENERS: RefCell<HashMap<String, Vec<js_sys::Function>>> = RefCell::new(HashMap::new());
+    // Sends messages from the host page to the app
+    static APP: RefCell<Option<Callback<Msg>>> = RefCell::new(None);
+}
+
+#[derive(Serialize)]
+pub struct SpotEvent {
+    pub call: String,
+    pub time: DateTime<Utc>,
+    pub frequency: f32,
+    pub tuned_frequency: f32,
+    pub mode: String,
+    pub snr: f32,
+    pub msg: Option<String>,
+}
+
+impl SpotEvent {
+    pub fn new(spot: &Spot) -> SpotEvent {
+        SpotEvent {
+            call: spot.call.call(),
+            time: spot.time,
+            frequency: spot.frequency,
+            tuned_frequency: spot.tuned_frequency,
+            mode: spot.mode.mode().to_string(),
+            snr: spot.snr as f32,
+            msg: spot.msg.clone(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct FrequencyEvent {
+    pub receiver_id: u32,
+    pub frequency: f32,
+    pub mode: String,
+}
+
+impl FrequencyEvent {
+    pub fn new(receiver: &Receiver) -> FrequencyEvent {
+        FrequencyEvent {
+            receiver_id: receiver.id,
+            frequency: receiver.frequency,
+            mode: receiver.mode.mode().to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct ConnectionEvent {
+    pub connected: bool,
+}
+
+// Called once when the app starts so host commands reach it
+pub fn attach(callback: Callback<Msg>) {
+    APP.with(|app| *app.borrow_mut() = Some(callback));
+}
+
+// Pass `payload` to every listener for `event`
+pub fn emit<T: Serialize>(event: &str, payload: &T) {
+    // copied out so listeners can call onEvent/offEvent while they run
+    let callbacks: Vec<js_sys::Function> =
+        LISTENERS.with(|listeners| listeners.borrow().get(event).cloned().unwrap_or_default());
+    if callbacks.is_empty() {
+        return;
+    }
+    let payload =
+        match serde_json::to_string(payload).map(|json| js_sys::JSON::parse(&json)) {
+            Ok(Ok(payload)) => payload,
+            _ => {
+                ConsoleService::error(&format!("unable to encode {} event", event));
+                return;
+            }
+        };
+    for callback in callbacks {
+        if let Err(err) = callback.call1(&JsValue::NULL, &payload) {
+            ConsoleService::error(&format!("{} event listener failed: {:?}", event, err));
+        }
+    }
+}
+
+fn send(msg: Msg) {
+    APP.with(|app| {
+        match &*app.borrow() {
+            Some(callback) => callback.emit(msg),
+            None => ConsoleService::error("host api used before the app started"),
+        }
+    });
+}
+
+#[wasm_bindgen(js_name = onEvent)]
+pub fn on_event(event: String, callback: js_sys::Function) {
+    LISTENERS.with(|listeners| listeners.borrow_mut().entry(event).or_insert_with(Vec::new).push(callback));
+}
+
+#[wasm_bindgen(js_name = offEvent)]
+pub fn off_event(event: String, callback: js_sys::Function) {
+    LISTENERS.with(|listeners| {
+        if let Some(callbacks) = listeners.borrow_mut().get_mut(&event) {
+            let callback: &JsValue = &callback;
+            callbacks.retain(|c| {
+                let c: &JsValue = c;
+                c != callback
+            });
+        }
+    });
+}
+
+// Tune a receiver, keeping its mode unless `mode` is given
+#[wasm_bindgen]
+pub fn tune(receiver_id: u32, frequency: f32, mode: Option<String>) {
+    send(Msg::TuneReceiver(receiver_id, frequency, mode.map(Mode::new)));
+}
+
+#[wasm_bindgen(js_name = setMode)]
+pub fn set_mode(receiver_id: u32, mode: String) {
+    send(Msg::ModeChanged(receiver_id, Mode::new(mode)));
+}
@@ -0,0 +1,535 @@
+use std::collections::HashMap;
+
+// Languages available for the UI. English is the fallback for any
+// key missing from another language's table.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Locale {
+    English,
+    Spanish,
+}
+
+pub const LOCALES: [Locale; 2] = [
+    Locale::English,
+    Locale::Spanish,
+];
+
+impl Locale {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::Spanish => "es",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Locale::English => "English",
+            Locale::Spanish => "Español",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Locale {
+        match code {
+            "es" => Locale::Spanish,
+            _ => Locale::English,
+        }
+    }
+
+    fn strings(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::English => ENGLISH,
+            Locale::Spanish => SPANISH,
+        }
+    }
+
+    // Country names by flag code, ham_rs only has English names
+    fn country_names(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::English => &[],
+            Locale::Spanish => SPANISH_COUNTRIES,
+        }
+    }
+}
+
+// String lookup for the currently selected locale
+pub struct I18n {
+    locale: Locale,
+    strings: HashMap<&'static str, &'static str>,
+    fallback: HashMap<&'static str, &'static str>,
+    countries: HashMap<&'static str, &'static str>,
+}
+
+impl I18n {
+    pub fn new(locale: Locale) -> I18n {
+        I18n {
+            locale,
+            strings: locale.strings().iter().cloned().collect(),
+            fallback: ENGLISH.iter().cloned().collect(),
+            countries: locale.country_names().iter().cloned().collect(),
+        }
+    }
+
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    // Translate `key`, falling back to English and finally the key itself
+    pub fn t(&self, key: &str) -> String {
+        match self.strings.get(key).or(self.fallback.get(key)) {
+            Some(value) => value.to_string(),
+            None => key.to_string(),
+        }
+    }
+
+    // Country name for a flag code, `english` if there's no
+    // translation
+    pub fn country(&self, code: &str, english: &str) -> String {
+        match self.countries.get(code) {
+            Some(name) => name.to_string(),
+            None => english.to_string(),
+        }
+    }
+}
+
+const ENGLISH: &[(&str, &str)] = &[
+    ("receivers", "Receivers"),
+    ("spots", "Spots"),
+    ("map", "Map"),
+    ("settings", "Settings"),
+    ("power", "Power"),
+    ("add_receiver", "Add Receiver"),
+    ("spot_filters", "Spot Filters"),
+    ("cq_only", "CQ Only"),
+    ("current_rx", "Current Rx"),
+    ("lotw", "LoTW"),
+    ("new_state", "New State"),
+    ("new_country", "New Country"),
+    ("log_file", "Log File"),
+    ("import_help", "Compare spots to log file to find new countries and states."),
+    ("adif_only", "(adif only)"),
+    ("loaded_contacts", "Loaded {} contacts"),
+    ("cancel_import", "Cancel Import"),
+    ("col_utc", "UTC"),
+    ("col_call", "Call"),
+    ("col_db", "dB"),
+    ("col_dt", "DT"),
+    ("col_freq", "Freq"),
+    ("col_mode", "Mode"),
+    ("col_dist", "Dist"),
+    ("col_message", "Message"),
+    ("yes", "Yes"),
+    ("no", "No"),
+    ("display", "Display"),
+    ("frequency_format", "Frequency Format"),
+    ("language", "Language"),
+    ("disconnected", "Disconnected"),
+    ("disconnected_help", "Make sure SparkSDR has Web Sockets enabled, and hostname is correct"),
+    ("connect", "Connect"),
+    ("col_local", "Local"),
+    ("spot_times", "Spot Times"),
+    ("utc", "UTC"),
+    ("local_time", "Local Time"),
+    ("highlight_words", "Highlight Words"),
+    ("station", "Station"),
+    ("my_call", "My Callsign"),
+    ("alert_on_call", "Alert When Called"),
+    ("audio", "Audio"),
+    ("output_device", "Output Device"),
+    ("default_device", "Default"),
+    ("enable_audio", "Audio is paused by the browser. Click here to enable audio."),
+    ("squelch", "Squelch"),
+    ("squelch_level", "Squelch Level"),
+    ("spot_click_action", "Spot Click"),
+    ("action_tune", "Tune"),
+    ("action_copy", "Copy Callsign"),
+    ("action_lookup", "Look Up Callsign"),
+    ("action_none", "Do Nothing"),
+    ("lookup_provider", "Callsign Lookup"),
+    ("add_lookup_provider", "Add Lookup Site"),
+    ("lookup_name", "Name"),
+    ("lookup_url_help", "https://example.com/{call}"),
+    ("add", "Add"),
+    ("log_qso", "Log QSO"),
+    ("call", "Callsign"),
+    ("rst_sent", "Sent"),
+    ("rst_rcvd", "Received"),
+    ("cancel", "Cancel"),
+    ("logged_contacts", "Logged {} contacts"),
+    ("export_log", "Export ADIF"),
+    ("dupe_help", "already worked on this band and mode"),
+    ("band_modes", "Band Modes"),
+    ("none", "None"),
+    ("pin_mode", "Keep mode when changing bands"),
+    ("lock_frequency", "Lock frequency"),
+    ("snap_to_dial", "Snap to dial frequency"),
+    ("audio_unavailable", "Audio is not available in this browser. Rig control still works."),
+    ("stale_data", "Connection lost, data may be stale"),
+    ("refresh_interval", "Clock Refresh"),
+    ("off", "Off"),
+    ("command_log", "Commands"),
+    ("waiting", "waiting..."),
+    ("certificate_help", "The secure connection failed before it opened. If SparkSDR uses a self-signed certificate, open the address below in a new tab, accept the certificate, then connect again."),
+    ("accept_certificate", "Open"),
+    ("profiles", "Connection Profiles"),
+    ("profile_label", "Label"),
+    ("profile_frequency", "Frequency (kHz)"),
+    ("save", "Save"),
+    ("backup", "Backup"),
+    ("export_settings", "Export Settings"),
+    ("import_settings", "Import Settings"),
+    ("merge", "Merge"),
+    ("replace", "Replace"),
+    ("worked_entities", "Worked {} countries, {} states"),
+    ("clear_worked", "Forget worked countries and states"),
+    ("mode_colors", "Mode Colors"),
+    ("pin_spot", "Pin spot"),
+    ("clear_spots", "Clear spots"),
+    ("clear_spots_confirm", "Clear {} spots?"),
+    ("clear_all", "Clear All"),
+    ("keep_pinned", "Keep Pinned"),
+    ("band_plan", "Band Plan"),
+    ("license_class", "License Class"),
+    ("license_none", "Not Set"),
+    ("license_technician", "Technician"),
+    ("license_general", "General"),
+    ("license_extra", "Extra"),
+    ("out_of_band", "Out of Band"),
+    ("privilege_override", "Special Permission (MARS)"),
+    ("privilege_warning", "{} is outside your license privileges."),
+    ("spot_limit", "Max Spots"),
+    ("no_limit", "No Limit"),
+    ("spot_max_age", "Remove Spots After"),
+    ("col_dxcc", "DXCC"),
+    ("audio_source", "Play audio from this receiver"),
+    ("merge_spots", "Merge Repeated Spots Within"),
+    ("merged_help", "Reports merged into this spot"),
+    ("shortcuts", "Keyboard Shortcuts"),
+    ("shortcut_receiver_list", "Show/Hide Receivers"),
+    ("shortcut_help", "Show Keyboard Shortcuts"),
+    ("shortcut_close", "Close menus and dialogs"),
+    ("export_frames", "Export Frames"),
+    ("replay", "Replay:"),
+    ("load_recording", "Load a recording of exported frames"),
+    ("pause", "Pause"),
+    ("replay_instant", "Instant"),
+    ("no_radios", "No radios found. Configure a radio in SparkSDR, then refresh."),
+    ("refresh", "Refresh"),
+    ("refreshing", "refreshing..."),
+    ("about_server", "About server"),
+    ("hide_server_info", "Hide"),
+    ("server_host", "Server"),
+    ("server_version", "Version"),
+    ("server_protocol", "Protocol Version"),
+    ("server_address", "Address"),
+    ("server_radios", "Radios"),
+    ("split_to_spot", "Split to this signal"),
+    ("split_tx", "TX"),
+    ("edit_note", "Note"),
+    ("note_help", "e.g. needs QSL, POTA activator"),
+    ("hide_call", "Hide this call"),
+    ("blocklist", "Hidden Calls"),
+    ("unblock", "Show again"),
+    ("distance_colors", "Distance Colors"),
+    ("local", "Local"),
+    ("regional", "Regional"),
+    ("continental", "Continental"),
+    ("dx", "DX"),
+    ("my_grid", "My Grid"),
+    ("sun_times", "Sunrise / sunset (UTC) at"),
+    ("grey_line", "Grey-line: within 30 minutes of sunrise or sunset"),
+    ("sun_always_up", "Sun up all day"),
+    ("sun_always_down", "Sun down all day"),
+    ("target_latency", "Audio Latency"),
+    ("audio_underruns", "Audio is dropping out, try a higher latency"),
+    ("audio_buffer", "Audio scheduled ahead of playback"),
+    ("underruns", "Times audio ran dry in the last minute"),
+    ("overruns", "Times queued audio was dropped to catch up in the last minute"),
+    ("filter_width", "BW"),
+    ("keypad_clear", "C"),
+    ("marked_receiver", "Marked as your transmit VFO. A reminder in this page only, not read from or sent to the rig"),
+    ("mark_receiver", "Mark as your transmit VFO (reminder only, the rig is not changed)"),
+    ("macro", "Macro"),
+    ("macros", "Macros"),
+    ("record_macro", "Record macro"),
+    ("stop_recording_macro", "Stop recording"),
+    ("stop_macro", "Stop macro"),
+    ("macro_steps", "steps"),
+    ("macros_help", "Record tunes and mode changes from the Macros menu in the navbar."),
+    ("col_country", "Country"),
+    ("export_spots_image", "Save spots as an image"),
+    ("radios", "Radios"),
+    ("show_radios", "Show radio controls"),
+    ("hide_radios", "Hide radio controls"),
+    ("spots_selected", "{} spots selected"),
+    ("log_selected", "Log all"),
+    ("clear_selection", "Clear selection"),
+    ("flag_source", "Flags"),
+    ("flags_icons", "Bundled icons"),
+    ("flags_emoji", "Emoji"),
+    ("flags_off", "Off"),
+    ("all_bands", "All bands"),
+    ("receiver", "Receiver"),
+    ("rename_receiver", "Rename receiver"),
+    ("receiver_name_help", "Nickname, e.g. 20m FT8 monitor"),
+    ("no_audio", "Disable Audio"),
+    ("reload_to_apply", "Reload the page to apply"),
+    ("col_rx", "Rx"),
+    ("all_receivers", "All receivers"),
+    ("spots_trimmed", "Showing the most recent {} spots, {} older spots dropped. "),
+    ("raise_limit", "Raise limit to"),
+    ("disconnect", "Disconnect"),
+    ("dt_colors", "Time Offset Colors"),
+    ("dt_tight", "tight"),
+    ("dt_loose", "loose"),
+    ("dt_poor", "poor"),
+    ("dt_tight_help", "Tight sync, good decode timing"),
+    ("dt_loose_help", "Loose sync, the station's clock may be off"),
+    ("dt_poor_help", "Poor sync, likely a drifting clock"),
+    ("table_density", "Table Density"),
+    ("density_comfortable", "Comfortable"),
+    ("density_compact", "Compact"),
+    ("spot_fade", "Highlight New Spots"),
+    ("transverter_offset", "Transverter Offset"),
+    ("scope_tune_help", "Click to tune the selected receiver, drag to fine tune"),
+    ("select_band", "Band, returns to the last frequency and mode used on it"),
+    ("reconnecting_in", "reconnecting in {}s"),
+    ("frequency_locked", "Frequency is locked, unlock it to tune"),
+    ("highlight_call", "Highlight this call"),
+    ("unhighlight_call", "Stop highlighting this call"),
+    ("unmark_worked", "Unmark as worked"),
+    ("no_default_receiver", "No receiver selected. Pick one in the full view to show it here."),
+];
+
+const SPANISH: &[(&str, &str)] = &[
+    ("receivers", "Receptores"),
+    ("spots", "Spots"),
+    ("map", "Mapa"),
+    ("settings", "Ajustes"),
+    ("power", "Encendido"),
+    ("add_receiver", "Añadir receptor"),
+    ("spot_filters", "Filtros de spots"),
+    ("cq_only", "Solo CQ"),
+    ("current_rx", "Rx actual"),
+    ("lotw", "LoTW"),
+    ("new_state", "Estado nuevo"),
+    ("new_country", "País nuevo"),
+    ("log_file", "Libro de guardia"),
+    ("import_help", "Compara los spots con el libro de guardia para encontrar países y estados nuevos."),
+    ("adif_only", "(solo adif)"),
+    ("loaded_contacts", "{} contactos cargados"),
+    ("cancel_import", "Cancelar importación"),
+    ("col_utc", "UTC"),
+    ("col_call", "Indicativo"),
+    ("col_db", "dB"),
+    ("col_dt", "DT"),
+    ("col_freq", "Frec"),
+    ("col_mode", "Modo"),
+    ("col_dist", "Dist"),
+    ("col_message", "Mensaje"),
+    ("yes", "Sí"),
+    ("no", "No"),
+    ("display", "Pantalla"),
+    ("frequency_format", "Formato de frecuencia"),
+    ("language", "Idioma"),
+    ("disconnected", "Desconectado"),
+    ("disconnected_help", "Asegúrate de que SparkSDR tenga los Web Sockets activados y de que el nombre del host sea correcto"),
+    ("connect", "Conectar"),
+    ("col_local", "Local"),
+    ("spot_times", "Hora de los spots"),
+    ("utc", "UTC"),
+    ("local_time", "Hora local"),
+    ("highlight_words", "Palabras destacadas"),
+    ("station", "Estación"),
+    ("my_call", "Mi indicativo"),
+    ("alert_on_call", "Avisar cuando me llamen"),
+    ("audio", "Audio"),
+    ("output_device", "Dispositivo de salida"),
+    ("default_device", "Predeterminado"),
+    ("enable_audio", "El navegador ha pausado el audio. Haz clic aquí para activarlo."),
+    ("squelch", "Silenciador"),
+    ("squelch_level", "Nivel del silenciador"),
+    ("spot_click_action", "Clic en un spot"),
+    ("action_tune", "Sintonizar"),
+    ("action_copy", "Copiar indicativo"),
+    ("action_lookup", "Buscar indicativo"),
+    ("action_none", "Nada"),
+    ("lookup_provider", "Búsqueda de indicativos"),
+    ("add_lookup_provider", "Añadir sitio de búsqueda"),
+    ("lookup_name", "Nombre"),
+    ("lookup_url_help", "https://example.com/{call}"),
+    ("add", "Añadir"),
+    ("log_qso", "Registrar QSO"),
+    ("call", "Indicativo"),
+    ("rst_sent", "Enviado"),
+    ("rst_rcvd", "Recibido"),
+    ("cancel", "Cancelar"),
+    ("logged_contacts", "{} contactos registrados"),
+    ("export_log", "Exportar ADIF"),
+    ("dupe_help", "ya trabajado en esta banda y modo"),
+    ("band_modes", "Modos por banda"),
+    ("none", "Ninguno"),
+    ("pin_mode", "Mantener el modo al cambiar de banda"),
+    ("lock_frequency", "Bloquear frecuencia"),
+    ("snap_to_dial", "Ir a la frecuencia de trabajo"),
+    ("audio_unavailable", "El audio no está disponible en este navegador. El control del equipo sigue funcionando."),
+    ("stale_data", "Conexión perdida, los datos pueden estar desactualizados"),
+    ("refresh_interval", "Actualización del reloj"),
+    ("off", "Desactivado"),
+    ("command_log", "Comandos"),
+    ("waiting", "esperando..."),
+    ("certificate_help", "La conexión segura falló antes de abrirse. Si SparkSDR usa un certificado autofirmado, abre la dirección de abajo en una pestaña nueva, acepta el certificado y vuelve a conectar."),
+    ("accept_certificate", "Abrir"),
+    ("profiles", "Perfiles de conexión"),
+    ("profile_label", "Nombre"),
+    ("profile_frequency", "Frecuencia (kHz)"),
+    ("save", "Guardar"),
+    ("backup", "Copia de seguridad"),
+    ("export_settings", "Exportar ajustes"),
+    ("import_settings", "Importar ajustes"),
+    ("merge", "Combinar"),
+    ("replace", "Reemplazar"),
+    ("worked_entities", "Trabajados {} países, {} estados"),
+    ("clear_worked", "Olvidar países y estados trabajados"),
+    ("mode_colors", "Colores por modo"),
+    ("pin_spot", "Fijar spot"),
+    ("clear_spots", "Borrar spots"),
+    ("clear_spots_confirm", "¿Borrar {} spots?"),
+    ("clear_all", "Borrar todo"),
+    ("keep_pinned", "Mantener fijados"),
+    ("band_plan", "Plan de banda"),
+    ("license_class", "Clase de licencia"),
+    ("license_none", "Sin definir"),
+    ("license_technician", "Technician"),
+    ("license_general", "General"),
+    ("license_extra", "Extra"),
+    ("out_of_band", "Fuera de banda"),
+    ("privilege_override", "Permiso especial (MARS)"),
+    ("privilege_warning", "{} está fuera de los privilegios de tu licencia."),
+    ("spot_limit", "Máximo de spots"),
+    ("no_limit", "Sin límite"),
+    ("spot_max_age", "Eliminar spots tras"),
+    ("col_dxcc", "DXCC"),
+    ("audio_source", "Reproducir el audio de este receptor"),
+    ("merge_spots", "Combinar spots repetidos en"),
+    ("merged_help", "Reportes combinados en este spot"),
+    ("shortcuts", "Atajos de teclado"),
+    ("shortcut_receiver_list", "Mostrar/ocultar receptores"),
+    ("shortcut_help", "Mostrar atajos de teclado"),
+    ("shortcut_close", "Cerrar menús y diálogos"),
+    ("export_frames", "Exportar tramas"),
+    ("replay", "Reproducir:"),
+    ("load_recording", "Cargar una grabación de tramas exportadas"),
+    ("pause", "Pausa"),
+    ("replay_instant", "Inmediato"),
+    ("no_radios", "No se encontraron equipos. Configura un equipo en SparkSDR y actualiza."),
+    ("refresh", "Actualizar"),
+    ("refreshing", "actualizando..."),
+    ("about_server", "Acerca del servidor"),
+    ("hide_server_info", "Ocultar"),
+    ("server_host", "Servidor"),
+    ("server_version", "Versión"),
+    ("server_protocol", "Versión del protocolo"),
+    ("server_address", "Dirección"),
+    ("server_radios", "Equipos"),
+    ("split_to_spot", "Split a esta señal"),
+    ("split_tx", "TX"),
+    ("edit_note", "Nota"),
+    ("note_help", "p. ej. necesita QSL, activador POTA"),
+    ("hide_call", "Ocultar este indicativo"),
+    ("blocklist", "Indicativos ocultos"),
+    ("unblock", "Mostrar de nuevo"),
+    ("distance_colors", "Colores de distancia"),
+    ("local", "Local"),
+    ("regional", "Regional"),
+    ("continental", "Continental"),
+    ("dx", "DX"),
+    ("my_grid", "Mi locator"),
+    ("sun_times", "Amanecer / atardecer (UTC) en"),
+    ("grey_line", "Línea gris: a menos de 30 minutos del amanecer o atardecer"),
+    ("sun_always_up", "Sol todo el día"),
+    ("sun_always_down", "Sin sol todo el día"),
+    ("target_latency", "Latencia de audio"),
+    ("audio_underruns", "El audio se corta, pruebe una latencia mayor"),
+    ("audio_buffer", "Audio programado antes de reproducirse"),
+    ("underruns", "Veces que el audio se agotó en el último minuto"),
+    ("overruns", "Veces que se descartó audio en cola para ponerse al día en el último minuto"),
+    ("filter_width", "AB"),
+    ("keypad_clear", "C"),
+    ("marked_receiver", "Marcado como tu VFO de transmisión. Solo un recordatorio en esta página, no se lee ni se envía al equipo"),
+    ("mark_receiver", "Marcar como tu VFO de transmisión (solo recordatorio, el equipo no cambia)"),
+    ("macro", "Macro"),
+    ("macros", "Macros"),
+    ("record_macro", "Grabar macro"),
+    ("stop_recording_macro", "Detener grabación"),
+    ("stop_macro", "Detener macro"),
+    ("macro_steps", "pasos"),
+    ("macros_help", "Grabe sintonías y cambios de modo desde el menú Macros de la barra de navegación."),
+    ("col_country", "País"),
+    ("export_spots_image", "Guardar spots como imagen"),
+    ("radios", "Equipos"),
+    ("show_radios", "Mostrar controles de equipos"),
+    ("hide_radios", "Ocultar controles de equipos"),
+    ("spots_selected", "{} spots seleccionados"),
+    ("log_selected", "Registrar todos"),
+    ("clear_selection", "Borrar selección"),
+    ("flag_source", "Banderas"),
+    ("flags_icons", "Iconos incluidos"),
+    ("flags_emoji", "Emoji"),
+    ("flags_off", "Desactivadas"),
+    ("all_bands", "Todas las bandas"),
+    ("receiver", "Receptor"),
+    ("rename_receiver", "Renombrar receptor"),
+    ("receiver_name_help", "Apodo, p. ej. monitor FT8 20m"),
+    ("no_audio", "Desactivar audio"),
+    ("reload_to_apply", "Recarga la página para aplicar"),
+    ("col_rx", "Rx"),
+    ("all_receivers", "Todos los receptores"),
+    ("spots_trimmed", "Mostrando los {} spots más recientes, {} spots anteriores descartados. "),
+    ("raise_limit", "Subir el límite a"),
+    ("disconnect", "Desconectar"),
+    ("dt_colors", "Colores del desfase"),
+    ("dt_tight", "ajustado"),
+    ("dt_loose", "holgado"),
+    ("dt_poor", "malo"),
+    ("dt_tight_help", "Sincronía ajustada, buena temporización"),
+    ("dt_loose_help", "Sincronía holgada, el reloj de la estación puede estar desviado"),
+    ("dt_poor_help", "Sincronía mala, probablemente un reloj a la deriva"),
+    ("table_density", "Densidad de la tabla"),
+    ("density_comfortable", "Cómoda"),
+    ("density_compact", "Compacta"),
+    ("spot_fade", "Resaltar spots nuevos"),
+    ("transverter_offset", "Desplazamiento del transverter"),
+    ("scope_tune_help", "Clic para sintonizar el receptor seleccionado, arrastre para ajuste fino"),
+    ("select_band", "Banda, vuelve a la última frecuencia y modo usados en ella"),
+    ("reconnecting_in", "reconectando en {}s"),
+    ("frequency_locked", "La frecuencia está bloqueada, desbloquéela para sintonizar"),
+    ("highlight_call", "Resaltar este indicativo"),
+    ("unhighlight_call", "Dejar de resaltar este indicativo"),
+    ("unmark_worked", "Desmarcar como trabajado"),
+    ("no_default_receiver", "Ningún receptor seleccionado. Elige uno en la vista completa para mostrarlo aquí."),
+];
+
+const SPANISH_COUNTRIES: &[(&str, &str)] = &[
+    ("al", "Albania"), ("ar", "Argentina"), ("at", "Austria"), ("au", "Australia"),
+    ("ba", "Bosnia y Herzegovina"), ("be", "Bélgica"), ("bg", "Bulgaria"), ("bo", "Bolivia"),
+    ("br", "Brasil"), ("by", "Bielorrusia"), ("ca", "Canadá"), ("ch", "Suiza"),
+    ("cl", "Chile"), ("cn", "China"), ("co", "Colombia"), ("cr", "Costa Rica"),
+    ("cu", "Cuba"), ("cy", "Chipre"), ("cz", "República Checa"), ("de", "Alemania"),
+    ("dk", "Dinamarca"), ("do", "República Dominicana"), ("ec", "Ecuador"), ("ee", "Estonia"),
+    ("eg", "Egipto"), ("es", "España"), ("fi", "Finlandia"), ("fr", "Francia"),
+    ("gb-eng", "Inglaterra"), ("gb-nir", "Irlanda del Norte"), ("gb-sct", "Escocia"), ("gb-wls", "Gales"),
+    ("gr", "Grecia"), ("gt", "Guatemala"), ("hk", "Hong Kong"), ("hn", "Honduras"),
+    ("hr", "Croacia"), ("hu", "Hungría"), ("id", "Indonesia"), ("ie", "Irlanda"),
+    ("il", "Israel"), ("in", "India"), ("is", "Islandia"), ("it", "Italia"),
+    ("jm", "Jamaica"), ("jp", "Japón"), ("ke", "Kenia"), ("kr", "Corea del Sur"),
+    ("kz", "Kazajistán"), ("lt", "Lituania"), ("lu", "Luxemburgo"), ("lv", "Letonia"),
+    ("ma", "Marruecos"), ("md", "Moldavia"), ("me", "Montenegro"), ("mk", "Macedonia del Norte"),
+    ("mt", "Malta"), ("mx", "México"), ("ng", "Nigeria"), ("ni", "Nicaragua"),
+    ("nl", "Países Bajos"), ("no", "Noruega"), ("nz", "Nueva Zelanda"), ("pa", "Panamá"),
+    ("pe", "Perú"), ("ph", "Filipinas"), ("pl", "Polonia"), ("pr", "Puerto Rico"),
+    ("pt", "Portugal"), ("py", "Paraguay"), ("ro", "Rumania"), ("rs", "Serbia"),
+    ("ru", "Rusia"), ("sa", "Arabia Saudita"), ("se", "Suecia"), ("sg", "Singapur"),
+    ("si", "Eslovenia"), ("sk", "Eslovaquia"), ("sv", "El Salvador"), ("th", "Tailandia"),
+    ("tr", "Turquía"), ("tw", "Taiwán"), ("ua", "Ucrania"), ("us", "Estados Unidos"),
+    ("uy", "Uruguay"), ("ve", "Venezuela"), ("vn", "Vietnam"), ("za", "Sudáfrica"),
+];
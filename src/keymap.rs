@@ -0,0 +1,94 @@
+use yew::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::model::{Msg};
+
+// Actions that can be bound to a key
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Action {
+    ToggleReceiverList,
+    ShowHelp,
+}
+
+pub const ACTIONS: [Action; 2] = [
+    Action::ToggleReceiverList,
+    Action::ShowHelp,
+];
+
+impl Action {
+    // Also the i18n key for the action's description
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::ToggleReceiverList => "shortcut_receiver_list",
+            Action::ShowHelp => "shortcut_help",
+        }
+    }
+
+    pub fn default_key(&self) -> &'static str {
+        match self {
+            Action::ToggleReceiverList => "r",
+            Action::ShowHelp => "?",
+        }
+    }
+
+    pub fn msg(&self) -> Msg {
+        match self {
+            Action::ToggleReceiverList => Msg::ToggleReceiverList,
+            Action::ShowHelp => Msg::ToggleShortcutHelp,
+        }
+    }
+}
+
+// Key (KeyboardEvent.key, e.g. "r") bound to an action
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KeyBinding {
+    pub key: String,
+    pub action: Action,
+}
+
+// Key for `action`, the default unless rebound in `keymap`
+pub fn key_for(keymap: &[KeyBinding], action: Action) -> String {
+    keymap.iter()
+        .find(|binding| binding.action == action)
+        .map(|binding| binding.key.clone())
+        .unwrap_or(action.default_key().to_string())
+}
+
+pub fn action_for(keymap: &[KeyBinding], key: &str) -> Option<Action> {
+    ACTIONS.iter()
+        .find(|action| !key.is_empty() && key_for(keymap, **action) == key)
+        .cloned()
+}
+
+// Empty `key` leaves the action without a shortcut. Any other action
+// already on `key` is unbound so a key never fires two actions.
+pub fn set_key(keymap: &mut Vec<KeyBinding>, action: Action, key: &str) {
+    let key = key.trim();
+    if !key.is_empty() {
+        let taken: Vec<Action> = ACTIONS.iter()
+            .filter(|other| **other != action && key_for(keymap, **other) == key)
+            .cloned()
+            .collect();
+        for other in taken {
+            keymap.retain(|binding| binding.action != other);
+            keymap.push(KeyBinding { key: String::new(), action: other });
+        }
+    }
+    keymap.retain(|binding| binding.action != action);
+    keymap.push(KeyBinding { key: key.to_string(), action });
+}
+
+// True if the key press is going into a form field or carries a
+// modifier, shortcuts are ignored then
+pub fn is_typing(e: &KeyboardEvent) -> bool {
+    if e.ctrl_key() || e.alt_key() || e.meta_key() {
+        return true;
+    }
+    match e.target().and_then(|target| target.dyn_into::<web_sys::HtmlElement>().ok()) {
+        Some(element) => {
+            let tag = element.tag_name();
+            tag == "INPUT" || tag == "TEXTAREA" || tag == "SELECT" || element.is_content_editable()
+        },
+        None => false,
+    }
+}
@@ -8,6 +8,7 @@ use yew::services::{ConsoleService};
 use yew_router::{Switch};
 use web_sys::{HtmlCanvasElement};
 use js_sys::{DataView};
+use std::time::Duration;
 
 use ham_rs::lotw::LoTWStatus;
 use sparkplug::{Command,CommandResponse};
@@ -17,6 +18,27 @@ mod color;
 mod spot;
 mod audio;
 mod spectrum;
+mod settings;
+mod recorder;
+mod i18n;
+mod logbook;
+mod dial;
+mod command_log;
+mod worked;
+mod band_plan;
+mod dxcc;
+mod display;
+mod keymap;
+mod replay;
+mod call_notes;
+mod solar;
+mod commands;
+mod host_api;
+mod macros;
+mod spot_image;
+mod flags;
+mod protocol;
+mod worker;
 
 use model::{Model,Msg,AppRoute};
 use spot::{SpotFilter};
@@ -28,6 +50,8 @@ impl Component for Model {
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
             Msg::Connected => {
+                self.connected();
+                host_api::emit("connection", &host_api::ConnectionEvent { connected: true });
                 // When we first connect to SparkSDR gather some basic information
                 self.send_command(Command::GetReceivers);
                 self.send_command(Command::GetRadios);
@@ -42,6 +66,7 @@ impl Component for Model {
                 false
             },
             Msg::CommandResponse(Ok(msg)) => {
+                self.command_log.received(&msg);
                 match msg {
                     // getReceiversResponse: update our receiver list
                     CommandResponse::Receivers { receivers } => {
@@ -57,23 +82,17 @@ impl Component for Model {
                     },
                     // spotResponse: new incoming spots
                     CommandResponse::Spots { spots } => {
-                        let cq_only = self.spots.cq_only_spot_filter_enabled();
-                        for spot in spots {
-                            if (cq_only && spot.is_cq()) || !cq_only {
-                                let current_rx_pass =
-                                    match self.default_receiver() {
-                                        Some(receiver) if self.spots.current_receiver_spot_filter_enabled() && receiver.has_spots() => {
-                                            if spot.current_rx(&receiver) { true } else { false }
-                                        },
-                                        _ => true,
-                                    };
+                        for spot in &spots {
+                            host_api::emit("spot", &host_api::SpotEvent::new(spot));
+                        }
+                        let current_rx = self.default_receiver();
+                        let called = self.spots.add_spots(&self.link, spots, &self.settings.my_call, &self.receivers, current_rx, self.settings.spot_limit);
 
-                                if current_rx_pass {
-                                    self.spots.add_spot(&self.link, spot, &self.import);
-                                }
-                            }
+                        // someone is answering my call
+                        if called && self.settings.alert_on_call {
+                            self.audio.play_alert_tone();
                         }
-                        self.spots.trim_spots(100);
+                        return self.schedule_spot_render();
                     },
                     // ReceiverResponse: receiver updates (mode/frequency)
                     CommandResponse::ReceiverResponse{ id: receiver_id, frequency, mode, filter_low, filter_high } => {
@@ -83,6 +102,8 @@ impl Component for Model {
                 true
             },
             Msg::EnableAudio => {
+                // clicking the audio button counts as a user gesture
+                self.audio.resume(&self.link);
                 match self.audio.receiving_audio() {
                     Some(_) => {
                         self.unsubscribe_to_audio();
@@ -98,30 +119,44 @@ impl Component for Model {
                 let data_type = view.get_uint8(0);
                 let receiver_id = view.get_int32(1);
 
-                match (data_type, self.audio.receiving_audio(), self.spectrum.receiving_spectrum()) {
-                    (1, Some(_), _) => {
-                        self.audio.import_audio_data(data);
-                    },
-                    (2, _, Some(subscribed_spectrum)) => {// if subscribed_spectrum == (receiver_id as u32) => {
-                        match self.default_receiver() {
-                            Some(receiver) => {
-                                let freq_start = view.get_float64_endian(5, true).floor();
-                                let freq_stop = view.get_float64_endian(13, true).floor();
-                                self.spectrum.import_spectrum_data(data, freq_start, freq_stop);
-                            },
-                            None => () // should never happen
-                        }
+                match (data_type, self.audio.receiving_audio()) {
+                    (1, _) if self.no_audio => (),
+                    (1, Some(audio_id)) if audio_id == receiver_id as u32 => {
+                        self.audio.import_audio_data(receiver_id as u32, data);
                     },
-                    (2, _, Some(_)) => (),
-                    (_, None, None) => {
-                        ConsoleService::error("receiving binary data but not subscribed to anything");
+                    // frames still arriving from a receiver we switched away from
+                    (1, Some(_)) => (),
+                    (1, None) => {
+                        ConsoleService::error("receiving audio but not subscribed to audio");
                     },
-                    (dt, _, _) => {
+                    (dt, _) => {
                         ConsoleService::error(&format!("unsupported data type: {}", dt));
                     }
                 }
                 false
             },
+            Msg::ReceivedSpectrum(data) => {
+                match (self.spectrum.receiving_spectrum(), self.default_receiver()) {
+                    (Some(_), Some(_)) => {
+                        let view = DataView::new(&data, 0, data.byte_length() as usize);
+                        let freq_start = view.get_float64_endian(5, true).floor();
+                        let freq_stop = view.get_float64_endian(13, true).floor();
+                        // re-render to move the scope's spot markers
+                        // when the span changes
+                        self.spectrum.import_spectrum_data(data, freq_start, freq_stop)
+                    },
+                    // frames still arriving after unsubscribing
+                    _ => false,
+                }
+            },
+            Msg::ToggleMarkedReceiver(receiver_id) => {
+                self.toggle_marked_receiver(receiver_id);
+                true
+            },
+            Msg::SetAudioReceiver(receiver) => {
+                self.set_audio_receiver(receiver);
+                true
+            },
             Msg::MuteUnmute => {
                 self.audio.toggle_mute();
                 true
@@ -140,33 +175,156 @@ impl Component for Model {
                     _ => ()
                 }
 
-                self.spots.cache_callsign_info(call, &self.import);
+                self.spots.cache_callsign_info(call);
+                self.schedule_spot_render()
+            },
+            Msg::RenderSpots => {
+                self.render_spots();
                 true
             },
             Msg::CallsignInfoReady(Err(err)) => {
                 ConsoleService::error(&format!("callsign info error: {}", err));
                 false
             },
+            Msg::TogglePinSpot(key) => {
+                self.spots.toggle_pin(key);
+                true
+            },
             Msg::ClearSpots => {
-                self.spots.clear_spots();
+                self.clear_spots();
+                true
+            },
+            Msg::ConfirmClearSpots(keep_pinned) => {
+                self.confirm_clear_spots(keep_pinned);
+                true
+            },
+            Msg::CancelClearSpots => {
+                self.cancel_clear_spots();
                 true
             },
             Msg::SetDefaultReceiver(receiver_id) => {
                 self.set_default_receiver(Some(receiver_id));
                 true
             },
+            Msg::SpotClicked(call, frequency, mode) => {
+                self.spot_clicked(call, frequency, mode);
+                true
+            },
+            Msg::ScopeTune(fraction) => {
+                self.scope_tune(fraction);
+                false
+            },
+            Msg::SendScopeTune => {
+                self.send_scope_tune();
+                true
+            },
+            Msg::ScopeTuneDone => {
+                self.finish_scope_tune();
+                true
+            },
+            Msg::SpotAction(action, call, frequency, mode) => {
+                self.hide_spot_menu();
+                self.spot_action(action, call, frequency, mode);
+                true
+            },
+            Msg::ShowSpotMenu(call, frequency, signal, mode, x, y) => {
+                self.show_spot_menu(call, frequency, signal, mode, x, y);
+                true
+            },
+            Msg::ToggleHighlightCall(call) => {
+                self.hide_spot_menu();
+                self.toggle_highlight_call(call);
+                true
+            },
+            Msg::BlockCall(entry) => {
+                self.hide_spot_menu();
+                // empty entry adds the one typed in settings
+                let entry = if entry.is_empty() { self.new_block.clone() } else { entry };
+                self.block_call(entry);
+                true
+            },
+            Msg::UnblockCall(entry) => {
+                self.spots.unblock(&entry);
+                true
+            },
+            Msg::UpdateNewBlock(entry) => {
+                self.new_block = entry;
+                false
+            },
+            Msg::EditCallNote(call) => {
+                self.hide_spot_menu();
+                self.edit_call_note(call);
+                true
+            },
+            Msg::UpdateCallNote(note) => {
+                self.update_call_note(note);
+                true
+            },
+            Msg::SaveCallNote => {
+                self.save_call_note();
+                true
+            },
+            Msg::CancelCallNote => {
+                self.cancel_call_note();
+                true
+            },
+            Msg::EditReceiverName(receiver_id) => {
+                self.edit_receiver_name(receiver_id);
+                true
+            },
+            Msg::UpdateReceiverName(name) => {
+                self.update_receiver_name(name);
+                true
+            },
+            Msg::SaveReceiverName => {
+                self.save_receiver_name();
+                true
+            },
+            Msg::CancelReceiverName => {
+                self.cancel_receiver_name();
+                true
+            },
+            Msg::OpenKeypad(receiver_id) => {
+                self.open_keypad(receiver_id);
+                true
+            },
+            Msg::KeypadPress(key) => {
+                self.keypad_press(key);
+                true
+            },
+            Msg::ConfirmKeypad => {
+                self.confirm_keypad();
+                true
+            },
+            Msg::CloseKeypad => {
+                self.close_keypad();
+                true
+            },
+            Msg::SplitToSpot(frequency) => {
+                self.hide_spot_menu();
+                self.split_to_spot(frequency);
+                true
+            },
+            Msg::ClearSplit => {
+                self.clear_split();
+                true
+            },
+            Msg::HideSpotMenu => {
+                self.hide_spot_menu();
+                true
+            },
             Msg::AddReceiver(radio_id) => {
-                self.send_command(Command::AddReceiver { id: radio_id });
+                self.send_checked(commands::add_receiver(&self.radios, radio_id));
                 false
             },
             Msg::RemoveReceiver(receiver_id) => {
-                self.send_command(Command::RemoveReceiver{ id: receiver_id });
+                self.send_checked(commands::remove_receiver(&self.receivers, receiver_id));
                 false
             },
             Msg::TogglePower(radio_id) => {
                 match self.get_radio_power_state(radio_id) {
                     Some(state) => {
-                        self.send_command(Command::SetRunning{ id: radio_id, running: !state });
+                        self.send_checked(commands::set_running(&self.radios, radio_id, !state));
                     },
                     None => {
                         ConsoleService::error(&format!("TogglePower: No radio found: {}", radio_id));
@@ -174,12 +332,68 @@ impl Component for Model {
                 }
                 false
             },
+            Msg::Shortcut(key) => {
+                match self.shortcut(&key) {
+                    Some(msg) => self.update(msg),
+                    None => false,
+                }
+            },
+            Msg::ToggleShortcutHelp => {
+                self.toggle_shortcut_help();
+                true
+            },
+            Msg::SetShortcut(action, key) => {
+                self.set_shortcut(action, key);
+                true
+            },
+            Msg::ToggleRadioList => {
+                self.toggle_radio_list();
+                true
+            },
             Msg::ToggleReceiverList => {
                 self.toggle_receiver_list();
                 true
             },
+            Msg::SendModeChanges => {
+                self.send_mode_changes();
+                false
+            },
+            Msg::StoreBandStack => {
+                self.store_band_stack();
+                false
+            },
+            Msg::TuneReceiver(receiver_id, frequency, mode) => {
+                let mode =
+                    match self.receivers.iter().find(|r| r.id == receiver_id) {
+                        Some(receiver) => mode.unwrap_or_else(|| receiver.mode.clone()),
+                        None => {
+                            self.show_error(format!("no receiver {}", receiver_id));
+                            return true;
+                        }
+                    };
+                self.tune_receiver(receiver_id, frequency, mode);
+                true
+            },
             Msg::ModeChanged(receiver_id, mode) => {
+                self.record_mode(receiver_id, &mode);
                 self.change_receiver_mode(receiver_id, mode);
+                self.remember_band(receiver_id);
+                true
+            },
+            Msg::SelectBand(receiver_id, band) => {
+                self.select_band(receiver_id, &band);
+                true
+            },
+            Msg::SnapToDial(receiver_id) => {
+                self.snap_to_dial(receiver_id);
+                true
+            },
+            Msg::ToggleFreqLock(receiver_id) => {
+                self.toggle_freq_lock(receiver_id);
+                true
+            },
+            Msg::TogglePinnedMode(receiver_id) => {
+                self.toggle_pinned_mode(receiver_id);
                 true
             },
             Msg::FrequencyDown(receiver_id, digit) => {
@@ -196,13 +410,298 @@ impl Component for Model {
                 self.connect(&addr);
                 true
             },
+            Msg::ConnectProfile(index) => {
+                self.connect_profile(index);
+                true
+            },
+            Msg::EditProfile(index) => {
+                self.edit_profile(index);
+                true
+            },
+            Msg::UpdateProfileForm(field, value) => {
+                self.update_profile_form(field, value);
+                false
+            },
+            Msg::SaveProfile => {
+                self.save_profile();
+                true
+            },
+            Msg::DeleteProfile(index) => {
+                self.delete_profile(index);
+                true
+            },
             Msg::UpdateWebsocketAddress(address) => {
                 self.ws_location = address;
                 true
             },
+            Msg::Disconnect => {
+                self.close_connection();
+                host_api::emit("connection", &host_api::ConnectionEvent { connected: false });
+                ConsoleService::log("Disconnected by user");
+                true
+            },
+            Msg::RevertChange(receiver_id) => {
+                self.revert_unconfirmed_change(receiver_id)
+            },
             Msg::Disconnected => {
                 self.disconnect();
+                host_api::emit("connection", &host_api::ConnectionEvent { connected: false });
                 ConsoleService::error("Disconnected");
+                self.schedule_reconnect();
+                true
+            },
+            Msg::Reconnect => {
+                self.reconnect();
+                true
+            },
+            Msg::SetNumberFormat(format) => {
+                self.set_number_format(format);
+                true
+            },
+            Msg::SetLocale(locale) => {
+                self.set_locale(locale);
+                true
+            },
+            Msg::SetTimeDisplay(time_display) => {
+                self.set_time_display(time_display);
+                true
+            },
+            Msg::SetHighlightTokens(tokens) => {
+                self.set_highlight_tokens(tokens);
+                true
+            },
+            Msg::SetMyCall(call) => {
+                self.set_my_call(call);
+                true
+            },
+            Msg::SetMyGrid(grid) => {
+                self.set_my_grid(grid);
+                true
+            },
+            Msg::ToggleAlertOnCall => {
+                self.toggle_alert_on_call();
+                true
+            },
+            Msg::AudioOutputDevices(devices) => {
+                self.audio.set_output_devices(devices);
+                true
+            },
+            Msg::SetAudioOutputDevice(device_id) => {
+                self.set_audio_output_device(device_id);
+                true
+            },
+            Msg::ResumeAudio => {
+                self.audio.resume(&self.link);
+                false
+            },
+            Msg::AudioResumed => {
+                ConsoleService::log("audio resumed");
+                self.audio.set_audio_enabled(true);
+                true
+            },
+            Msg::AudioStateChanged => {
+                self.audio.state_changed();
+                true
+            },
+            Msg::PageVisible => {
+                // may still need a click if the browser requires one
+                self.audio.resume(&self.link);
+                false
+            },
+            Msg::SetPan(receiver_id, pan) => {
+                self.audio.set_pan(receiver_id, pan);
+                true
+            },
+            Msg::ToggleRecording => {
+                self.toggle_recording();
+                true
+            },
+            Msg::RecordedAudio(blob) => {
+                self.recorder.add_chunk(blob);
+                false
+            },
+            Msg::RecordingStopped => {
+                self.recorder.finish();
+                true
+            },
+            Msg::ToggleSquelch => {
+                self.toggle_squelch();
+                true
+            },
+            Msg::SetSquelchLevel(level) => {
+                self.set_squelch_level(level);
+                true
+            },
+            Msg::SetTargetLatency(seconds) => {
+                self.set_target_latency(seconds);
+                true
+            },
+            Msg::UpdateSquelch => {
+                self.audio.update_squelch();
+                false
+            },
+            Msg::SetTransverterOffset(receiver_id, offset) => {
+                self.set_transverter_offset(receiver_id, offset);
+                true
+            },
+            Msg::ToggleSpotFade => {
+                self.toggle_spot_fade();
+                true
+            },
+            Msg::SetTableDensity(density) => {
+                self.set_table_density(density);
+                true
+            },
+            Msg::SetFlagSource(source) => {
+                self.set_flag_source(source);
+                true
+            },
+            Msg::SetSpotClickAction(action) => {
+                self.set_spot_click_action(action);
+                true
+            },
+            Msg::SetModeColor(mode, color) => {
+                self.set_mode_color(mode, color);
+                true
+            },
+            Msg::SetBandPlanRegion(region) => {
+                self.set_band_plan_region(region);
+                true
+            },
+            Msg::SetLicenseClass(class) => {
+                self.set_license_class(class);
+                true
+            },
+            Msg::TogglePrivilegeOverride => {
+                self.toggle_privilege_override();
+                true
+            },
+            Msg::SetBandMode(band, mode) => {
+                self.set_band_mode(band, mode);
+                true
+            },
+            Msg::SetLookupProvider(index) => {
+                self.set_lookup_provider(index);
+                true
+            },
+            Msg::UpdateNewLookupProvider(name, url) => {
+                self.update_new_lookup_provider(name, url);
+                true
+            },
+            Msg::AddLookupProvider => {
+                self.add_lookup_provider();
+                true
+            },
+            Msg::RemoveLookupProvider(index) => {
+                self.remove_lookup_provider(index);
+                true
+            },
+            Msg::OpenQsoForm(call, frequency, mode) => {
+                self.hide_spot_menu();
+                self.open_qso_form(call, frequency, mode);
+                true
+            },
+            Msg::UpdateQsoForm(field, value) => {
+                self.update_qso_form(field, value);
+                false
+            },
+            Msg::LogQso => {
+                self.log_qso();
+                true
+            },
+            Msg::CancelQso => {
+                self.cancel_qso();
+                true
+            },
+            Msg::DismissToast(index) => {
+                self.dismiss_toast(index);
+                true
+            },
+            Msg::ExpireToast(id) => {
+                self.expire_toast(id)
+            },
+            Msg::DismissDupeWarning => {
+                self.dismiss_dupe_warning();
+                true
+            },
+            Msg::ToggleCommandLog => {
+                self.show_command_log = !self.show_command_log;
+                true
+            },
+            Msg::ClearCommandLog => {
+                self.command_log.clear();
+                self.frame_log.clear();
+                true
+            },
+            Msg::Tick => {
+                self.tick()
+            },
+            Msg::SetSpotLimit(limit) => {
+                self.set_spot_limit(limit);
+                true
+            },
+            Msg::SetSpotMaxAge(minutes) => {
+                self.set_spot_max_age(minutes);
+                true
+            },
+            Msg::SetMergeFrequency(frequency) => {
+                self.set_merge_frequency(frequency);
+                true
+            },
+            Msg::SetMergeSeconds(seconds) => {
+                self.set_merge_seconds(seconds);
+                true
+            },
+            Msg::SetDistanceThreshold(bucket, km) => {
+                self.set_distance_threshold(bucket, km);
+                true
+            },
+            Msg::SetDtThreshold(bucket, seconds) => {
+                self.set_dt_threshold(bucket, seconds);
+                true
+            },
+            Msg::SetReceiverFilter(receiver_id) => {
+                self.receiver_filter = receiver_id;
+                true
+            },
+            Msg::SetBandFilter(band) => {
+                self.set_band_filter(band);
+                true
+            },
+            Msg::ToggleSpotSelection(key) => {
+                self.toggle_spot_selection(key);
+                true
+            },
+            Msg::ClearSpotSelection => {
+                self.selected_spots.clear();
+                true
+            },
+            Msg::LogSelectedSpots => {
+                self.log_selected_spots();
+                true
+            },
+            Msg::ExportLog => {
+                self.export_log();
+                false
+            },
+            Msg::ExportSelectedSpots => {
+                self.export_selected_spots();
+                false
+            },
+            Msg::ExportSpotsImage => {
+                self.export_spots_image();
+                false
+            },
+            Msg::ToggleNoAudio => {
+                self.toggle_no_audio();
+                true
+            },
+            Msg::ToggleDxccColumn => {
+                self.toggle_dxcc_column();
+                true
+            },
+            Msg::SetTickInterval(seconds) => {
+                self.set_tick_interval(seconds);
                 true
             },
             Msg::SetGain(gain) => {
@@ -232,6 +731,117 @@ impl Component for Model {
                 self.load_adif_data(data);
                 true
             },
+            Msg::TextFrame(text) => {
+                self.frame_log.received(&text);
+                self.update(Msg::CommandResponse(protocol::parse_response(&text)))
+            },
+            Msg::WorkerDone(response) => {
+                self.worker_done(response);
+                true
+            },
+            Msg::ReplayFrame(text) => {
+                self.update(Msg::CommandResponse(protocol::parse_response(&text)))
+            },
+            Msg::ToggleMacroRecording => {
+                self.toggle_macro_recording();
+                true
+            },
+            Msg::PlayMacro(index) => {
+                self.play_macro(index);
+                true
+            },
+            Msg::StopMacro => {
+                self.macro_player.stop();
+                true
+            },
+            Msg::MacroNext => {
+                match self.macro_player.next(&self.link) {
+                    Some(msg) => self.update(msg),
+                    None => true,
+                }
+            },
+            Msg::RenameMacro(index, name) => {
+                self.rename_macro(index, name);
+                true
+            },
+            Msg::DeleteMacro(index) => {
+                self.delete_macro(index);
+                true
+            },
+            Msg::ReplayNext => {
+                match self.replay.next(&self.link) {
+                    Some(text) => self.update(Msg::ReplayFrame(text)),
+                    None => true,
+                }
+            },
+            Msg::ExportFrames => {
+                self.export_frames();
+                false
+            },
+            Msg::ReplayFile(file) => {
+                self.read_replay_file(file);
+                false
+            },
+            Msg::ReplayFileLoaded(data) => {
+                self.load_replay_file(data);
+                true
+            },
+            Msg::PlayReplay(speed) => {
+                self.replay.play(&self.link, speed);
+                true
+            },
+            Msg::PauseReplay => {
+                self.replay.pause();
+                true
+            },
+            Msg::UnloadReplay => {
+                self.replay.unload();
+                true
+            },
+            Msg::Refresh => {
+                self.refresh();
+                true
+            },
+            Msg::ToggleServerInfo => {
+                self.toggle_server_info();
+                true
+            },
+            Msg::RefreshRadios => {
+                self.refresh_radios();
+                false
+            },
+            Msg::SettingsFile(file) => {
+                self.read_settings_file(file);
+                false
+            },
+            Msg::SettingsFileLoaded(data) => {
+                self.load_settings_file(data);
+                true
+            },
+            Msg::ExportSettings => {
+                self.download_settings();
+                false
+            },
+            Msg::ApplySettingsImport(merge) => {
+                self.apply_settings_import(merge);
+                true
+            },
+            Msg::CancelSettingsImport => {
+                self.cancel_settings_import();
+                true
+            },
+            Msg::ClearWorked => {
+                self.spots.clear_worked();
+                true
+            },
+            Msg::UnmarkWorkedCountry(code) => {
+                self.spots.unmark_worked_country(&code);
+                true
+            },
+            Msg::UnmarkWorkedState(state) => {
+                self.spots.unmark_worked_state(&state);
+                true
+            },
             Msg::Files(files, _) => {
                 for file in files.into_iter() {
                     self.read_file(file);
@@ -298,8 +908,14 @@ impl Component for Model {
     }
 
     fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        host_api::attach(link.callback(|msg| msg));
         let mut model = Model::new(link);
-        model.connect("ws://localhost:4649/Spark");
+        let url = model.ws_location.to_string();
+        model.connect(&url);
+        if model.settings.tick_interval > 0 {
+            model.enable_ticks(Duration::from_secs(model.settings.tick_interval));
+        }
+        model.listen_for_shortcuts();
         model
     }
 
@@ -313,26 +929,63 @@ impl Component for Model {
 
         let tmp_canvas = self.spectrum.tmp_canvas_node_ref.cast::<HtmlCanvasElement>().unwrap();
         self.spectrum.tmp_canvas = Some(tmp_canvas);
+        self.spectrum.scope = self.spectrum.scope_node_ref.cast::<HtmlCanvasElement>();
 
-        if first_render {
+        self.restore_scroll_anchor();
+
+        if first_render && !self.no_audio {
             self.audio.create_audio_context();
+            self.audio.watch_state(&self.link);
+            if let Some(device_id) = &self.settings.audio_output_device {
+                self.audio.set_output_device(device_id);
+            }
+            self.audio.enumerate_output_devices(&self.link);
+            self.audio.set_squelch(&self.link, self.settings.squelch());
+            self.audio.set_target_latency(self.settings.target_latency);
+        }
+        if first_render {
             js_sys::eval("initMap();").unwrap();
         }
     }
 
     fn view(&self) -> Html {
+        let route = AppRoute::switch(self.route.clone());
         let (is_index, spectrum_style, map_style) =
-            match AppRoute::switch(self.route.clone()) {
+            match route {
                 Some(AppRoute::Index) | None => (true, "position:relative;margin-top:10px", "height:0px;overflow:hidden;"),
+                Some(AppRoute::Settings) => (false, "height:110px;overflow:hidden;position:relative;margin-top:10px", "height:0px;overflow:hidden;"),
+                Some(AppRoute::Mini) => (false, "display:none", "height:0px;overflow:hidden;"),
                 _ => (false, "height:110px;overflow:hidden;position:relative;margin-top:10px", ""),
             };
 
-        match self.is_connected() {
+        match self.is_connected() || self.is_stale() || self.replay.is_loaded() {
             false => self.disconnected_view(),
+            // the canvases and map are still needed by the spectrum
+            // and map code, just hidden
+            true if matches!(route, Some(AppRoute::Mini)) => {
+                html! {
+                    <div class=if self.is_stale() { "stale" } else { "" }>
+                        { self.mini_view() }
+                        <div style=spectrum_style>
+                            <canvas ref=self.spectrum.canvas_node_ref.clone() width="2048" height="200" />
+                        </div>
+                        <canvas ref=self.spectrum.tmp_canvas_node_ref.clone() width="2048" height="200" style="display:none" />
+                        <div style=map_style>
+                            <div id="map" style="width:100%;height:600px"> </div>
+                        </div>
+                    </div>
+                }
+            },
             true => {
                 html! {
-                    <>
+                    <div class=if self.is_stale() { "stale" } else { "" }>
                         { self.navbar_view() }
+                        { self.stale_view() }
+                        { self.no_radios_view() }
+                        { self.audio_prompt_view() }
+                        { self.dupe_warning_view() }
+                        { self.shortcut_help_view() }
+                        { self.toasts_view() }
 
                         <div style="clear:both"></div>
 
@@ -340,6 +993,7 @@ impl Component for Model {
                         { self.spot_filters_sidebar() }
 
                         <div style="margin-left:15em;padding:0 10px 0 20px">
+                            { if is_index { self.band_scope_view() } else { html! {} } }
                             <div style=spectrum_style>
                                 <div id="receiver-marker" style="display:none">
                                     <div></div>
@@ -359,6 +1013,8 @@ impl Component for Model {
                                             { self.spots_view() }
                                         </>
                                     }
+                                } else if let Some(AppRoute::Settings) = route {
+                                    self.settings_view()
                                 } else {
                                     html! { }
                                 }
@@ -371,7 +1027,13 @@ impl Component for Model {
                         <canvas ref=self.spectrum.tmp_canvas_node_ref.clone() width="2048" height="200" style="display:none;background-color: black ;" />
 
                         { self.footer_view() }
-                    </>
+                        { self.command_log_view() }
+                        { self.spot_menu_view() }
+                        { self.qso_form_view() }
+                        { self.note_form_view() }
+                        { self.name_form_view() }
+                        { self.keypad_view() }
+                    </div>
                 }
             }
         }
@@ -380,6 +1042,10 @@ impl Component for Model {
 
 #[wasm_bindgen(start)]
 pub fn run_app() {
+    // the web worker loads this module too, but has no page
+    if web_sys::window().is_none() {
+        return;
+    }
     //App::<Model>::new().mount_to_body();
     yew::start_app::<Model>();
 }
\ No newline at end of file
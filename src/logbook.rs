@@ -0,0 +1,191 @@
+use std::collections::HashSet;
+use yew::format::{Json};
+use yew::services::storage::{StorageService};
+use yew::services::{ConsoleService};
+use chrono::{DateTime, Utc};
+
+use ham_rs::{Mode,Band,LogEntry};
+use sparkplug::Spot;
+
+const QSOS_KEY: &str = "radio.qsos";
+
+// A contact logged from within the app
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QsoEntry {
+    pub call: String,
+    pub frequency: f32,
+    pub mode: Mode,
+    pub time: DateTime<Utc>,
+    pub rst_sent: String,
+    pub rst_rcvd: String,
+}
+
+impl QsoEntry {
+    // Contact from a spot, the spot's SNR as the received report
+    pub fn from_spot(spot: &Spot) -> QsoEntry {
+        QsoEntry {
+            call: spot.call.call(),
+            frequency: spot.tuned_frequency,
+            mode: spot.mode.clone(),
+            time: spot.time,
+            rst_sent: String::new(),
+            rst_rcvd: format!("{:+.0}", spot.snr as f64),
+        }
+    }
+
+    // Single ADIF record for this contact
+    pub fn to_adif(&self) -> String {
+        let mut record = String::new();
+        record.push_str(&adif_field("CALL", &self.call));
+        record.push_str(&adif_field("QSO_DATE", &self.time.format("%Y%m%d").to_string()));
+        record.push_str(&adif_field("TIME_ON", &self.time.format("%H%M%S").to_string()));
+        record.push_str(&adif_field("FREQ", &format!("{:.6}", self.frequency / 1000000.0)));
+        if let Some(band) = Band::new(self.frequency as i32).band() {
+            record.push_str(&adif_field("BAND", &band.to_string()));
+        }
+        record.push_str(&adif_field("MODE", &self.mode.mode().to_string()));
+        record.push_str(&adif_field("RST_SENT", &self.rst_sent));
+        record.push_str(&adif_field("RST_RCVD", &self.rst_rcvd));
+        record.push_str("<EOR>\n");
+        record
+    }
+}
+
+fn adif_field(name: &str, value: &str) -> String {
+    if value.is_empty() {
+        String::new()
+    } else {
+        format!("<{}:{}>{} ", name, value.len(), value)
+    }
+}
+
+// Quick log form, prefilled from a spot or the current receiver
+#[derive(Clone, Debug)]
+pub struct QsoForm {
+    pub call: String,
+    pub frequency: f32,
+    pub mode: Mode,
+    pub time: DateTime<Utc>,
+    pub rst_sent: String,
+    pub rst_rcvd: String,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum QsoField {
+    Call,
+    RstSent,
+    RstRcvd,
+}
+
+impl QsoForm {
+    pub fn new(call: String, frequency: f32, mode: Mode) -> QsoForm {
+        QsoForm {
+            call,
+            frequency,
+            mode,
+            time: Utc::now(),
+            rst_sent: String::new(),
+            rst_rcvd: String::new(),
+        }
+    }
+
+    pub fn set(&mut self, field: QsoField, value: String) {
+        match field {
+            QsoField::Call => self.call = value.trim().to_uppercase(),
+            QsoField::RstSent => self.rst_sent = value.trim().to_string(),
+            QsoField::RstRcvd => self.rst_rcvd = value.trim().to_string(),
+        }
+    }
+
+    pub fn entry(&self) -> Option<QsoEntry> {
+        if self.call.is_empty() {
+            return None;
+        }
+        Some(QsoEntry {
+            call: self.call.clone(),
+            frequency: self.frequency,
+            mode: self.mode.clone(),
+            time: self.time,
+            rst_sent: self.rst_sent.clone(),
+            rst_rcvd: self.rst_rcvd.clone(),
+        })
+    }
+}
+
+// (call, band, mode) a contact counts as worked on, upper case
+pub type DupeKey = (String, String, String);
+
+pub fn dupe_key(call: &str, frequency: f32, mode: &str) -> Option<DupeKey> {
+    let band = Band::new(frequency as i32).band()?.to_string();
+    Some((call.to_uppercase(), band.to_uppercase(), mode.to_uppercase()))
+}
+
+// Key for an imported contact. LogEntry keeps the ADIF band, mode and
+// frequency, read here from its serialized fields; ADIF frequencies
+// are in MHz.
+fn imported_key(entry: &LogEntry) -> Option<DupeKey> {
+    let value = serde_json::to_value(entry).ok()?;
+    let text = |field: &str| -> Option<String> {
+        match value.get(field)? {
+            serde_json::Value::String(s) if !s.is_empty() => Some(s.to_string()),
+            serde_json::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        }
+    };
+    let mode = text("mode")?;
+    match text("band") {
+        Some(band) => Some((entry.call.call().to_uppercase(), band.to_uppercase(), mode.to_uppercase())),
+        None => {
+            let frequency: f32 = text("frequency").or_else(|| text("freq"))?.parse().ok()?;
+            let hz = if frequency < 1000.0 { frequency * 1_000_000.0 } else { frequency };
+            dupe_key(&entry.call.call(), hz, &mode)
+        }
+    }
+}
+
+// Keys for every logged and imported contact, rebuilt when either
+// changes so rows don't scan the logs on each render
+pub fn dupe_keys(entries: &[QsoEntry], import: Option<&[LogEntry]>) -> HashSet<DupeKey> {
+    let mut keys: HashSet<DupeKey> = entries.iter()
+        .filter_map(|entry| dupe_key(&entry.call, entry.frequency, &entry.mode.mode().to_string()))
+        .collect();
+    if let Some(import) = import {
+        let before = keys.len();
+        keys.extend(import.iter().filter_map(imported_key));
+        if keys.len() == before && !import.is_empty() {
+            ConsoleService::log("imported log has no band/mode to check dupes against");
+        }
+    }
+    keys
+}
+
+// True if `call` has already been worked on this band and mode
+pub fn is_dupe(keys: &HashSet<DupeKey>, call: &str, frequency: f32, mode: &Mode) -> bool {
+    match dupe_key(call, frequency, &mode.mode().to_string()) {
+        Some(key) if !call.is_empty() => keys.contains(&key),
+        _ => false,
+    }
+}
+
+pub fn restore_qsos(storage: &StorageService) -> Vec<QsoEntry> {
+    match storage.restore(QSOS_KEY) {
+        Json(Ok(entries)) => entries,
+        Json(Err(_)) => {
+            ConsoleService::log("no logged contacts found");
+            Vec::new()
+        }
+    }
+}
+
+pub fn store_qsos(storage: &mut StorageService, entries: &[QsoEntry]) {
+    storage.store(QSOS_KEY, Json(entries));
+}
+
+// ADIF file containing only the contacts logged in the app
+pub fn export_adif(entries: &[QsoEntry]) -> String {
+    let mut adif = format!("sparksdr-websocket-demo log export\n<ADIF_VER:5>3.1.1 <PROGRAMID:23>sparksdr-websocket-demo <EOH>\n");
+    for entry in entries {
+        adif.push_str(&entry.to_adif());
+    }
+    adif
+}
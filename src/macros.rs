@@ -0,0 +1,131 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+use yew::{ComponentLink};
+use yew::format::{Json};
+use yew::services::storage::{StorageService};
+use yew::services::timeout::{TimeoutService, TimeoutTask};
+use yew::services::{ConsoleService};
+
+use ham_rs::Mode;
+
+use crate::model::{Model,Msg};
+
+const MACROS_KEY: &str = "radio.macros";
+// Longest pause kept between recorded steps (ms), so a macro
+// recorded over a coffee break doesn't replay the wait
+const MAX_STEP_DELAY_MS: f64 = 5000.0;
+
+// A recorded action, replayed through the same messages the UI sends
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum MacroStep {
+    Tune { receiver_id: u32, frequency: f32, mode: String },
+    SetMode { receiver_id: u32, mode: String },
+}
+
+impl MacroStep {
+    fn msg(&self) -> Msg {
+        match self {
+            MacroStep::Tune { receiver_id, frequency, mode } => {
+                Msg::TuneReceiver(*receiver_id, *frequency, Some(Mode::new(mode.to_string())))
+            },
+            MacroStep::SetMode { receiver_id, mode } => Msg::ModeChanged(*receiver_id, Mode::new(mode.to_string())),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Macro {
+    pub name: String,
+    // (ms since the previous step, step)
+    pub steps: Vec<(f64, MacroStep)>,
+}
+
+pub fn restore_macros(storage: &StorageService) -> Vec<Macro> {
+    match storage.restore(MACROS_KEY) {
+        Json(Ok(macros)) => macros,
+        Json(Err(_)) => {
+            ConsoleService::log("no stored macros found");
+            Vec::new()
+        }
+    }
+}
+
+pub fn store_macros(storage: &mut StorageService, macros: &[Macro]) {
+    storage.store(MACROS_KEY, Json(&macros));
+}
+
+// Captures steps while recording is on
+pub struct MacroRecorder {
+    steps: Option<Vec<(f64, MacroStep)>>,
+    last: f64,
+}
+
+impl MacroRecorder {
+    pub fn new() -> MacroRecorder {
+        MacroRecorder { steps: None, last: 0.0 }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.steps.is_some()
+    }
+
+    pub fn start(&mut self) {
+        self.steps = Some(Vec::new());
+        self.last = js_sys::Date::now();
+    }
+
+    // Steps recorded since start
+    pub fn stop(&mut self) -> Vec<(f64, MacroStep)> {
+        self.steps.take().unwrap_or_default()
+    }
+
+    pub fn record(&mut self, step: MacroStep) {
+        if let Some(steps) = &mut self.steps {
+            let now = js_sys::Date::now();
+            let delay = if steps.is_empty() { 0.0 } else { (now - self.last).min(MAX_STEP_DELAY_MS) };
+            steps.push((delay, step));
+            self.last = now;
+        }
+    }
+}
+
+// Replays a macro's steps with their recorded timing
+pub struct MacroPlayer {
+    steps: VecDeque<(f64, MacroStep)>,
+    task: Option<TimeoutTask>,
+}
+
+impl MacroPlayer {
+    pub fn new() -> MacroPlayer {
+        MacroPlayer { steps: VecDeque::new(), task: None }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.task.is_some()
+    }
+
+    pub fn play(&mut self, link: &ComponentLink<Model>, steps: Vec<(f64, MacroStep)>) {
+        self.steps = steps.into_iter().collect();
+        self.schedule(link);
+    }
+
+    pub fn stop(&mut self) {
+        self.task = None;
+        self.steps.clear();
+    }
+
+    // Message for the step that is due, scheduling the one after it
+    pub fn next(&mut self, link: &ComponentLink<Model>) -> Option<Msg> {
+        self.task = None;
+        let (_, step) = self.steps.pop_front()?;
+        self.schedule(link);
+        Some(step.msg())
+    }
+
+    fn schedule(&mut self, link: &ComponentLink<Model>) {
+        if let Some((delay, _)) = self.steps.front() {
+            let callback = link.callback(|_| Msg::MacroNext);
+            self.task = Some(TimeoutService::spawn(Duration::from_millis(delay.max(0.0) as u64), callback));
+        }
+    }
+}
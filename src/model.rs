@@ -8,20 +8,125 @@ use yew::format::{Json};
 use yew::services::reader::{File, FileData, ReaderService, ReaderTask};
 use yew::services::websocket::{WebSocketStatus};
 use yew::services::storage::{Area, StorageService};
-use web_sys::{WebSocket,BinaryType,MessageEvent};
-use std::str;
+use yew::services::interval::{IntervalService, IntervalTask};
+use yew::services::timeout::{TimeoutService, TimeoutTask};
+use yew::services::keyboard::{KeyboardService, KeyListenerHandle};
+use web_sys::{WebSocket,BinaryType,MessageEvent,Element};
+use std::collections::{HashMap,HashSet};
+use std::time::Duration;
+use chrono::{DateTime, Local, Utc};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 
-use ham_rs::{Call,Country,CountryInfo,LogEntry,Mode};
+use ham_rs::{Call,Country,CountryInfo,LogEntry,Mode,Band};
 use ham_rs::lotw::LoTWStatus;
 
 use sparkplug::{Command,CommandResponse,Receiver,Radio,Version,RECEIVER_MODES,Spot};
-use crate::spot::{SpotDB};
-use crate::audio::{AudioProvider};
-use crate::spectrum::{SpectrumProvider};
+use crate::spot::{SpotDB,SpotFilter,SpotKey,is_calling};
+use crate::audio::{AudioProvider,AudioOutputDevice};
+use crate::recorder::{AudioRecorder};
+use crate::spectrum::{SpectrumProvider,SPECTRUM_FRAME};
+use crate::settings::{Settings,ConnectionProfile,NumberFormat,NUMBER_FORMATS,TimeDisplay,SpotClickAction,SPOT_CLICK_ACTIONS,FlagSource,FLAG_SOURCES,TableDensity,TABLE_DENSITIES};
+use crate::i18n::{I18n,Locale,LOCALES};
+use crate::dial::{band_dial,dial_frequency};
+use crate::display::{frequency_digits,format_snr,format_dt,distance_class,DISTANCE_BUCKETS,dt_class,DT_BUCKETS};
+use crate::dxcc::{dxcc_entity,call_prefix};
+use crate::band_plan::{Region,REGIONS,LicenseClass,LICENSE_CLASSES,segment,in_plan};
+use crate::command_log::{CommandLog};
+use crate::replay::{Frame,FrameLog,ReplayPlayer};
+use crate::call_notes::{CallNotes};
+use crate::worked::{WorkedEntities};
+use crate::spot_image;
+use crate::worker::{SpotWorker,WorkerRequest,WorkerResponse};
+use crate::flags;
+use crate::macros::{Macro,MacroStep,MacroRecorder,MacroPlayer,restore_macros,store_macros};
+use crate::commands::{self,CommandError};
+use crate::host_api;
+use crate::solar::{SunTimes,grid_to_lat_lon,sun_times,is_grey_line};
+use crate::keymap::{self,Action,ACTIONS,is_typing};
+use crate::logbook::{QsoEntry,QsoForm,QsoField,DupeKey,restore_qsos,store_qsos,export_adif,dupe_keys,is_dupe};
 
 const LOGBOOK_KEY: &str = "radio.logs";
+// Ask before clearing more spots than this
+const CLEAR_SPOTS_CONFIRM: usize = 25;
+// How long an error toast stays on screen
+const TOAST_SECONDS: u64 = 8;
+// Incoming spots are rendered at most this often
+const SPOT_RENDER_MS: u64 = 250;
+// Minimum time between manual refreshes
+const REFRESH_DEBOUNCE_MS: f64 = 2000.0;
+// Reconnect attempts after a dropped connection back off from this,
+// doubling each time up to RECONNECT_MAX_MS
+const RECONNECT_BASE_MS: u64 = 1000;
+const RECONNECT_MAX_MS: u64 = 30000;
+// Mode changes are sent once the mode has been left alone this long
+const MODE_SETTLE_MS: u64 = 300;
+// Band stack changes are stored once tuning has stopped this long
+const BAND_STACK_STORE_MS: u64 = 2000;
+// Frequency/mode changes not confirmed within this long are reverted
+const CHANGE_TIMEOUT_MS: u64 = 5000;
+// Smallest +/- range (Hz) drawn around the carrier in the passband view
+const PASSBAND_MIN_SPAN_HZ: f32 = 3000.0;
+// Viewports that get the frequency keypad
+const KEYPAD_MEDIA_QUERY: &str = "(pointer: coarse), (max-width: 768px)";
+// Spots decoded this recently are highlighted, the highlight fades
+// out once they're older. Decodes reach us several seconds after
+// their decode time, so this covers a full FT8 cycle.
+const NEW_SPOT_SECONDS: i64 = 20;
+// Spots older than this are dimmed
+const OLD_SPOT_MINUTES: i64 = 10;
+// Spot limit choices offered in settings, 0 for no limit
+const SPOT_LIMITS: [usize; 5] = [0, 50, 100, 250, 500];
+// Most spot markers drawn over the band scope, newest first
+const SCOPE_MARKERS: usize = 40;
+// Clicking or dragging on the band scope tunes in steps of this (Hz)
+const SCOPE_TUNE_STEP_HZ: f64 = 10.0;
+// While dragging on the band scope a frequency is sent at most this often
+const SCOPE_TUNE_MS: u64 = 100;
+// Spots drawn in the exported spot table image
+const SPOT_IMAGE_ROWS: usize = 40;
+
+// Receivers are looked up by id everywhere, always taking the first
+// match, so a repeated id (which SparkSDR shouldn't send) would leave
+// the later entry shown but never updated. Keep the first of each id
+// and log the rest.
+fn dedupe_receivers(receivers: Vec<Receiver>) -> Vec<Receiver> {
+    let mut seen = HashSet::new();
+    let mut duplicates = Vec::new();
+    let receivers: Vec<Receiver> = receivers.into_iter()
+        .filter(|receiver| {
+            let first = seen.insert(receiver.id);
+            if !first {
+                duplicates.push(receiver.id);
+            }
+            first
+        })
+        .collect();
+    if !duplicates.is_empty() {
+        ConsoleService::error(&format!("receiver list repeats ids {:?}, keeping the first of each", duplicates));
+    }
+    receivers
+}
+
+// Remove a socket's event handlers so none run after it's replaced
+fn detach_socket(ws: &WebSocket) {
+    ws.set_onopen(None);
+    ws.set_onerror(None);
+    ws.set_onclose(None);
+    ws.set_onmessage(None);
+}
+
+// `?audio=off` in the page url runs without audio, for embedding
+fn no_audio_requested() -> bool {
+    web_sys::window()
+        .and_then(|window| window.location().search().ok())
+        .map(|search| search.trim_start_matches('?').split('&').any(|param| param == "audio=off"))
+        .unwrap_or(false)
+}
+// Spots shown in the mini mode ticker
+const MINI_SPOTS: usize = 5;
+// Audio latency choices offered in settings (seconds)
+const TARGET_LATENCIES: [f64; 6] = [0.05, 0.1, 0.2, 0.3, 0.5, 1.0];
 
 pub struct Model {
     pub route_service: RouteService<()>,
@@ -32,34 +137,226 @@ pub struct Model {
     // SparkSDR connection
     pub ws_location: String,
     wss: Option<WebSocket>,
+    // Socket open/error/close and message handlers, kept rather than
+    // forgotten so they're freed when the next connection replaces them
+    ws_handlers: Vec<Closure<dyn FnMut(JsValue)>>,
+    ws_onmessage: Option<Closure<dyn FnMut(MessageEvent)>>,
+    // Whether the current connection attempt ever opened
+    ws_opened: bool,
+    // Profile being added/edited and the index it will replace
+    profile_form: ConnectionProfile,
+    editing_profile: Option<usize>,
+    // Profile whose frequency/mode is applied once receivers arrive
+    pending_profile: Option<usize>,
+    // Set when a wss:// connection failed before opening, likely
+    // an untrusted (self-signed) certificate. Holds the https url
+    // to visit to accept it.
+    certificate_hint: Option<String>,
 
     // List of receivers from getReceivers command
     receivers: Vec<Receiver>,
     // List of radios from the getRadios command
     radios: Vec<Radio>,
+    // A getRadios response arrived on this connection, to tell "no
+    // radios configured" apart from "not fetched yet"
+    radios_received: bool,
+    // When receivers/radios/version were last refreshed by hand (ms)
+    last_refresh: f64,
     // Version response from the getVersion command
     version: Option<Version>,
+    // Expanded "about server" card in the footer
+    show_server_info: bool,
     // Currently selected receiver
     default_receiver: Option<u32>,
+    // Receiver to play audio from, None follows default_receiver
+    audio_receiver: Option<u32>,
+    // Receiver the user marked as their transmit VFO. Local to this
+    // page only, SparkSDR doesn't report or accept the transmit VFO.
+    marked_receiver: Option<u32>,
+    // Split set up from a spot (receiver id, transmit frequency)
+    split: Option<(u32, f32)>,
+    call_notes: CallNotes,
+    // Note being edited (call, note)
+    note_form: Option<(String, String)>,
+    // Receiver nickname being edited (receiver id, name)
+    name_form: Option<(u32, String)>,
+    keypad: Option<Keypad>,
+    // Web worker for heavy processing (log imports)
+    worker: SpotWorker,
+    // Audio is never set up, from settings.no_audio or the page url
+    pub no_audio: bool,
+    // Band the spot table is limited to, from the header counts
+    band_filter: Option<String>,
+    // Receiver the spot table is limited to
+    pub receiver_filter: Option<u32>,
+    // Spots selected for bulk actions
+    selected_spots: HashSet<SpotKey>,
+    // Blocklist entry being added in settings
+    new_block: String,
+    // Mode changes not yet sent, by receiver, and the settle timer
+    pending_modes: HashMap<u32, Mode>,
+    mode_settle: Option<TimeoutTask>,
+    // Pending store of settings after band stack changes
+    band_stack_store: Option<TimeoutTask>,
+    // Latest band scope position while dragging, (receiver, frequency)
+    scope_target: Option<(u32, f32)>,
+    scope_settle: Option<TimeoutTask>,
+    // Receiver being tuned from the band scope until the button is released
+    scope_dragging: Option<u32>,
+    // Frequency/mode last confirmed by SparkSDR for each receiver, and
+    // the revert timeout of an unconfirmed change
+    confirmed: HashMap<u32, (f32, Mode)>,
+    in_flight: HashMap<u32, TimeoutTask>,
+    // Receiver selection and audio state from before a dropped
+    // connection, restored once the receiver list is fetched again
+    reconnect_receiver: Option<u32>,
+    reconnect_audio: bool,
+    // When the connection dropped, receivers/radios/spots shown
+    // since then may be out of date
+    connection_lost_at: Option<DateTime<Utc>>,
+    // Automatic reconnects, armed once connected and disarmed by a
+    // user disconnect or connecting to another address. Attempts go
+    // to the address last connected to.
+    reconnect_enabled: bool,
+    reconnect_attempts: u32,
+    reconnect_task: Option<TimeoutTask>,
+    reconnect_at: Option<DateTime<Utc>>,
+    last_location: Option<String>,
+    // Receivers whose mode is kept when changing bands
+    pinned_modes: Vec<u32>,
+    // Receivers with tuning locked
+    locked: HashSet<u32>,
 
     pub spots: SpotDB,
     pub audio: AudioProvider,
     pub spectrum: SpectrumProvider,
+    pub recorder: AudioRecorder,
+    // User preferences (persisted)
+    pub settings: Settings,
+    // UI strings for the selected locale
+    i18n: I18n,
+
+    // Periodic local UI updates (clock)
+    ticks: Option<IntervalTask>,
+    // Current time as of the last tick
+    now: DateTime<Utc>,
+    // Pending render for spots that arrived since the last one
+    spot_render: Option<TimeoutTask>,
+    // Spot row at the top of the viewport and its offset, kept in
+    // place when new spots are rendered above it
+    scroll_anchor: Option<(String, f64)>,
+    spot_table: NodeRef,
+    // Error messages shown to the user (id, message, task that
+    // removes it after TOAST_SECONDS)
+    toasts: Vec<(u32, String, TimeoutTask)>,
+    next_toast: u32,
+
+    // Lookup site being added in settings (name, url)
+    new_lookup_provider: (String, String),
+    // Waiting on the user to confirm clearing the spot table
+    confirm_clear_spots: bool,
+    // Open spot context menu and its Escape key listener
+    spot_menu: Option<SpotMenu>,
+    spot_menu_keys: Option<KeyListenerHandle>,
+    // Global keyboard shortcut listener
+    shortcut_keys: Option<KeyListenerHandle>,
+    show_shortcut_help: bool,
+
+    // Sent commands paired with their responses (debug panel)
+    pub command_log: CommandLog,
+    pub show_command_log: bool,
+    // Raw text frames received, exportable for replay
+    pub frame_log: FrameLog,
+    pub replay: ReplayPlayer,
+    // Recorded sequences of tunes and mode changes
+    macros: Vec<Macro>,
+    macro_recorder: MacroRecorder,
+    pub macro_player: MacroPlayer,
 
     // Show/Hide receiver list
     show_receiver_list: bool,
     // Imported log file (ADIF format) for spot cross checking
     pub import: Option<Vec<LogEntry>>,
+    // Contacts logged from within the app
+    pub log_entries: Vec<QsoEntry>,
+    // Worked (call, band, mode) from log_entries and the import
+    dupe_keys: HashSet<DupeKey>,
+    // Open quick log form
+    qso_form: Option<QsoForm>,
+    // Settings file read for import, waiting on merge/replace
+    settings_import: Option<SettingsExport>,
+    // Callsign of a duplicate contact we just tuned to
+    dupe_warning: Option<String>,
     // Services for file importing (log file)
     reader: ReaderService,
     tasks: Vec<ReaderTask>,
 }
 
+#[derive(Clone, Copy, Debug)]
+pub enum ProfileField {
+    Label,
+    Url,
+    Frequency,
+    Mode,
+}
+
+// Everything persisted, as written to an exported settings file
+#[derive(Serialize, Deserialize)]
+pub struct SettingsExport {
+    settings: Settings,
+    #[serde(default)]
+    spot_filters: Vec<SpotFilter>,
+    #[serde(default)]
+    call_notes: CallNotes,
+    #[serde(default)]
+    qsos: Vec<QsoEntry>,
+    #[serde(default)]
+    macros: Vec<Macro>,
+    #[serde(default)]
+    blocklist: Vec<String>,
+    #[serde(default)]
+    worked: WorkedEntities,
+}
+
+// On screen frequency entry for touch screens
+pub struct Keypad {
+    receiver_id: u32,
+    entry: String,
+    // Entry is in MHz, otherwise kHz
+    mhz: bool,
+}
+
+pub enum KeypadKey {
+    Digit(char),
+    Point,
+    Backspace,
+    Clear,
+    ToggleUnit,
+}
+
+// Right click menu for a spot row
+pub struct SpotMenu {
+    call: String,
+    frequency: f32,
+    // Frequency of the signal itself (dial + audio offset)
+    signal: f32,
+    mode: Mode,
+    // Position of the menu (client coordinates)
+    x: i32,
+    y: i32,
+}
+
 // Currently this is unused as there is only one route: /
 #[derive(Clone,Switch, Debug)]
 pub enum AppRoute {
     #[to = "/map"]
     Map,
+    #[to = "/settings"]
+    Settings,
+    // Compact default receiver control and spot ticker for a small
+    // window or dashboard
+    #[to = "/mini"]
+    Mini,
     #[to = "/"]
     Index,
 }
@@ -81,14 +378,28 @@ pub enum Msg {
 
     // Websocket connection
     Connect,
+    // User asked to disconnect
+    Disconnect,
     Disconnected,
+    // Scheduled reconnect after a dropped connection
+    Reconnect,
     Connected,
     UpdateWebsocketAddress(String),
+    // Connection profiles
+    ConnectProfile(usize),
+    EditProfile(usize),
+    UpdateProfileForm(ProfileField, String),
+    SaveProfile,
+    DeleteProfile(usize),
 
     // Command responses from SparkSDR (e.g. getReceiversResponse, getVersionResponse)
     CommandResponse(Result<CommandResponse, Error>),
-    // Audio/Spectrum data
+    // A frequency/mode change went unconfirmed too long
+    RevertChange(u32),
+    // Audio data
     ReceivedAudio(js_sys::ArrayBuffer),
+    // Spectrum (FFT) data, binary frames of type SPECTRUM_FRAME
+    ReceivedSpectrum(js_sys::ArrayBuffer),
 
     // The following Msg will result in commands
     // being sent to SparkSDR
@@ -98,8 +409,75 @@ pub enum Msg {
     FrequencyDown(u32, i32), // digit 0 - 8
     // Request change to receiver mode
     ModeChanged(u32, Mode),
+    // Tune from the host page API, None keeps the current mode
+    TuneReceiver(u32, f32, Option<Mode>),
+    // Send mode changes that have settled
+    SendModeChanges,
+    // Store band stack changes once tuning stops
+    StoreBandStack,
+    // Keep a receivers mode when changing bands
+    TogglePinnedMode(u32),
+    // Ignore frequency up/down for a receiver
+    ToggleFreqLock(u32),
+    // Tune to the digital mode dial frequency on the current band
+    SnapToDial(u32),
+    // Band picked for a receiver, back to where it was last on the band
+    SelectBand(u32, String),
+    // Spot row clicked (call, tuned frequency, mode)
+    SpotClicked(String, f32, Mode),
+    // Band scope clicked or dragged, position across its width (0.0 - 1.0)
+    ScopeTune(f64),
+    SendScopeTune,
+    // Mouse button released over (or dragged off) the band scope
+    ScopeTuneDone,
+    // Perform an action on a spot (action, call, tuned frequency, mode)
+    SpotAction(SpotClickAction, String, f32, Mode),
+    // Spot context menu (call, tuned frequency, mode, x, y)
+    // (call, dial frequency, signal frequency, mode, x, y)
+    ShowSpotMenu(String, f32, f32, Mode, i32, i32),
+    // Work a spot split, transmitting on its signal frequency
+    SplitToSpot(f32),
+    // Hide spots from a callsign or prefix ("VK*")
+    BlockCall(String),
+    // Add/remove a call from the highlight words
+    ToggleHighlightCall(String),
+    UnblockCall(String),
+    UpdateNewBlock(String),
+    // Note shown on every spot for a callsign
+    EditCallNote(String),
+    UpdateCallNote(String),
+    SaveCallNote,
+    CancelCallNote,
+    // Local receiver nicknames
+    EditReceiverName(u32),
+    UpdateReceiverName(String),
+    SaveReceiverName,
+    CancelReceiverName,
+    // Frequency keypad (touch screens)
+    OpenKeypad(u32),
+    KeypadPress(KeypadKey),
+    ConfirmKeypad,
+    CloseKeypad,
+    ClearSplit,
+    HideSpotMenu,
+    // Keep a spot at the top of the table
+    TogglePinSpot(SpotKey),
+    DismissToast(usize),
+    // A toast's time is up
+    ExpireToast(u32),
+    // Quick log form (call, frequency, mode)
+    OpenQsoForm(String, f32, Mode),
+    UpdateQsoForm(QsoField, String),
+    LogQso,
+    CancelQso,
+    DismissDupeWarning,
     // Request to add a receiver to a radio
     AddReceiver(u32),
+    // Ask SparkSDR for its radios (and receivers) again
+    RefreshRadios,
+    // Re-query receivers, radios and version
+    Refresh,
+    ToggleServerInfo,
     // Request to remove a receiver
     RemoveReceiver(u32),
     // Toggle radio power state
@@ -114,21 +492,152 @@ pub enum Msg {
     // Local only messages
 
     ToggleReceiverList,
+    ToggleRadioList,
+    // Key pressed outside of a form field
+    Shortcut(String),
+    SetShortcut(Action, String),
+    ToggleShortcutHelp,
+    // Command/response debug panel
+    ToggleCommandLog,
+    ClearCommandLog,
+    // Periodic local update (see enable_ticks)
+    Tick,
     None,
     // Log file import (adif file format)
     Files(Vec<File>, Chunks),
     Loaded(FileData),
     CancelImport,
+    // Forget all worked countries/states
+    ClearWorked,
+    // Forget a single worked country/state
+    UnmarkWorkedCountry(String),
+    UnmarkWorkedState(String),
     ConfirmImport,
     // Control for client playback/volume
     SetGain(f32),
     MuteUnmute,
+    // Play audio from a receiver, None follows the selected receiver
+    SetAudioReceiver(Option<u32>),
+    // Macro recording and playback
+    ToggleMacroRecording,
+    PlayMacro(usize),
+    StopMacro,
+    MacroNext,
+    RenameMacro(usize, String),
+    DeleteMacro(usize),
+    // Mark/unmark a receiver as the user's transmit VFO (local only)
+    ToggleMarkedReceiver(u32),
+    // Stereo position of a receivers audio (-1.0 to 1.0)
+    SetPan(u32, f32),
+    // Record received audio to a downloadable file
+    ToggleRecording,
+    RecordedAudio(web_sys::Blob),
+    RecordingStopped,
+    // Noise squelch
+    ToggleSquelch,
+    SetSquelchLevel(f32),
+    // Audio scheduling lead in seconds
+    SetTargetLatency(f64),
+    UpdateSquelch,
     ClearSpots,
+    // Clear after confirmation, true keeps pinned spots
+    ConfirmClearSpots(bool),
+    // Show only spots on a band (None for all bands)
+    SetBandFilter(Option<String>),
+    // Show only spots heard by a receiver (None for all receivers)
+    SetReceiverFilter(Option<u32>),
+    // Ctrl/shift click selection for bulk actions
+    ToggleSpotSelection(SpotKey),
+    ClearSpotSelection,
+    LogSelectedSpots,
+    // Download the selected spots as ADIF
+    ExportSelectedSpots,
+    // Download the logged contacts as ADIF
+    ExportLog,
+    // Download the spot table as a PNG
+    ExportSpotsImage,
+    CancelClearSpots,
+    // Settings
+    SetNumberFormat(NumberFormat),
+    SetLocale(Locale),
+    SetTimeDisplay(TimeDisplay),
+    SetHighlightTokens(String),
+    SetMyCall(String),
+    SetMyGrid(String),
+    ToggleAlertOnCall,
+    SetSpotClickAction(SpotClickAction),
+    SetFlagSource(FlagSource),
+    SetTableDensity(TableDensity),
+    // Transverter LO offset for a receiver (receiver id, Hz)
+    SetTransverterOffset(u32, f64),
+    ToggleSpotFade,
+    // Seconds between ticks (0 disables)
+    SetTickInterval(u64),
+    SetSpotLimit(usize),
+    // Spot expiry in minutes, 0 is off
+    SetSpotMaxAge(i64),
+    ToggleDxccColumn,
+    // Run without audio from the next load
+    ToggleNoAudio,
+    // Spot merge tolerances (Hz, seconds)
+    SetMergeFrequency(f32),
+    SetMergeSeconds(i64),
+    // Distance color bucket upper bound (bucket, km)
+    SetDistanceThreshold(usize, f64),
+    // Time offset quality bucket upper bound (bucket, seconds)
+    SetDtThreshold(usize, f64),
+    // Mode used on a band (band, mode)
+    SetBandMode(String, String),
+    SetBandPlanRegion(Region),
+    SetLicenseClass(LicenseClass),
+    TogglePrivilegeOverride,
+    // Spot row color for a mode (mode, color)
+    SetModeColor(String, String),
+    // Callsign lookup sites
+    SetLookupProvider(usize),
+    UpdateNewLookupProvider(String, String),
+    AddLookupProvider,
+    RemoveLookupProvider(usize),
+    // Settings export/import
+    SettingsFile(File),
+    SettingsFileLoaded(FileData),
+    // Text frame from the websocket, recorded then handled as a
+    // CommandResponse
+    TextFrame(String),
+    // Replay a recorded text frame without a live connection
+    ReplayFrame(String),
+    ReplayNext,
+    ReplayFile(File),
+    ReplayFileLoaded(FileData),
+    // Download the received frames as a recording
+    ExportFrames,
+    // Result of work handed to the web worker
+    WorkerDone(WorkerResponse),
+    // Playback speed, 0 plays without delays
+    PlayReplay(f64),
+    PauseReplay,
+    UnloadReplay,
+    // Download the settings backup
+    ExportSettings,
+    ApplySettingsImport(bool), // true to merge, false to replace
+    CancelSettingsImport,
+    // List of available audio outputs
+    AudioOutputDevices(Vec<AudioOutputDevice>),
+    SetAudioOutputDevice(String),
+    // Resume audio context after a user gesture
+    ResumeAudio,
+    AudioResumed,
+    // Audio context suspended/resumed by the browser
+    AudioStateChanged,
+    // Tab shown again after being in the background
+    PageVisible,
 
     // Spot messages
 
     // Response to our callsign info request
     CallsignInfoReady(Result<Call,Error>),
+    // Render spots batched up by schedule_spot_render
+    RenderSpots,
     // Response to our LoTW users request
     LotwUsers(String),
     // States geoJson data
@@ -162,30 +671,115 @@ impl Model {
                 _ => None
             };
 
-        let spot_db = SpotDB::new();
+        let mut spot_db = SpotDB::new();
         spot_db.update_states_overlay_js();
+        // worked entities started out as just the imported log
+        if let (Some(import), true) = (&entries, spot_db.worked().is_empty()) {
+            spot_db.add_worked_log(import);
+        }
+        let settings = Settings::restore(&storage);
+        spot_db.set_merge_tolerance(settings.merge_frequency, settings.merge_seconds);
+        let log_entries = restore_qsos(&storage);
+        let call_notes = CallNotes::restore(&storage);
+        let macros = restore_macros(&storage);
+        let no_audio = settings.no_audio || no_audio_requested();
+        let worker = SpotWorker::new(&link);
 
-        let model = Model {
+        let mut model = Model {
             route_service,
             route,
             storage,
             link,
-            ws_location: "ws://localhost:4649/Spark".to_string(),
+            ws_location:
+                match settings.last_profile() {
+                    Some(profile) => profile.url.to_string(),
+                    None => "ws://localhost:4649/Spark".to_string(),
+                },
             wss: None,
+            ws_handlers: Vec::new(),
+            ws_onmessage: None,
+            ws_opened: false,
+            profile_form: ConnectionProfile::default(),
+            editing_profile: None,
+            pending_profile: None,
+            certificate_hint: None,
             receivers: Vec::new(),
             radios: Vec::new(),
+            radios_received: false,
+            show_server_info: false,
+            last_refresh: 0.0,
             default_receiver: None,
+            audio_receiver: None,
+            marked_receiver: None,
+            split: None,
+            call_notes,
+            note_form: None,
+            name_form: None,
+            keypad: None,
+            new_block: String::new(),
+            selected_spots: HashSet::new(),
+            band_filter: None,
+            receiver_filter: None,
+            pending_modes: HashMap::new(),
+            mode_settle: None,
+            band_stack_store: None,
+            scope_target: None,
+            scope_settle: None,
+            scope_dragging: None,
+            confirmed: HashMap::new(),
+            in_flight: HashMap::new(),
+            reconnect_receiver: None,
+            reconnect_audio: false,
+            connection_lost_at: None,
+            reconnect_enabled: false,
+            reconnect_attempts: 0,
+            reconnect_task: None,
+            reconnect_at: None,
+            last_location: None,
+            pinned_modes: Vec::new(),
+            locked: HashSet::new(),
             version: None,
             spots: spot_db,
             audio: AudioProvider::new(),
             spectrum: SpectrumProvider::new(),
+            recorder: AudioRecorder::new(),
+            i18n: I18n::new(settings.locale),
+            settings,
+            ticks: None,
+            now: Utc::now(),
+            spot_render: None,
+            scroll_anchor: None,
+            spot_table: NodeRef::default(),
+            toasts: Vec::new(),
+            next_toast: 0,
+            new_lookup_provider: (String::new(), String::new()),
+            confirm_clear_spots: false,
+            spot_menu: None,
+            spot_menu_keys: None,
+            shortcut_keys: None,
+            show_shortcut_help: false,
+            command_log: CommandLog::new(),
+            show_command_log: false,
+            frame_log: FrameLog::new(),
+            replay: ReplayPlayer::new(),
+            no_audio,
+            worker,
+            macros,
+            macro_recorder: MacroRecorder::new(),
+            macro_player: MacroPlayer::new(),
             show_receiver_list: false,
             import: entries,
+            log_entries,
+            dupe_keys: HashSet::new(),
+            qso_form: None,
+            settings_import: None,
+            dupe_warning: None,
             reader: ReaderService::new(),
             tasks: Vec::new(),
         };
 
         model.update_state_map_overlay();
+        model.update_dupe_keys();
         model
     }
 
@@ -211,11 +805,40 @@ impl Model {
     }
 
     // CommandResponse: getReceiversResponse
+    //
+    // The default receiver follows the list: with no valid default the
+    // first receiver (the only one, usually) becomes the default, and
+    // if the default went away it falls back to the first or None.
+    // After a reconnect the previously selected receiver is kept if
+    // the server still has it
     pub fn set_receivers(&mut self, receivers: Vec<Receiver>) {
+        let receivers = dedupe_receivers(receivers);
+        self.confirmed = receivers.iter().map(|r| (r.id, (r.frequency, r.mode.clone()))).collect();
+        self.in_flight.clear();
         self.receivers = receivers;
+        self.connection_lost_at = None;
+        if self.reconnect_receiver.is_some() {
+            // resubscribe from scratch, server side subscriptions
+            // were lost with the old connection
+            self.default_receiver = None;
+        }
         match self.default_receiver {
             None => {
-                self.set_default_receiver(Some(self.receivers[0].id));
+                let previous = self.reconnect_receiver.take()
+                    .filter(|id| self.receivers.iter().any(|r| r.id == *id));
+                if let Some(receiver_id) = previous.or(self.receivers.first().map(|r| r.id)) {
+                    self.set_default_receiver(Some(receiver_id));
+                    if self.reconnect_audio {
+                        self.subscribe_to_audio();
+                    }
+                }
+                self.reconnect_audio = false;
+                self.apply_pending_profile();
+            },
+            Some(receiver_id) if !self.receivers.iter().any(|r| r.id == receiver_id) => {
+                // selected receiver was removed
+                let first = self.receivers.first().map(|r| r.id);
+                self.set_default_receiver(first);
             },
             _ => ()
         }
@@ -235,8 +858,35 @@ impl Model {
     }
 
     // CommandResponse: getRadioResponse
+    //
+    // Note: the protocol does not currently report transmit power or
+    // SWR (neither Radio nor any CommandResponse carries them), so no
+    // tx meter is shown. Add one here if SparkSDR starts sending them.
     pub fn set_radios(&mut self, radios: Vec<Radio>) {
         self.radios = radios;
+        self.radios_received = true;
+    }
+
+    // Re-sync with SparkSDR after the rig setup changed there,
+    // ignored if the last refresh was very recent
+    pub fn refresh(&mut self) {
+        let now = js_sys::Date::now();
+        if now - self.last_refresh < REFRESH_DEBOUNCE_MS {
+            return;
+        }
+        self.last_refresh = now;
+        self.send_command(Command::GetReceivers);
+        self.send_command(Command::GetRadios);
+        self.send_command(Command::GetVersion);
+    }
+
+    pub fn toggle_server_info(&mut self) {
+        self.show_server_info = !self.show_server_info;
+    }
+
+    pub fn refresh_radios(&mut self) {
+        self.send_command(Command::GetRadios);
+        self.send_command(Command::GetReceivers);
     }
 
     // CommandResponse: getVersionResponse
@@ -245,313 +895,1939 @@ impl Model {
     }
 
     // CommandResponse: ReceiverResponse
+    //
+    // SparkSDR pushes these whenever a receiver changes (including
+    // changes made in SparkSDR itself), so no polling is needed. A
+    // push for a receiver we don't know about means the list changed,
+    // refetch it.
     pub fn update_receiver(&mut self, receiver_id: u32, mode: Mode, frequency: f32, filter_low: f32, filter_high: f32) {
+        self.in_flight.remove(&receiver_id);
+        self.confirmed.insert(receiver_id, (frequency, mode.clone()));
         if let Some(index) = self.receivers.iter().position(|i| i.id == receiver_id) {
             self.receivers[index].frequency = frequency;
-            self.receivers[index].mode = mode;
+            // a mode change waiting to be sent wins over the old mode
+            self.receivers[index].mode = self.pending_modes.get(&receiver_id).cloned().unwrap_or(mode);
             self.receivers[index].filter_low = filter_low;
             self.receivers[index].filter_high = filter_high;
             let receiver = self.receivers[index].clone();
+            host_api::emit("frequency", &host_api::FrequencyEvent::new(&receiver));
 
             let js = &format!("initWaterfallNav(\"{}\", {}, {}, {});", receiver.mode.mode(), receiver.frequency, receiver.filter_high, receiver.filter_low);
             ConsoleService::log(&format!("js: {}", js));
             js_sys::eval(&js).unwrap();
         } else {
-            ConsoleService::error(&format!("Attempted to update a receiver that does not exist: {}", receiver_id));
+            ConsoleService::log(&format!("update for unknown receiver {}, refreshing receiver list", receiver_id));
+            self.send_command(Command::GetReceivers);
         }
     }
 
+    // The mode is shown right away, but SetMode is only sent once
+    // changes stop for MODE_SETTLE_MS so cycling through the mode list
+    // (or a band change that also sets a mode) sends just the last one.
+    // Band modes and profiles are free text in settings, so the mode is
+    // checked before it's shown rather than when SetMode is sent.
     pub fn change_receiver_mode(&mut self, receiver_id: u32, mode: Mode) {
+        if let Err(err) = commands::set_mode(&self.receivers, receiver_id, mode.clone()) {
+            self.show_error(format!("not sent: {}", err));
+            return;
+        }
         if let Some(index) = self.receivers.iter().position(|i| i.id == receiver_id) {
             self.receivers[index].mode = mode.clone();
-            self.send_command(Command::SetMode { mode: mode.clone(), id: receiver_id });
+            self.pending_modes.insert(receiver_id, mode);
+            let callback = self.link.callback(|_| Msg::SendModeChanges);
+            self.mode_settle = Some(TimeoutService::spawn(Duration::from_millis(MODE_SETTLE_MS), callback));
         }
     }
 
-    pub fn frequency_up(&mut self, receiver_id: u32, digit: i32) {
-        if let Some(index) = self.receivers.iter().position(|i| i.id == receiver_id) {
-            if digit == 0 { self.receivers[index].frequency += 100000000.0 }
-            if digit == 1 { self.receivers[index].frequency += 10000000.0 }
-            if digit == 2 { self.receivers[index].frequency += 1000000.0 }
-            if digit == 3 { self.receivers[index].frequency += 100000.0 }
-            if digit == 4 { self.receivers[index].frequency += 10000.0 }
-            if digit == 5 { self.receivers[index].frequency += 1000.0 }
-            if digit == 6 { self.receivers[index].frequency += 100.0 }
-            if digit == 7 { self.receivers[index].frequency += 10.0 }
-            if digit == 8 { self.receivers[index].frequency += 1.0 }
+    // True while a frequency/mode change is shown but not yet
+    // confirmed by SparkSDR
+    pub fn is_change_pending(&self, receiver_id: u32) -> bool {
+        self.in_flight.contains_key(&receiver_id) || self.pending_modes.contains_key(&receiver_id)
+    }
 
-            self.send_command(Command::SetFrequency { frequency: (self.receivers[index].frequency as i32).to_string(), id: receiver_id });
+    fn track_change(&mut self, cmd: &Command) {
+        match cmd {
+            // timed from the first unconfirmed change
+            Command::SetFrequency { id, .. } | Command::SetMode { id, .. } if !self.in_flight.contains_key(id) => {
+                let receiver_id = *id;
+                let callback = self.link.callback(move |_| Msg::RevertChange(receiver_id));
+                self.in_flight.insert(receiver_id, TimeoutService::spawn(Duration::from_millis(CHANGE_TIMEOUT_MS), callback));
+            },
+            _ => (),
         }
     }
 
-    pub fn frequency_down(&mut self, receiver_id: u32, digit: i32) {
-        if let Some(index) = self.receivers.iter().position(|i| i.id == receiver_id) {
-            if digit == 0 { self.receivers[index].frequency -= 100000000.0 }
-            if digit == 1 { self.receivers[index].frequency -= 10000000.0 }
-            if digit == 2 { self.receivers[index].frequency -= 1000000.0 }
-            if digit == 3 { self.receivers[index].frequency -= 100000.0 }
-            if digit == 4 { self.receivers[index].frequency -= 10000.0 }
-            if digit == 5 { self.receivers[index].frequency -= 1000.0 }
-            if digit == 6 { self.receivers[index].frequency -= 100.0 }
-            if digit == 7 { self.receivers[index].frequency -= 10.0 }
-            if digit == 8 { self.receivers[index].frequency -= 1.0 }
-
-            self.send_command(Command::SetFrequency { frequency: (self.receivers[index].frequency as i32).to_string(), id: receiver_id });
+    // Put a receiver whose change went unanswered for CHANGE_TIMEOUT_MS
+    // back to the last confirmed frequency/mode, true if it was reverted
+    pub fn revert_unconfirmed_change(&mut self, receiver_id: u32) -> bool {
+        if self.in_flight.remove(&receiver_id).is_none() {
+            return false;
+        }
+        if let (Some(index), Some((frequency, mode))) = (self.receivers.iter().position(|i| i.id == receiver_id), self.confirmed.get(&receiver_id)) {
+            self.receivers[index].frequency = *frequency;
+            self.receivers[index].mode = mode.clone();
         }
+        self.show_error(format!("receiver {} did not confirm the change, reverted", receiver_id));
+        true
     }
 
-    // Two channels for the websocket connection
-    // 1) Text: Json encoded messages for control/info (e.g. get/set frequency)
-    // 2) Binary: Binary encoded audio data
-    //
-    // Both channels are bi-directional (e.g. transmit using binary encoded audio)
-    // 
-    pub fn connect(&mut self, ws: &str) {
-        let ws = WebSocket::new(ws).unwrap();
-        ws.set_binary_type(BinaryType::Arraybuffer);
-
-		let cbnot = self.link.callback(|input| {
-			match input {
-				WebSocketStatus::Closed | WebSocketStatus::Error => {
-					Msg::Disconnected
-                },
-                WebSocketStatus::Opened => {
-                    Msg::Connected
-                }
-			}
-        });
-        
-        let notify = cbnot.clone();
-        let onopen_callback = Closure::wrap(Box::new(move |_| {
-            ConsoleService::log("rig control: connection opened");
-            notify.emit(WebSocketStatus::Opened);
-        }) as Box<dyn FnMut(JsValue)>);
-        ws.set_onopen(Some(onopen_callback.as_ref().unchecked_ref()));
-        onopen_callback.forget();
+    pub fn send_mode_changes(&mut self) {
+        self.mode_settle = None;
+        let pending: Vec<(u32, Mode)> = self.pending_modes.drain().collect();
+        for (receiver_id, mode) in pending {
+            self.send_checked(commands::set_mode(&self.receivers, receiver_id, mode));
+        }
+    }
 
-        let notify = cbnot.clone();
-        let onerror_callback = Closure::wrap(Box::new(move |_| {
-            ConsoleService::error("rig control: connection error");
-            notify.emit(WebSocketStatus::Error);
-        }) as Box<dyn FnMut(JsValue)>);
-        ws.set_onerror(Some(onerror_callback.as_ref().unchecked_ref()));
-        onerror_callback.forget();
+    pub fn toggle_pinned_mode(&mut self, receiver_id: u32) {
+        match self.pinned_modes.iter().position(|i| *i == receiver_id) {
+            Some(index) => { self.pinned_modes.remove(index); },
+            None => self.pinned_modes.push(receiver_id),
+        }
+    }
 
-        let notify = cbnot.clone();
-        let onclose_callback = Closure::wrap(Box::new(move |_| {
-            ConsoleService::error("rig control: connection closed");
-            notify.emit(WebSocketStatus::Closed);
-        }) as Box<dyn FnMut(JsValue)>);
-        ws.set_onclose(Some(onclose_callback.as_ref().unchecked_ref()));
-        onclose_callback.forget();
+    // Transmit on `frequency` while the selected receiver's dial stays
+    // put. SparkSDR has no split/RIT command in the websocket protocol
+    // yet, so the split is shown on the receiver (frequency and audio
+    // offset) for setting up the transmit side.
+    pub fn split_to_spot(&mut self, frequency: f32) {
+        self.split = self.default_receiver.map(|receiver_id| (receiver_id, frequency));
+    }
 
-        let cbout = self.link.callback(|data| {
-            match data {
-                WebsocketMsgType::BinaryMsg(binary) => {
-                    Msg::ReceivedAudio(binary)
-                },
-                WebsocketMsgType::TextMsg(text) => {
-                    let Json(data): Json<Result<CommandResponse, _>> = Json::from(Ok(text));
-                    Msg::CommandResponse(data)
-                }
-            }
-        });
+    pub fn clear_split(&mut self) {
+        self.split = None;
+    }
 
-        let onmessage_callback = Closure::wrap(Box::new(move |e: MessageEvent| {
-            if let Ok(abuf) = e.data().dyn_into::<js_sys::ArrayBuffer>() {
-                //let array = js_sys::Uint8Array::new(&abuf);
-                cbout.emit(WebsocketMsgType::BinaryMsg(abuf));
-            } else if let Ok(_blob) = e.data().dyn_into::<web_sys::Blob>() {
-                ConsoleService::error("rig control: unexpected blob message from server");
-            } else if let Ok(txt) = e.data().dyn_into::<js_sys::JsString>() {
-                cbout.emit(WebsocketMsgType::TextMsg(txt.into()));
-            } else {
-                ConsoleService::error("rig control: unexpected message from server");
-            }
-        }) as Box<dyn FnMut(MessageEvent)>);
-        ws.set_onmessage(Some(onmessage_callback.as_ref().unchecked_ref()));
-        onmessage_callback.forget();
-        self.wss = Some(ws)
+    pub fn edit_call_note(&mut self, call: String) {
+        let note = self.call_notes.get(&call).cloned().unwrap_or(String::new());
+        self.note_form = Some((call, note));
     }
 
-    pub fn disconnect(&mut self) {
-        self.wss = None;
-        self.receivers = Vec::new();
-        self.radios = Vec::new();
-        self.version = None;
-        self.default_receiver = None;
-        self.spots = SpotDB::new();
+    pub fn update_call_note(&mut self, note: String) {
+        if let Some((_, current)) = &mut self.note_form {
+            *current = note;
+        }
     }
 
-    pub fn is_connected(&self) -> bool {
-        match self.wss {
-            Some(_) => true,
-            None => false
+    pub fn save_call_note(&mut self) {
+        if let Some((call, note)) = self.note_form.take() {
+            self.call_notes.set(&call, &note);
+            self.call_notes.store(&mut self.storage);
         }
     }
 
-    pub fn send_command(&mut self, cmd: Command) {
-        let j = serde_json::to_string(&cmd).unwrap();
-        if let Some(wss) = &self.wss {
-            wss.send_with_str(&j).unwrap();
-            ConsoleService::log(&format!("sent: {}", j));
-        } else {
-            ConsoleService::error(&format!("attempted to send: {}, but not connected", j));
+    // Only touch or small screens get the keypad, elsewhere the
+    // digit up/down controls are easier
+    pub fn open_keypad(&mut self, receiver_id: u32) {
+        let touch = web_sys::window()
+            .and_then(|window| window.match_media(KEYPAD_MEDIA_QUERY).ok().flatten())
+            .map(|query| query.matches())
+            .unwrap_or(false);
+        if touch && !self.is_locked(receiver_id) {
+            self.keypad = Some(Keypad { receiver_id, entry: String::new(), mhz: true });
         }
     }
 
-    pub fn subscribe_to_audio(&mut self) {
-        match self.audio.receiving_audio() {
-            Some(previous_audio_channel) => {
-                self.send_command(Command::SubscribeToAudio{ rx_id: previous_audio_channel, enable: false });
-            },
-            None => ()
+    pub fn keypad_press(&mut self, key: KeypadKey) {
+        if let Some(keypad) = &mut self.keypad {
+            match key {
+                KeypadKey::Digit(digit) => keypad.entry.push(digit),
+                KeypadKey::Point if !keypad.entry.contains('.') => keypad.entry.push('.'),
+                KeypadKey::Point => (),
+                KeypadKey::Backspace => { keypad.entry.pop(); },
+                KeypadKey::Clear => keypad.entry.clear(),
+                KeypadKey::ToggleUnit => keypad.mhz = !keypad.mhz,
+            }
         }
-        let rx_id =
-            match self.default_receiver() {
-                Some(receiver) => {
-                    self.send_command(Command::SubscribeToAudio{ rx_id: receiver.id, enable: true });
-                    ConsoleService::log(&format!("subscribed to audio channel: {}", receiver.id));
-                    Some(receiver.id)
-                },
-                None => None,
-            };
-        self.audio.set_subscribed(rx_id);
     }
 
-    pub fn unsubscribe_to_audio(&mut self) {
-        match self.audio.receiving_audio() {
-            Some(previous_audio_channel) => {
-                self.send_command(Command::SubscribeToAudio{ rx_id: previous_audio_channel, enable: false });
-                ConsoleService::log("unsubscribed to audio");
-            },
-            None => ()
+    pub fn confirm_keypad(&mut self) {
+        let keypad =
+            match self.keypad.take() {
+                Some(keypad) => keypad,
+                None => return,
+            };
+        // locked while the keypad was open
+        if self.is_locked(keypad.receiver_id) {
+            self.show_error(self.t("frequency_locked"));
+            return;
+        }
+        let scale = if keypad.mhz { 1_000_000.0 } else { 1_000.0 };
+        let mode = self.receivers.iter().find(|r| r.id == keypad.receiver_id).map(|r| r.mode.clone());
+        // the operating frequency is entered, the radio tunes the IF
+        let offset = self.settings.transverter_offset(keypad.receiver_id);
+        match (keypad.entry.parse::<f64>(), mode) {
+            (Ok(value), Some(mode)) => self.tune_receiver(keypad.receiver_id, (value * scale - offset).round() as f32, mode),
+            (Err(_), _) => self.show_error(format!("invalid frequency: {}", keypad.entry)),
+            (_, None) => self.show_error(format!("no receiver {}", keypad.receiver_id)),
         }
-        self.audio.set_subscribed(None);
     }
 
-    pub fn set_default_receiver(&mut self, receiver: Option<u32>) {
-        if self.default_receiver == receiver { /* do nothing */ }
-        else {
-            // unsubscribe to old spectrum data
-            match self.spectrum.receiving_spectrum() {
-                Some(previous_subscription) => {
-                    self.send_command(Command::SubscribeToSpectrum{ rx_id: previous_subscription, enable: false });
-                },
-                None => ()
-            }
+    pub fn close_keypad(&mut self) {
+        self.keypad = None;
+    }
 
-            // subscribe to new spectrum data
-            match receiver {
-                Some(receiver_id) => {
-                    if let Some(index) = self.receivers.iter().position(|i| i.id == receiver_id) {
-                        let receiver = self.receivers[index].clone();
-                        self.send_command(Command::SubscribeToSpectrum{ rx_id: receiver_id, enable: true });
-                        self.spectrum.set_subscribed(Some(receiver_id));
+    pub fn cancel_call_note(&mut self) {
+        self.note_form = None;
+    }
 
-                        let js = format!("initWaterfallNav(\"{}\", {}, {}, {});", receiver.mode.mode(), receiver.frequency, receiver.filter_high, receiver.filter_low);
-                        ConsoleService::log(&format!("js: {}", js));
-                        js_sys::eval(&js).unwrap();
+    pub fn edit_receiver_name(&mut self, receiver_id: u32) {
+        let name = self.settings.receiver_name(receiver_id).cloned().unwrap_or(String::new());
+        self.name_form = Some((receiver_id, name));
+    }
 
-                        // update default receiver
-                        self.default_receiver = Some(receiver_id);
+    pub fn update_receiver_name(&mut self, name: String) {
+        if let Some((_, current)) = &mut self.name_form {
+            *current = name;
+        }
+    }
 
-                        // switch audio subscriptions if already subscribed
-                        match self.audio.receiving_audio() {
-                            Some(_) => {
-                                self.subscribe_to_audio();
-                            },
-                            None => ()
-                        }
-                    } else {
-                        ConsoleService::error(&format!("Attempted to set default receiver with invalid receiver id: {}", receiver_id));
-                    }
-                },
-                None => {
-                    self.default_receiver = None;
-                    self.unsubscribe_to_audio();
-                    js_sys::eval("initWaterfallNav(null, null, null, null);").unwrap();
-                }
-            }
+    pub fn save_receiver_name(&mut self) {
+        if let Some((receiver_id, name)) = self.name_form.take() {
+            self.settings.set_receiver_name(receiver_id, &name);
+            self.settings.store(&mut self.storage);
         }
     }
 
-    pub fn toggle_receiver_list(&mut self) {
-        self.show_receiver_list = !self.show_receiver_list;
+    pub fn cancel_receiver_name(&mut self) {
+        self.name_form = None;
     }
 
-    pub fn show_receiver_list(&mut self) {
-        self.show_receiver_list = true
+    pub fn block_call(&mut self, entry: String) {
+        self.spots.block(&entry);
+        self.new_block = String::new();
     }
 
-    pub fn hide_receiver_list(&mut self) {
-        self.show_receiver_list = false
+    pub fn snap_to_dial(&mut self, receiver_id: u32) {
+        if let Some(index) = self.receivers.iter().position(|i| i.id == receiver_id) {
+            let mode = self.receivers[index].mode.clone();
+            match dial_frequency(self.receivers[index].frequency, &mode) {
+                Some(frequency) => self.tune_receiver(receiver_id, frequency, mode),
+                None => ConsoleService::error(&format!("no dial frequency for {} on this band", mode.mode())),
+            }
+        }
     }
 
-    pub fn read_file(&mut self, file: File) {
-        let task = {
-            let callback = self.link.callback(|data| Msg::Loaded(data));
-            self.reader.read_file(file, callback).unwrap()
-        };
-        self.tasks.push(task);
+    pub fn toggle_freq_lock(&mut self, receiver_id: u32) {
+        if !self.locked.remove(&receiver_id) {
+            self.locked.insert(receiver_id);
+        }
     }
 
-    pub fn load_adif_data(&mut self, data: FileData) {
-        match ham_rs::adif::adif_parse("import", &mut data.content.as_slice()) {
-            Ok(adif) => {
-                let mut records = Vec::new();
-                for record in adif.adif_records.as_slice() {
-                    match LogEntry::from_adif_record(&record) {
-                        Ok(entry) => {
-                            records.push(entry);
-                        },
-                        Err(e) => {
-                            ConsoleService::error(&format!("failed to import record [{:?}]: {:?}", e, record));
-                        }
+    pub fn is_locked(&self, receiver_id: u32) -> bool {
+        self.locked.contains(&receiver_id)
+    }
+
+    pub fn is_mode_pinned(&self, receiver_id: u32) -> bool {
+        self.pinned_modes.contains(&receiver_id)
+    }
+
+    // Called after a receiver changes frequency, switches to the mode
+    // configured for the new band if the band changed
+    pub fn apply_band_mode(&mut self, receiver_id: u32, previous_frequency: f32) {
+        if self.is_mode_pinned(receiver_id) {
+            return;
+        }
+        if let Some(index) = self.receivers.iter().position(|i| i.id == receiver_id) {
+            let previous_band = Band::new(previous_frequency as i32).band().map(|b| b.to_string());
+            let band = Band::new(self.receivers[index].frequency as i32).band().map(|b| b.to_string());
+            match band {
+                Some(band) if Some(band.clone()) != previous_band => {
+                    let mode =
+                        match self.settings.band_mode(&band) {
+                            Some(mode) => Mode::new(mode.to_string()),
+                            None => return,
+                        };
+                    if self.receivers[index].mode != mode {
+                        self.change_receiver_mode(receiver_id, mode);
                     }
-                }
-                self.import = Some(records);
-                self.storage.store(LOGBOOK_KEY, Json(&self.import));
-                self.update_state_map_overlay();
-            },
-            Err(e) => {
-                ConsoleService::error(&format!("unable to load adif: {}", e));
+                },
+                _ => (),
             }
         }
     }
 
-    pub fn clear_adif_data(&mut self) {
-        self.import = None;
-        self.storage.store(LOGBOOK_KEY, Json(&self.import));
-        self.update_state_map_overlay();
+    // Frequency actually operated on when `receiver_id` is tuned to
+    // `frequency`, the radio's IF plus any transverter offset
+    fn operating_frequency(&self, receiver_id: u32, frequency: f32) -> f32 {
+        (frequency as f64 + self.settings.transverter_offset(receiver_id)) as f32
     }
 
-    pub fn get_radio_power_state(&self, radio_id: u32) -> Option<bool> {
-        if let Some(index) = self.radios.iter().position(|i| i.id == radio_id) {
-            Some(self.radios[index].running)
-        } else {
-            None
-        }
+    pub fn set_transverter_offset(&mut self, receiver_id: u32, offset: f64) {
+        self.settings.set_transverter_offset(receiver_id, offset);
+        self.settings.store(&mut self.storage);
     }
 
-    pub fn toggle_receivers_button(&self) -> Html {
-        let cls = if self.show_receiver_list == true {
-            "fa-chevron-up"
-        } else {
-            "fa-chevron-down"
-        };
+    // Operating frequency readout for receivers behind a transverter
+    fn transverter_view(&self, receiver: &Receiver) -> Html {
+        let offset = self.settings.transverter_offset(receiver.id);
+        if offset == 0.0 {
+            return html! {};
+        }
+        let operating = receiver.frequency as f64 + offset;
         html! {
-            <button class="button is-text" onclick=self.link.callback(move |_| Msg::ToggleReceiverList)>
-                <span>{ format!("{} Receivers", self.receivers.len()) }</span>
-                <span class="icon is-small">
-                    <i class=("fas", cls)></i>
-                </span>
-            </button>
+            <span class="tag is-link is-light transverter" title=format!("{} {:+.6} MHz", self.t("transverter_offset"), offset / 1_000_000.0)>
+                { format!("{:.6} MHz", operating / 1_000_000.0) }
+            </span>
         }
     }
 
-    pub fn receiver_list_control(&self) -> Html {
-        html! {
+    // True if the operating frequency is outside the license class's
+    // privileges. Only a warning, receiving anywhere is fine and the
+    // app has no transmit path.
+    fn outside_privileges(&self, receiver_id: u32, frequency: f32) -> bool {
+        let operating = self.operating_frequency(receiver_id, frequency);
+        !self.settings.privilege_override
+            && self.settings.license_class.permits(self.settings.band_plan_region, operating) == Some(false)
+    }
+
+    pub fn toggle_privilege_override(&mut self) {
+        self.settings.privilege_override = !self.settings.privilege_override;
+        self.settings.store(&mut self.storage);
+    }
+
+    // Tune a receiver directly to `frequency` and `mode`
+    pub fn tune_receiver(&mut self, receiver_id: u32, frequency: f32, mode: Mode) {
+        if self.set_receiver_frequency(receiver_id, frequency, mode) {
+            self.remember_band(receiver_id);
+        }
+    }
+
+    // Tuning without updating the band stack, true if the change was
+    // sent
+    fn set_receiver_frequency(&mut self, receiver_id: u32, frequency: f32, mode: Mode) -> bool {
+        if self.is_locked(receiver_id) {
+            return false;
+        }
+        if let Some(index) = self.receivers.iter().position(|i| i.id == receiver_id) {
+            if !self.send_checked(commands::set_frequency(&self.receivers, receiver_id, frequency)) {
+                return false;
+            }
+            self.receivers[index].frequency = frequency;
+            if self.receivers[index].mode != mode {
+                self.change_receiver_mode(receiver_id, mode);
+            }
+            self.record_tune(receiver_id);
+            return true;
+        }
+        false
+    }
+
+    // Tune the default receiver to the frequency under `fraction` of
+    // the band scope's width. A drag produces many of these, the
+    // latest is sent at most every SCOPE_TUNE_MS.
+    pub fn scope_tune(&mut self, fraction: f64) {
+        let (receiver_id, frequency) =
+            match (self.default_receiver, self.spectrum.frequency_at(fraction)) {
+                (Some(receiver_id), Some(frequency)) => (receiver_id, frequency),
+                _ => return,
+            };
+        if self.is_locked(receiver_id) {
+            return;
+        }
+        let frequency = ((frequency / SCOPE_TUNE_STEP_HZ).round() * SCOPE_TUNE_STEP_HZ) as f32;
+        self.scope_target = Some((receiver_id, frequency));
+        self.scope_dragging = Some(receiver_id);
+        if self.scope_settle.is_none() {
+            let callback = self.link.callback(|_| Msg::SendScopeTune);
+            self.scope_settle = Some(TimeoutService::spawn(Duration::from_millis(SCOPE_TUNE_MS), callback));
+        }
+    }
+
+    pub fn send_scope_tune(&mut self) {
+        self.scope_settle = None;
+        if let Some((receiver_id, frequency)) = self.scope_target.take() {
+            let current = self.receivers.iter().find(|r| r.id == receiver_id).map(|r| (r.frequency, r.mode.clone()));
+            match current {
+                // the same step repeats while dragging slowly
+                Some((tuned, mode)) if tuned != frequency => { self.set_receiver_frequency(receiver_id, frequency, mode); },
+                _ => (),
+            }
+        }
+    }
+
+    // Button released: send the last position now and keep it in the
+    // band stack
+    pub fn finish_scope_tune(&mut self) {
+        if let Some(receiver_id) = self.scope_dragging.take() {
+            self.send_scope_tune();
+            self.remember_band(receiver_id);
+        }
+    }
+
+    // Band stacking: a receiver's frequency and mode become the place
+    // to return to when its band is selected again. Every tune lands
+    // here, so settings are only stored once tuning has stopped for
+    // BAND_STACK_STORE_MS.
+    pub fn remember_band(&mut self, receiver_id: u32) {
+        let entry = self.receivers.iter()
+            .find(|r| r.id == receiver_id)
+            .and_then(|r| Band::new(r.frequency as i32).band().map(|band| (band.to_string(), r.frequency, r.mode.mode().to_string())));
+        if let Some((band, frequency, mode)) = entry {
+            self.settings.set_band_stack(&band, frequency, &mode);
+            let callback = self.link.callback(|_| Msg::StoreBandStack);
+            self.band_stack_store = Some(TimeoutService::spawn(Duration::from_millis(BAND_STACK_STORE_MS), callback));
+        }
+    }
+
+    pub fn store_band_stack(&mut self) {
+        self.band_stack_store = None;
+        self.settings.store(&mut self.storage);
+    }
+
+    // Tune to where `receiver_id` was last on `band`, or to the band's
+    // dial frequency in the band's mode the first time
+    pub fn select_band(&mut self, receiver_id: u32, band: &str) {
+        if self.is_locked(receiver_id) {
+            return;
+        }
+        let current =
+            match self.receivers.iter().find(|r| r.id == receiver_id) {
+                Some(receiver) => receiver.mode.clone(),
+                None => return,
+            };
+        let (frequency, mode) =
+            match self.settings.band_stack(band) {
+                Some((frequency, mode)) => (frequency, Mode::new(mode.to_string())),
+                None => {
+                    let mode = self.settings.band_mode(band).map(|mode| Mode::new(mode.to_string())).unwrap_or_else(|| current.clone());
+                    match band_dial(band, &mode) {
+                        Some(dial) => (dial, mode),
+                        None => {
+                            self.show_error(format!("no dial frequency for {}", band));
+                            return;
+                        }
+                    }
+                },
+            };
+        let mode = if self.is_mode_pinned(receiver_id) { current } else { mode };
+        self.tune_receiver(receiver_id, frequency, mode);
+    }
+
+    fn record_tune(&mut self, receiver_id: u32) {
+        if let Some(receiver) = self.receivers.iter().find(|r| r.id == receiver_id) {
+            let (frequency, mode) = (receiver.frequency, receiver.mode.mode().to_string());
+            self.macro_recorder.record(MacroStep::Tune { receiver_id, frequency, mode });
+        }
+    }
+
+    pub fn record_mode(&mut self, receiver_id: u32, mode: &Mode) {
+        self.macro_recorder.record(MacroStep::SetMode { receiver_id, mode: mode.mode().to_string() });
+    }
+
+    // Stopping saves the recording as a new macro, renamed in settings
+    pub fn toggle_macro_recording(&mut self) {
+        if !self.macro_recorder.is_recording() {
+            self.macro_player.stop();
+            self.macro_recorder.start();
+            return;
+        }
+        let steps = self.macro_recorder.stop();
+        if steps.is_empty() {
+            return;
+        }
+        let name = format!("{} {}", self.t("macro"), self.macros.len() + 1);
+        self.macros.push(Macro { name, steps });
+        store_macros(&mut self.storage, &self.macros);
+    }
+
+    pub fn play_macro(&mut self, index: usize) {
+        if self.macro_recorder.is_recording() {
+            self.show_error("stop recording before playing a macro".to_string());
+            return;
+        }
+        if let Some(steps) = self.macros.get(index).map(|m| m.steps.clone()) {
+            self.macro_player.play(&self.link, steps);
+        }
+    }
+
+    pub fn rename_macro(&mut self, index: usize, name: String) {
+        if let Some(m) = self.macros.get_mut(index) {
+            m.name = name.trim().to_string();
+            store_macros(&mut self.storage, &self.macros);
+        }
+    }
+
+    pub fn delete_macro(&mut self, index: usize) {
+        if index < self.macros.len() {
+            self.macros.remove(index);
+            store_macros(&mut self.storage, &self.macros);
+        }
+    }
+
+    fn macros_menu(&self) -> Html {
+        let (record_class, record_title) =
+            match self.macro_recorder.is_recording() {
+                true => ("fas fa-stop has-text-danger", self.t("stop_recording_macro")),
+                false => ("fas fa-dot-circle", self.t("record_macro")),
+            };
+        html! {
+            <div class="navbar-item has-dropdown is-hoverable macros">
+                <a class="navbar-link">{ self.t("macros") }</a>
+                <div class="navbar-dropdown is-right">
+                    <a class="navbar-item" onclick=self.link.callback(|_| Msg::ToggleMacroRecording)>
+                        <span class="icon is-small"><i class=record_class></i></span>
+                        <span>{ record_title }</span>
+                    </a>
+                    {
+                        match self.macro_player.is_playing() {
+                            true => html! {
+                                <a class="navbar-item" onclick=self.link.callback(|_| Msg::StopMacro)>
+                                    <span class="icon is-small"><i class="fas fa-stop"></i></span>
+                                    <span>{ self.t("stop_macro") }</span>
+                                </a>
+                            },
+                            false => html! {},
+                        }
+                    }
+                    {
+                        match self.macros.is_empty() {
+                            true => html! {},
+                            false => html! { <hr class="navbar-divider" /> },
+                        }
+                    }
+                    {
+                        for self.macros.iter().enumerate().map(|(i, m)| html! {
+                            <a class="navbar-item" onclick=self.link.callback(move |_| Msg::PlayMacro(i))>
+                                <span class="icon is-small"><i class="fas fa-play"></i></span>
+                                <span>{ &m.name }</span>
+                            </a>
+                        })
+                    }
+                </div>
+            </div>
+        }
+    }
+
+    pub fn spot_clicked(&mut self, call: String, frequency: f32, mode: Mode) {
+        self.spot_action(self.settings.spot_click_action, call, frequency, mode);
+    }
+
+    pub fn spot_action(&mut self, action: SpotClickAction, call: String, frequency: f32, mode: Mode) {
+        match action {
+            SpotClickAction::Tune => {
+                self.dupe_warning =
+                    if self.is_dupe(&call, frequency, &mode) { Some(call.clone()) } else { None };
+                // prefer a receiver already on the spot, otherwise
+                // move the default receiver to it
+                match self.receivers.iter().position(|i| i.frequency == frequency && i.mode == mode) {
+                    Some(index) => {
+                        let receiver_id = self.receivers[index].id;
+                        self.set_default_receiver(Some(receiver_id));
+                    },
+                    None => {
+                        if let Some(receiver_id) = self.default_receiver {
+                            self.tune_receiver(receiver_id, frequency, mode);
+                        }
+                    }
+                }
+            },
+            SpotClickAction::Copy => {
+                // web-sys only exposes Clipboard behind web_sys_unstable_apis,
+                // so pass the call as a JSON string literal instead
+                match serde_json::to_string(&call) {
+                    Ok(literal) => { js_sys::eval(&format!("navigator.clipboard.writeText({});", literal)).unwrap_or(JsValue::NULL); },
+                    Err(e) => ConsoleService::error(&format!("unable to copy {}: {}", call, e)),
+                }
+            },
+            SpotClickAction::Lookup => {
+                match (self.settings.lookup_url(&call), web_sys::window()) {
+                    (Some(url), Some(window)) => {
+                        window.open_with_url_and_target(&url, "_blank").unwrap_or(None);
+                    },
+                    _ => ConsoleService::error("no callsign lookup site configured"),
+                }
+            },
+            SpotClickAction::Nothing => (),
+        }
+    }
+
+    // Clear the spot table, asking first if there are many spots
+    // or any are pinned
+    pub fn clear_spots(&mut self) {
+        if self.spots.spots().len() > CLEAR_SPOTS_CONFIRM || self.spots.has_pinned() {
+            self.confirm_clear_spots = true;
+        } else {
+            self.spots.clear_spots(false);
+        }
+    }
+
+    pub fn confirm_clear_spots(&mut self, keep_pinned: bool) {
+        self.confirm_clear_spots = false;
+        self.spots.clear_spots(keep_pinned);
+    }
+
+    pub fn cancel_clear_spots(&mut self) {
+        self.confirm_clear_spots = false;
+    }
+
+    pub fn show_spot_menu(&mut self, call: String, frequency: f32, signal: f32, mode: Mode, x: i32, y: i32) {
+        self.spot_menu = Some(SpotMenu { call, frequency, signal, mode, x, y });
+        if self.spot_menu_keys.is_none() {
+            if let Some(window) = web_sys::window() {
+                let callback = self.link.callback(|e: KeyboardEvent| {
+                    if e.key() == "Escape" { Msg::HideSpotMenu } else { Msg::None }
+                });
+                self.spot_menu_keys = Some(KeyboardService::register_key_down(&window, callback));
+            }
+        }
+    }
+
+    pub fn listen_for_shortcuts(&mut self) {
+        if let Some(window) = web_sys::window() {
+            let callback = self.link.callback(|e: KeyboardEvent| {
+                if is_typing(&e) { Msg::None } else { Msg::Shortcut(e.key()) }
+            });
+            self.shortcut_keys = Some(KeyboardService::register_key_down(&window, callback));
+        }
+    }
+
+    // Message bound to `key` in the keymap, Escape closes the
+    // shortcut help
+    pub fn shortcut(&self, key: &str) -> Option<Msg> {
+        if key == "Escape" && self.show_shortcut_help {
+            return Some(Msg::ToggleShortcutHelp);
+        }
+        keymap::action_for(&self.settings.keymap, key).map(|action| action.msg())
+    }
+
+    pub fn toggle_shortcut_help(&mut self) {
+        self.show_shortcut_help = !self.show_shortcut_help;
+    }
+
+    pub fn set_shortcut(&mut self, action: Action, key: String) {
+        keymap::set_key(&mut self.settings.keymap, action, &key);
+        self.settings.store(&mut self.storage);
+    }
+
+    pub fn hide_spot_menu(&mut self) {
+        self.spot_menu = None;
+        self.spot_menu_keys = None;
+    }
+
+    pub fn is_dupe(&self, call: &str, frequency: f32, mode: &Mode) -> bool {
+        is_dupe(&self.dupe_keys, call, frequency, mode)
+    }
+
+    // After log_entries or the import changes
+    fn update_dupe_keys(&mut self) {
+        self.dupe_keys = dupe_keys(&self.log_entries, self.import.as_deref());
+    }
+
+    pub fn dismiss_dupe_warning(&mut self) {
+        self.dupe_warning = None;
+    }
+
+    pub fn open_qso_form(&mut self, call: String, frequency: f32, mode: Mode) {
+        self.qso_form = Some(QsoForm::new(call, frequency, mode));
+    }
+
+    pub fn update_qso_form(&mut self, field: QsoField, value: String) {
+        if let Some(form) = &mut self.qso_form {
+            form.set(field, value);
+        }
+    }
+
+    // Save the quick log form to log_entries
+    pub fn log_qso(&mut self) {
+        match self.qso_form.as_ref().and_then(|form| form.entry()) {
+            Some(entry) => {
+                ConsoleService::log(&format!("logged contact with {}", entry.call));
+                // the spot's Call carries any looked up state, otherwise
+                // the country comes from the prefix like an imported log
+                let call = self.spots.spots().iter().rev()
+                    .find(|s| s.call.call().eq_ignore_ascii_case(&entry.call))
+                    .map(|s| s.call.clone())
+                    .unwrap_or_else(|| Call::new(entry.call.clone()));
+                self.spots.add_worked_call(&call);
+                self.log_entries.push(entry);
+                store_qsos(&mut self.storage, &self.log_entries);
+                self.update_dupe_keys();
+                self.qso_form = None;
+            },
+            None => ConsoleService::error("unable to log contact: missing callsign"),
+        }
+    }
+
+    pub fn cancel_qso(&mut self) {
+        self.qso_form = None;
+    }
+
+    pub fn frequency_up(&mut self, receiver_id: u32, digit: i32) {
+        if self.is_locked(receiver_id) {
+            return;
+        }
+        if let Some(index) = self.receivers.iter().position(|i| i.id == receiver_id) {
+            let previous_frequency = self.receivers[index].frequency;
+            if digit == 0 { self.receivers[index].frequency += 100000000.0 }
+            if digit == 1 { self.receivers[index].frequency += 10000000.0 }
+            if digit == 2 { self.receivers[index].frequency += 1000000.0 }
+            if digit == 3 { self.receivers[index].frequency += 100000.0 }
+            if digit == 4 { self.receivers[index].frequency += 10000.0 }
+            if digit == 5 { self.receivers[index].frequency += 1000.0 }
+            if digit == 6 { self.receivers[index].frequency += 100.0 }
+            if digit == 7 { self.receivers[index].frequency += 10.0 }
+            if digit == 8 { self.receivers[index].frequency += 1.0 }
+
+            let frequency = self.receivers[index].frequency;
+            if !self.send_checked(commands::set_frequency(&self.receivers, receiver_id, frequency)) {
+                self.receivers[index].frequency = previous_frequency;
+                return;
+            }
+            self.apply_band_mode(receiver_id, previous_frequency);
+            self.record_tune(receiver_id);
+            self.remember_band(receiver_id);
+        }
+    }
+
+    pub fn frequency_down(&mut self, receiver_id: u32, digit: i32) {
+        if self.is_locked(receiver_id) {
+            return;
+        }
+        if let Some(index) = self.receivers.iter().position(|i| i.id == receiver_id) {
+            let previous_frequency = self.receivers[index].frequency;
+            if digit == 0 { self.receivers[index].frequency -= 100000000.0 }
+            if digit == 1 { self.receivers[index].frequency -= 10000000.0 }
+            if digit == 2 { self.receivers[index].frequency -= 1000000.0 }
+            if digit == 3 { self.receivers[index].frequency -= 100000.0 }
+            if digit == 4 { self.receivers[index].frequency -= 10000.0 }
+            if digit == 5 { self.receivers[index].frequency -= 1000.0 }
+            if digit == 6 { self.receivers[index].frequency -= 100.0 }
+            if digit == 7 { self.receivers[index].frequency -= 10.0 }
+            if digit == 8 { self.receivers[index].frequency -= 1.0 }
+
+            let frequency = self.receivers[index].frequency;
+            if !self.send_checked(commands::set_frequency(&self.receivers, receiver_id, frequency)) {
+                self.receivers[index].frequency = previous_frequency;
+                return;
+            }
+            self.apply_band_mode(receiver_id, previous_frequency);
+            self.record_tune(receiver_id);
+            self.remember_band(receiver_id);
+        }
+    }
+
+    // Two channels for the websocket connection
+    // 1) Text: Json encoded messages for control/info (e.g. get/set frequency)
+    // 2) Binary: Binary encoded audio data
+    //
+    // Both channels are bi-directional (e.g. transmit using binary encoded audio)
+    // 
+    pub fn connect(&mut self, ws: &str) {
+        // the previous socket can't call its handlers once detached,
+        // so they can be freed
+        if let Some(previous) = self.wss.take() {
+            detach_socket(&previous);
+            let _ = previous.close();
+        }
+        self.ws_handlers.clear();
+        self.ws_onmessage = None;
+        self.ws_opened = false;
+        self.certificate_hint = None;
+        // a manual connect replaces any scheduled attempt
+        self.reconnect_task = None;
+        self.reconnect_at = None;
+        // reconnects only go to an address that has opened, a new one
+        // is armed again by connected()
+        if self.last_location.as_deref() != Some(ws) {
+            self.reconnect_enabled = false;
+            self.reconnect_attempts = 0;
+        }
+        self.last_location = Some(ws.to_string());
+        let ws =
+            match WebSocket::new(ws) {
+                Ok(ws) => ws,
+                Err(err) => {
+                    self.show_error(format!("unable to connect to {}: {:?}", ws, err));
+                    return;
+                }
+            };
+        ws.set_binary_type(BinaryType::Arraybuffer);
+
+		let cbnot = self.link.callback(|input| {
+			match input {
+				WebSocketStatus::Closed | WebSocketStatus::Error => {
+					Msg::Disconnected
+                },
+                WebSocketStatus::Opened => {
+                    Msg::Connected
+                }
+			}
+        });
+        
+        let notify = cbnot.clone();
+        let onopen_callback = Closure::wrap(Box::new(move |_| {
+            ConsoleService::log("rig control: connection opened");
+            notify.emit(WebSocketStatus::Opened);
+        }) as Box<dyn FnMut(JsValue)>);
+        ws.set_onopen(Some(onopen_callback.as_ref().unchecked_ref()));
+        self.ws_handlers.push(onopen_callback);
+
+        let notify = cbnot.clone();
+        let onerror_callback = Closure::wrap(Box::new(move |_| {
+            ConsoleService::error("rig control: connection error");
+            notify.emit(WebSocketStatus::Error);
+        }) as Box<dyn FnMut(JsValue)>);
+        ws.set_onerror(Some(onerror_callback.as_ref().unchecked_ref()));
+        self.ws_handlers.push(onerror_callback);
+
+        let notify = cbnot.clone();
+        let onclose_callback = Closure::wrap(Box::new(move |_| {
+            ConsoleService::error("rig control: connection closed");
+            notify.emit(WebSocketStatus::Closed);
+        }) as Box<dyn FnMut(JsValue)>);
+        ws.set_onclose(Some(onclose_callback.as_ref().unchecked_ref()));
+        self.ws_handlers.push(onclose_callback);
+
+        let cbout = self.link.callback(|data| {
+            match data {
+                WebsocketMsgType::BinaryMsg(binary) => {
+                    match binary.byte_length() > 0 && js_sys::Uint8Array::new(&binary).get_index(0) == SPECTRUM_FRAME {
+                        true => Msg::ReceivedSpectrum(binary),
+                        false => Msg::ReceivedAudio(binary),
+                    }
+                },
+                WebsocketMsgType::TextMsg(text) => {
+                    Msg::TextFrame(text)
+                }
+            }
+        });
+
+        let onmessage_callback = Closure::wrap(Box::new(move |e: MessageEvent| {
+            if let Ok(abuf) = e.data().dyn_into::<js_sys::ArrayBuffer>() {
+                //let array = js_sys::Uint8Array::new(&abuf);
+                cbout.emit(WebsocketMsgType::BinaryMsg(abuf));
+            } else if let Ok(_blob) = e.data().dyn_into::<web_sys::Blob>() {
+                ConsoleService::error("rig control: unexpected blob message from server");
+            } else if let Ok(txt) = e.data().dyn_into::<js_sys::JsString>() {
+                cbout.emit(WebsocketMsgType::TextMsg(txt.into()));
+            } else {
+                ConsoleService::error("rig control: unexpected message from server");
+            }
+        }) as Box<dyn FnMut(MessageEvent)>);
+        ws.set_onmessage(Some(onmessage_callback.as_ref().unchecked_ref()));
+        self.ws_onmessage = Some(onmessage_callback);
+        self.wss = Some(ws)
+    }
+
+    pub fn connect_profile(&mut self, index: usize) {
+        if let Some(profile) = self.settings.profiles.get(index) {
+            self.ws_location = profile.url.to_string();
+            self.settings.last_profile = Some(index);
+            self.settings.store(&mut self.storage);
+            self.pending_profile = Some(index);
+            let url = self.ws_location.to_string();
+            self.connect(&url);
+        }
+    }
+
+    pub fn edit_profile(&mut self, index: usize) {
+        if let Some(profile) = self.settings.profiles.get(index) {
+            self.profile_form = profile.clone();
+            self.editing_profile = Some(index);
+        }
+    }
+
+    pub fn update_profile_form(&mut self, field: ProfileField, value: String) {
+        let value = value.trim().to_string();
+        match field {
+            ProfileField::Label => self.profile_form.label = value,
+            ProfileField::Url => self.profile_form.url = value,
+            // entered in kHz
+            ProfileField::Frequency => self.profile_form.frequency = value.parse::<f32>().ok().map(|khz| khz * 1000.0),
+            ProfileField::Mode => self.profile_form.mode = if value.is_empty() { None } else { Some(value) },
+        }
+    }
+
+    pub fn save_profile(&mut self) {
+        let mut profile = self.profile_form.clone();
+        if profile.url.is_empty() {
+            profile.url = self.ws_location.to_string();
+        }
+        if profile.label.is_empty() {
+            profile.label = profile.url.to_string();
+        }
+        match self.editing_profile.filter(|index| *index < self.settings.profiles.len()) {
+            Some(index) => self.settings.profiles[index] = profile,
+            None => self.settings.profiles.push(profile),
+        }
+        self.settings.store(&mut self.storage);
+        self.profile_form = ConnectionProfile::default();
+        self.editing_profile = None;
+    }
+
+    pub fn delete_profile(&mut self, index: usize) {
+        self.settings.remove_profile(index);
+        self.settings.store(&mut self.storage);
+        self.editing_profile = None;
+    }
+
+    // Tune the default receiver to the profile's frequency/mode
+    fn apply_pending_profile(&mut self) {
+        let profile =
+            match self.pending_profile.take().and_then(|index| self.settings.profiles.get(index)) {
+                Some(profile) => profile.clone(),
+                None => return,
+            };
+        if let Some(receiver) = self.default_receiver() {
+            let mode = profile.mode.map(|mode| Mode::new(mode)).unwrap_or(receiver.mode.clone());
+            let frequency = profile.frequency.unwrap_or(receiver.frequency);
+            self.tune_receiver(receiver.id, frequency, mode);
+        }
+    }
+
+    pub fn connected(&mut self) {
+        self.radios_received = false;
+        self.ws_opened = true;
+        self.certificate_hint = None;
+        self.reconnect_enabled = true;
+        self.reconnect_attempts = 0;
+        self.reconnect_task = None;
+        self.reconnect_at = None;
+    }
+
+    // After a dropped connection, try again after a delay that doubles
+    // with each failed attempt. Only one attempt is scheduled at a
+    // time, an error and the close after it are a single drop.
+    pub fn schedule_reconnect(&mut self) {
+        if !self.reconnect_enabled || self.reconnect_task.is_some() || self.last_location.is_none() {
+            return;
+        }
+        let delay = RECONNECT_BASE_MS.saturating_mul(1 << self.reconnect_attempts.min(16)).min(RECONNECT_MAX_MS);
+        self.reconnect_attempts += 1;
+        ConsoleService::log(&format!("rig control: reconnect attempt {} in {}ms", self.reconnect_attempts, delay));
+        let callback = self.link.callback(|_| Msg::Reconnect);
+        self.reconnect_task = Some(TimeoutService::spawn(Duration::from_millis(delay), callback));
+        self.reconnect_at = Some(Utc::now() + chrono::Duration::milliseconds(delay as i64));
+    }
+
+    // Scheduled attempt is due, sent from a timeout rather than the
+    // socket's own handlers, which connect frees
+    pub fn reconnect(&mut self) {
+        self.reconnect_task = None;
+        self.reconnect_at = None;
+        if self.is_connected() {
+            return;
+        }
+        if let Some(location) = self.last_location.clone() {
+            self.connect(&location);
+        }
+        // the socket couldn't even be created, no close will follow
+        if !self.is_connected() {
+            self.schedule_reconnect();
+        }
+    }
+
+    // A wss:// connection that errors and closes without ever opening
+    // is most often a certificate the browser doesn't trust. The
+    // browser won't say so, but visiting the same host over https
+    // lets the user accept the certificate.
+    fn check_certificate_failure(&mut self) {
+        // a reconnect to a server that was reached before isn't a
+        // certificate problem
+        if !self.ws_opened && !self.reconnect_enabled && self.ws_location.starts_with("wss://") {
+            self.certificate_hint = Some(self.ws_location.replacen("wss://", "https://", 1));
+        }
+    }
+
+    // Receivers, radios and spots are kept (shown as stale) and the
+    // receiver selection is remembered so a brief drop is invisible
+    // once reconnected
+    pub fn disconnect(&mut self) {
+        self.check_certificate_failure();
+        self.recorder.stop();
+        // an error is followed by a close, only handle the first
+        if let Some(ws) = self.wss.take() {
+            detach_socket(&ws);
+        }
+        if self.default_receiver.is_some() {
+            self.reconnect_receiver = self.default_receiver;
+            self.reconnect_audio = self.audio.receiving_audio().is_some();
+        }
+        if self.connection_lost_at.is_none() && !self.receivers.is_empty() {
+            self.connection_lost_at = Some(Utc::now());
+        }
+        // server side subscriptions are gone with the connection, and
+        // unconfirmed changes will never be answered
+        self.in_flight.clear();
+        self.spectrum.set_subscribed(None);
+        self.audio.set_subscribed(None);
+    }
+
+    // User initiated disconnect: close the socket with its handlers
+    // removed (so it isn't treated as a dropped connection) and drop
+    // everything tied to the connection
+    pub fn close_connection(&mut self) {
+        if let Some(ws) = self.wss.take() {
+            detach_socket(&ws);
+            if let Err(err) = ws.close() {
+                ConsoleService::error(&format!("rig control: unable to close connection: {:?}", err));
+            }
+        }
+        // closed on purpose, not a certificate failure
+        self.ws_opened = true;
+        self.disconnect();
+        self.connection_lost_at = None;
+        self.reconnect_receiver = None;
+        self.reconnect_audio = false;
+        // a deliberate close isn't retried
+        self.reconnect_enabled = false;
+        self.reconnect_attempts = 0;
+        self.reconnect_task = None;
+        self.reconnect_at = None;
+        // nothing to follow until the next receiver list, which then
+        // picks a default (and subscribes) afresh
+        self.default_receiver = None;
+        self.pending_modes.clear();
+        self.mode_settle = None;
+        self.scope_target = None;
+        self.scope_settle = None;
+        self.scope_dragging = None;
+        self.confirmed.clear();
+        self.spot_render = None;
+        self.receivers.clear();
+        self.radios.clear();
+        self.spots.cancel_fetches();
+    }
+
+    // True while showing data from a connection that was lost
+    pub fn is_stale(&self) -> bool {
+        !self.is_connected() && self.connection_lost_at.is_some()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        match self.wss {
+            Some(_) => true,
+            None => false
+        }
+    }
+
+    pub fn send_command(&mut self, cmd: Command) {
+        let j =
+            match serde_json::to_string(&cmd) {
+                Ok(j) => j,
+                Err(err) => {
+                    self.show_error(format!("unable to encode command: {}", err));
+                    return;
+                }
+            };
+        let sent =
+            match &self.wss {
+                Some(wss) => wss.send_with_str(&j),
+                None => {
+                    ConsoleService::error(&format!("attempted to send: {}, but not connected", j));
+                    return;
+                }
+            };
+        match sent {
+            Ok(_) => {
+                ConsoleService::log(&format!("sent: {}", j));
+                self.command_log.sent(&cmd, &j);
+                self.track_change(&cmd);
+            },
+            Err(err) => self.show_error(format!("unable to send: {}: {:?}", j, err)),
+        }
+    }
+
+    // Send a command built by the commands module, showing the
+    // error instead if it didn't validate. Returns true if sent.
+    pub fn send_checked(&mut self, cmd: Result<Command, CommandError>) -> bool {
+        match cmd {
+            Ok(cmd) => {
+                self.send_command(cmd);
+                true
+            },
+            Err(err) => {
+                self.show_error(format!("not sent: {}", err));
+                false
+            }
+        }
+    }
+
+    // Log an error and show it to the user for a few seconds
+    pub fn show_error(&mut self, message: String) {
+        ConsoleService::error(&message);
+        // removed by a timeout so it goes even with ticks disabled
+        let id = self.next_toast;
+        self.next_toast = self.next_toast.wrapping_add(1);
+        let callback = self.link.callback(move |_| Msg::ExpireToast(id));
+        let task = TimeoutService::spawn(Duration::from_secs(TOAST_SECONDS), callback);
+        self.toasts.push((id, message, task));
+    }
+
+    // True if the toast was still shown
+    pub fn expire_toast(&mut self, id: u32) -> bool {
+        let toasts = self.toasts.len();
+        self.toasts.retain(|(toast, _, _)| *toast != id);
+        toasts != self.toasts.len()
+    }
+
+    pub fn dismiss_toast(&mut self, index: usize) {
+        if index < self.toasts.len() {
+            self.toasts.remove(index);
+        }
+    }
+
+    pub fn subscribe_to_audio(&mut self) {
+        if self.no_audio {
+            return;
+        }
+        match self.audio.receiving_audio() {
+            Some(previous_audio_channel) => {
+                self.send_checked(commands::subscribe_to_audio(&self.receivers, previous_audio_channel, false));
+            },
+            None => ()
+        }
+        let rx_id =
+            match self.audio_receiver_id() {
+                Some(receiver_id) => {
+                    match self.send_checked(commands::subscribe_to_audio(&self.receivers, receiver_id, true)) {
+                        true => {
+                            ConsoleService::log(&format!("subscribed to audio channel: {}", receiver_id));
+                            Some(receiver_id)
+                        },
+                        false => None,
+                    }
+                },
+                None => None,
+            };
+        self.audio.set_subscribed(rx_id);
+    }
+
+    // Receiver audio is played from, the chosen audio receiver if it
+    // still exists, otherwise the default receiver
+    fn audio_receiver_id(&self) -> Option<u32> {
+        self.audio_receiver
+            .filter(|id| self.receivers.iter().any(|r| r.id == *id))
+            .or(self.default_receiver)
+    }
+
+    pub fn is_audio_receiver(&self, receiver_id: u32) -> bool {
+        self.audio_receiver == Some(receiver_id)
+    }
+
+    pub fn set_audio_receiver(&mut self, receiver: Option<u32>) {
+        self.audio_receiver = receiver;
+        // switch audio subscriptions if already subscribed
+        if self.audio.receiving_audio().is_some() && self.audio.receiving_audio() != self.audio_receiver_id() {
+            self.subscribe_to_audio();
+        }
+    }
+
+    // SparkSDR has no command or status for the transmit VFO, so
+    // this is only a reminder drawn on the receiver, not rig state
+    pub fn toggle_marked_receiver(&mut self, receiver_id: u32) {
+        self.marked_receiver =
+            match self.marked_receiver {
+                Some(marked) if marked == receiver_id => None,
+                _ => Some(receiver_id),
+            };
+    }
+
+    pub fn unsubscribe_to_audio(&mut self) {
+        match self.audio.receiving_audio() {
+            Some(previous_audio_channel) => {
+                self.send_checked(commands::subscribe_to_audio(&self.receivers, previous_audio_channel, false));
+                ConsoleService::log("unsubscribed to audio");
+            },
+            None => ()
+        }
+        self.audio.set_subscribed(None);
+    }
+
+    pub fn toggle_recording(&mut self) {
+        match self.recorder.recording() {
+            Some(_) => self.recorder.stop(),
+            None => {
+                match (self.audio.audio_ctx(), self.audio.gain(), self.audio.receiving_audio()) {
+                    (Some(audio_ctx), Some(gain), Some(receiver_id)) => {
+                        self.recorder.start(&self.link, &audio_ctx, &gain, receiver_id);
+                    },
+                    _ => {
+                        ConsoleService::error("unable to record: not receiving audio");
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn set_default_receiver(&mut self, receiver: Option<u32>) {
+        if self.default_receiver == receiver { /* do nothing */ }
+        else {
+            // unsubscribe to old spectrum data
+            match self.spectrum.receiving_spectrum() {
+                Some(previous_subscription) => {
+                    self.send_checked(commands::subscribe_to_spectrum(&self.receivers, previous_subscription, false));
+                },
+                None => ()
+            }
+
+            // subscribe to new spectrum data
+            match receiver {
+                Some(receiver_id) => {
+                    if let Some(index) = self.receivers.iter().position(|i| i.id == receiver_id) {
+                        let receiver = self.receivers[index].clone();
+                        self.send_checked(commands::subscribe_to_spectrum(&self.receivers, receiver_id, true));
+                        self.spectrum.set_subscribed(Some(receiver_id));
+
+                        let js = format!("initWaterfallNav(\"{}\", {}, {}, {});", receiver.mode.mode(), receiver.frequency, receiver.filter_high, receiver.filter_low);
+                        ConsoleService::log(&format!("js: {}", js));
+                        js_sys::eval(&js).unwrap();
+
+                        // update default receiver
+                        self.default_receiver = Some(receiver_id);
+
+                        // switch audio subscriptions if already subscribed
+                        // and following the selected receiver
+                        match self.audio.receiving_audio() {
+                            Some(audio_id) if audio_id != self.audio_receiver_id().unwrap_or(audio_id) => {
+                                self.subscribe_to_audio();
+                            },
+                            _ => ()
+                        }
+                    } else {
+                        ConsoleService::error(&format!("Attempted to set default receiver with invalid receiver id: {}", receiver_id));
+                    }
+                },
+                None => {
+                    self.default_receiver = None;
+                    self.unsubscribe_to_audio();
+                    js_sys::eval("initWaterfallNav(null, null, null, null);").unwrap();
+                }
+            }
+        }
+    }
+
+    pub fn set_number_format(&mut self, format: NumberFormat) {
+        self.settings.number_format = format;
+        self.settings.store(&mut self.storage);
+    }
+
+    pub fn set_time_display(&mut self, time_display: TimeDisplay) {
+        self.settings.time_display = time_display;
+        self.settings.store(&mut self.storage);
+    }
+
+    // Start emitting Msg::Tick every `interval` to keep local
+    // parts of the ui (e.g. clock) up to date
+    pub fn enable_ticks(&mut self, interval: Duration) {
+        let callback = self.link.callback(|_| Msg::Tick);
+        self.ticks = Some(IntervalService::spawn(interval, callback));
+    }
+
+    // Spots can arrive many times a second during an opening. The
+    // spot table is updated right away but rendered at most every
+    // SPOT_RENDER_MS, always returns false (don't render now).
+    pub fn schedule_spot_render(&mut self) -> bool {
+        if self.spot_render.is_none() {
+            let callback = self.link.callback(|_| Msg::RenderSpots);
+            self.spot_render = Some(TimeoutService::spawn(Duration::from_millis(SPOT_RENDER_MS), callback));
+        }
+        false
+    }
+
+    pub fn render_spots(&mut self) {
+        self.spot_render = None;
+        self.scroll_anchor = self.visible_spot_row();
+    }
+
+    // First spot row inside the viewport, None when scrolled to the
+    // top so new spots come into view
+    fn visible_spot_row(&self) -> Option<(String, f64)> {
+        let window = web_sys::window()?;
+        if window.scroll_y().ok()? <= 0.0 {
+            return None;
+        }
+        let rows = self.spot_table.cast::<Element>()?.query_selector_all("tr[data-key]").ok()?;
+        (0..rows.length())
+            .filter_map(|i| rows.item(i).and_then(|node| node.dyn_into::<Element>().ok()))
+            .map(|row| {
+                let top = row.get_bounding_client_rect().top();
+                (row, top)
+            })
+            .find(|(_, top)| *top >= 0.0)
+            .and_then(|(row, top)| row.get_attribute("data-key").map(|key| (key, top)))
+    }
+
+    // Scroll so the anchored row is back where it was before render
+    pub fn restore_scroll_anchor(&mut self) {
+        let (key, top) =
+            match self.scroll_anchor.take() {
+                Some(anchor) => anchor,
+                None => return,
+            };
+        let row = self.spot_table.cast::<Element>()
+            .and_then(|table| table.query_selector(&format!("tr[data-key=\"{}\"]", key)).ok().flatten());
+        if let (Some(window), Some(row)) = (web_sys::window(), row) {
+            let shift = row.get_bounding_client_rect().top() - top;
+            if shift.abs() >= 1.0 {
+                window.scroll_by_with_x_and_y(0.0, shift);
+            }
+        }
+    }
+
+    pub fn disable_ticks(&mut self) {
+        self.ticks = None;
+        self.now = Utc::now();
+    }
+
+    pub fn set_tick_interval(&mut self, seconds: u64) {
+        self.settings.tick_interval = seconds;
+        self.settings.store(&mut self.storage);
+        match seconds {
+            0 => self.disable_ticks(),
+            _ => self.enable_ticks(Duration::from_secs(seconds)),
+        }
+    }
+
+    // Only local, time based parts of the ui are refreshed here
+    // (navbar clock and sun times, stale data age), receiver and
+    // spot state is updated by messages from SparkSDR. Returns true
+    // if anything visible changed.
+    pub fn tick(&mut self) -> bool {
+        self.now = Utc::now();
+        let now = self.now;
+        let expired =
+            match self.settings.spot_max_age {
+                0 => false,
+                minutes => self.spots.expire_spots(now - chrono::Duration::minutes(minutes)),
+            };
+        self.is_connected() || self.is_stale() || expired
+    }
+
+    pub fn set_spot_limit(&mut self, limit: usize) {
+        self.settings.spot_limit = limit;
+        self.settings.store(&mut self.storage);
+        self.spots.trim_spots(limit);
+    }
+
+    pub fn set_spot_max_age(&mut self, minutes: i64) {
+        self.settings.spot_max_age = minutes;
+        self.settings.store(&mut self.storage);
+        if minutes > 0 {
+            self.spots.expire_spots(Utc::now() - chrono::Duration::minutes(minutes));
+        }
+    }
+
+    pub fn set_merge_frequency(&mut self, frequency: f32) {
+        self.settings.merge_frequency = frequency.max(0.0);
+        self.settings.store(&mut self.storage);
+        self.spots.set_merge_tolerance(self.settings.merge_frequency, self.settings.merge_seconds);
+    }
+
+    pub fn set_merge_seconds(&mut self, seconds: i64) {
+        self.settings.merge_seconds = seconds.max(0);
+        self.settings.store(&mut self.storage);
+        self.spots.set_merge_tolerance(self.settings.merge_frequency, self.settings.merge_seconds);
+    }
+
+    // Keeps the thresholds increasing by moving the neighbouring ones
+    pub fn set_distance_threshold(&mut self, bucket: usize, km: f64) {
+        let thresholds = &mut self.settings.distance_thresholds;
+        if bucket >= thresholds.len() || !km.is_finite() {
+            return;
+        }
+        let km = km.max(0.0);
+        for (i, threshold) in thresholds.iter_mut().enumerate() {
+            if i < bucket {
+                *threshold = threshold.min(km);
+            } else if i > bucket {
+                *threshold = threshold.max(km);
+            } else {
+                *threshold = km;
+            }
+        }
+        self.settings.store(&mut self.storage);
+    }
+
+    pub fn set_dt_threshold(&mut self, bucket: usize, seconds: f64) {
+        let thresholds = &mut self.settings.dt_thresholds;
+        if bucket >= thresholds.len() || !seconds.is_finite() {
+            return;
+        }
+        let seconds = seconds.max(0.0);
+        thresholds[bucket] = seconds;
+        // keep the buckets in order
+        if bucket == 0 {
+            thresholds[1] = thresholds[1].max(seconds);
+        } else {
+            thresholds[0] = thresholds[0].min(seconds);
+        }
+        self.settings.store(&mut self.storage);
+    }
+
+    pub fn toggle_no_audio(&mut self) {
+        self.settings.no_audio = !self.settings.no_audio;
+        self.settings.store(&mut self.storage);
+    }
+
+    pub fn toggle_dxcc_column(&mut self) {
+        self.settings.show_dxcc = !self.settings.show_dxcc;
+        self.settings.store(&mut self.storage);
+    }
+
+    // Highlight a call wherever it appears in spot messages, the
+    // highlight words double as a watch list
+    pub fn toggle_highlight_call(&mut self, call: String) {
+        self.settings.toggle_highlight_token(&call);
+        self.settings.store(&mut self.storage);
+    }
+
+    pub fn set_highlight_tokens(&mut self, tokens: String) {
+        self.settings.set_highlight_tokens(&tokens);
+        self.settings.store(&mut self.storage);
+    }
+
+    pub fn set_my_call(&mut self, call: String) {
+        self.settings.my_call = call.trim().to_uppercase();
+        self.settings.store(&mut self.storage);
+    }
+
+    pub fn set_my_grid(&mut self, grid: String) {
+        self.settings.my_grid = grid.trim().to_uppercase();
+        self.settings.store(&mut self.storage);
+    }
+
+    // Sunrise/sunset at my_grid, shown in the navbar
+    fn sun_view(&self) -> Html {
+        let (lat, lon) =
+            match grid_to_lat_lon(&self.settings.my_grid) {
+                Some(location) => location,
+                None => return html! {},
+            };
+        let times = sun_times(self.now, lat, lon);
+        let (class, title) =
+            match is_grey_line(self.now, &times) {
+                true => ("navbar-item sun grey-line", self.t("grey_line")),
+                false => ("navbar-item sun", format!("{} {}", self.t("sun_times"), self.settings.my_grid)),
+            };
+        let text =
+            match times {
+                SunTimes::RiseSet(rise, set) => format!("{} / {}", rise.format("%H%M"), set.format("%H%M")),
+                SunTimes::AlwaysUp => self.t("sun_always_up"),
+                SunTimes::AlwaysDown => self.t("sun_always_down"),
+            };
+        html! {
+            <span class=class title=title>
+                <span class="icon is-small"><i class="fas fa-sun"></i></span>
+                <span>{ text }</span>
+            </span>
+        }
+    }
+
+    pub fn toggle_alert_on_call(&mut self) {
+        self.settings.alert_on_call = !self.settings.alert_on_call;
+        self.settings.store(&mut self.storage);
+    }
+
+    // Empty `device_id` selects the system default output
+    pub fn set_audio_output_device(&mut self, device_id: String) {
+        self.audio.set_output_device(&device_id);
+        self.settings.audio_output_device = if device_id.is_empty() { None } else { Some(device_id) };
+        self.settings.store(&mut self.storage);
+    }
+
+    pub fn toggle_squelch(&mut self) {
+        self.settings.squelch_enabled = !self.settings.squelch_enabled;
+        self.settings.store(&mut self.storage);
+        self.audio.set_squelch(&self.link, self.settings.squelch());
+    }
+
+    pub fn set_squelch_level(&mut self, level: f32) {
+        self.settings.squelch_level = level;
+        self.settings.store(&mut self.storage);
+        self.audio.set_squelch(&self.link, self.settings.squelch());
+    }
+
+    pub fn set_target_latency(&mut self, seconds: f64) {
+        self.settings.target_latency = seconds;
+        self.settings.store(&mut self.storage);
+        self.audio.set_target_latency(seconds);
+    }
+
+    pub fn set_spot_click_action(&mut self, action: SpotClickAction) {
+        self.settings.spot_click_action = action;
+        self.settings.store(&mut self.storage);
+    }
+
+    pub fn toggle_spot_fade(&mut self) {
+        self.settings.spot_fade = !self.settings.spot_fade;
+        self.settings.store(&mut self.storage);
+    }
+
+    pub fn set_table_density(&mut self, density: TableDensity) {
+        self.settings.table_density = density;
+        self.settings.store(&mut self.storage);
+    }
+
+    pub fn set_flag_source(&mut self, source: FlagSource) {
+        self.settings.flag_source = source;
+        self.settings.store(&mut self.storage);
+    }
+
+    pub fn set_band_mode(&mut self, band: String, mode: String) {
+        self.settings.set_band_mode(&band, &mode);
+        self.settings.store(&mut self.storage);
+    }
+
+    pub fn set_band_plan_region(&mut self, region: Region) {
+        self.settings.band_plan_region = region;
+        self.settings.store(&mut self.storage);
+    }
+
+    pub fn set_license_class(&mut self, class: LicenseClass) {
+        self.settings.license_class = class;
+        self.settings.store(&mut self.storage);
+    }
+
+    pub fn set_mode_color(&mut self, mode: String, color: String) {
+        self.settings.mode_colors.insert(mode.to_uppercase(), color);
+        self.settings.store(&mut self.storage);
+    }
+
+    pub fn set_lookup_provider(&mut self, index: usize) {
+        self.settings.lookup_provider = index;
+        self.settings.store(&mut self.storage);
+    }
+
+    pub fn update_new_lookup_provider(&mut self, name: String, url: String) {
+        self.new_lookup_provider = (name, url);
+    }
+
+    pub fn add_lookup_provider(&mut self) {
+        let (name, url) = &self.new_lookup_provider;
+        self.settings.add_lookup_provider(name, url);
+        self.settings.store(&mut self.storage);
+        self.new_lookup_provider = (String::new(), String::new());
+    }
+
+    pub fn remove_lookup_provider(&mut self, index: usize) {
+        self.settings.remove_lookup_provider(index);
+        self.settings.store(&mut self.storage);
+    }
+
+    pub fn set_locale(&mut self, locale: Locale) {
+        self.settings.locale = locale;
+        self.settings.store(&mut self.storage);
+        self.i18n = I18n::new(locale);
+    }
+
+    // Translated UI string for `key`
+    pub fn t(&self, key: &str) -> String {
+        self.i18n.t(key)
+    }
+
+    pub fn toggle_radio_list(&mut self) {
+        self.settings.show_radio_list = !self.settings.show_radio_list;
+        self.settings.store(&mut self.storage);
+    }
+
+    // Collapsed radio controls: radio count and a power dot per radio
+    fn radio_summary(&self) -> Html {
+        html! {
+            <a class="navbar-item radio-summary" title=self.t("show_radios") onclick=self.link.callback(|_| Msg::ToggleRadioList)>
+                <span>{ format!("{} {} ", self.radios.len(), self.t("radios")) }</span>
+                {
+                    for self.radios.iter().map(|radio| html! {
+                        <span class=if radio.running { "power-dot has-text-success" } else { "power-dot has-text-grey-light" } title=&radio.name>{ "●" }</span>
+                    })
+                }
+                <span class="icon is-small"><i class="fas fa-chevron-right"></i></span>
+            </a>
+        }
+    }
+
+    // Clicking the band already filtered on shows all bands again
+    pub fn set_band_filter(&mut self, band: Option<String>) {
+        self.band_filter =
+            match band {
+                Some(band) if self.band_filter.as_ref() == Some(&band) => None,
+                band => band,
+            };
+    }
+
+    fn on_band_filter(&self, spot: &Spot) -> bool {
+        let on_receiver =
+            match self.receiver_filter {
+                Some(receiver_id) => self.spots.heard_by(spot) == Some(receiver_id),
+                None => true,
+            };
+        match &self.band_filter {
+            Some(band) => on_receiver && Band::new(spot.tuned_frequency as i32).band().map(|b| b.to_string()).as_ref() == Some(band),
+            None => on_receiver,
+        }
+    }
+
+    // Nickname for a receiver, or "Receiver <id>"
+    fn receiver_label(&self, receiver_id: u32) -> String {
+        self.settings.receiver_name(receiver_id).cloned().unwrap_or(format!("{} {}", self.t("receiver"), receiver_id))
+    }
+
+    // Note shown once older spots have been dropped to stay within
+    // the spot limit, with a button for the next larger limit
+    fn trim_notice_view(&self) -> Html {
+        let limit = self.settings.spot_limit;
+        if limit == 0 || self.spots.trimmed() == 0 || self.spots.spots().len() < limit {
+            return html! {};
+        }
+        let raised = SPOT_LIMITS.iter().cloned().find(|l| *l > limit).unwrap_or(0);
+        let label = if raised == 0 { self.t("no_limit") } else { raised.to_string() };
+        html! {
+            <p class="help trim-notice">
+                { self.t("spots_trimmed").replacen("{}", &limit.to_string(), 1).replacen("{}", &self.spots.trimmed().to_string(), 1) }
+                <a onclick=self.link.callback(move |_| Msg::SetSpotLimit(raised))>{ format!("{} {}", self.t("raise_limit"), label) }</a>
+            </p>
+        }
+    }
+
+    // Limit the spot table to the spots one receiver heard
+    fn receiver_filter_view(&self) -> Html {
+        if self.receivers.len() < 2 {
+            return html! {};
+        }
+        html! {
+            <div class="select receiver-filter">
+                <select onchange=self.link.callback(|e: ChangeData|
+                    match e {
+                        ChangeData::Select(sel) => Msg::SetReceiverFilter(sel.value().parse().ok()),
+                        _ => Msg::None,
+                    } )>
+                    <option value="" selected=self.receiver_filter.is_none()>{ self.t("all_receivers") }</option>
+                    {
+                        for self.receivers.iter().map(|receiver| {
+                            html! { <option value=receiver.id.to_string() selected=self.receiver_filter == Some(receiver.id)>{ self.receiver_label(receiver.id) }</option> }
+                        })
+                    }
+                </select>
+            </div>
+        }
+    }
+
+    // Live spot total and per band counts for the header
+    fn band_counts_view(&self) -> Html {
+        let counts = self.spots.band_counts();
+        html! {
+            <div class="navbar-item band-counts">
+                <span class=if self.band_filter.is_none() { "tag is-info" } else { "tag" } title=self.t("all_bands")
+                    onclick=self.link.callback(|_| Msg::SetBandFilter(None))>
+                    { self.spots.spots().len() }
+                </span>
+                {
+                    for counts.into_iter().map(|(band, count)| {
+                        let class = if self.band_filter.as_ref() == Some(&band) { "tag is-link" } else { "tag is-light" };
+                        let label = format!("{}:{}", band, count);
+                        html! {
+                            <span class=class onclick=self.link.callback(move |_| Msg::SetBandFilter(Some(band.clone())))>{ label }</span>
+                        }
+                    })
+                }
+            </div>
+        }
+    }
+
+    pub fn toggle_receiver_list(&mut self) {
+        self.show_receiver_list = !self.show_receiver_list;
+    }
+
+    pub fn show_receiver_list(&mut self) {
+        self.show_receiver_list = true
+    }
+
+    pub fn hide_receiver_list(&mut self) {
+        self.show_receiver_list = false
+    }
+
+    pub fn export_settings(&self) -> String {
+        let export = SettingsExport {
+            settings: self.settings.clone(),
+            spot_filters: self.spots.filters().clone(),
+            call_notes: self.call_notes.clone(),
+            qsos: self.log_entries.clone(),
+            macros: self.macros.clone(),
+            blocklist: self.spots.blocklist().into_iter().cloned().collect(),
+            worked: self.spots.worked().clone(),
+        };
+        serde_json::to_string_pretty(&export).unwrap_or_default()
+    }
+
+    // Download the settings backup, built only when asked for since it
+    // includes the whole logbook
+    pub fn download_settings(&mut self) {
+        let url = format!("data:application/json;charset=utf-8,{}", js_sys::encode_uri_component(&self.export_settings()));
+        if let Err(err) = spot_image::download(&url, "sparksdr-settings.json") {
+            self.show_error(format!("unable to export settings: {:?}", err));
+        }
+    }
+
+    pub fn read_settings_file(&mut self, file: File) {
+        let callback = self.link.callback(Msg::SettingsFileLoaded);
+        match self.reader.read_file(file, callback) {
+            Ok(task) => self.tasks.push(task),
+            Err(err) => self.show_error(format!("unable to read settings file: {}", err)),
+        }
+    }
+
+    pub fn load_settings_file(&mut self, data: FileData) {
+        match serde_json::from_slice::<SettingsExport>(&data.content) {
+            Ok(import) => self.settings_import = Some(import),
+            Err(err) => self.show_error(format!("invalid settings file {}: {}", data.name, err)),
+        }
+    }
+
+    pub fn apply_settings_import(&mut self, merge: bool) {
+        if let Some(import) = self.settings_import.take() {
+            let mut filters = import.spot_filters;
+            let (mut qsos, mut macros) = (import.qsos, import.macros);
+            if merge {
+                self.settings.merge(import.settings);
+                filters.extend(self.spots.filters().iter().cloned());
+                self.call_notes.merge(import.call_notes);
+                // contacts and macros already here aren't added twice
+                qsos.retain(|q| !self.log_entries.iter().any(|e| e.call == q.call && e.time == q.time));
+                qsos = std::mem::take(&mut self.log_entries).into_iter().chain(qsos).collect();
+                macros.retain(|m| !self.macros.iter().any(|existing| existing.name == m.name));
+                macros = std::mem::take(&mut self.macros).into_iter().chain(macros).collect();
+            } else {
+                self.settings = import.settings;
+                self.call_notes = import.call_notes;
+            }
+            self.settings.store(&mut self.storage);
+            self.spots.set_filters(filters);
+            self.call_notes.store(&mut self.storage);
+            self.log_entries = qsos;
+            store_qsos(&mut self.storage, &self.log_entries);
+            self.update_dupe_keys();
+            self.macros = macros;
+            store_macros(&mut self.storage, &self.macros);
+            self.spots.import_lists(import.blocklist, import.worked, merge);
+            self.i18n = I18n::new(self.settings.locale);
+            self.audio.set_squelch(&self.link, self.settings.squelch());
+        }
+    }
+
+    // Download the recorded frames, built only when asked for since
+    // the log can hold thousands of frames
+    pub fn export_frames(&mut self) {
+        let url = format!("data:application/json;charset=utf-8,{}", js_sys::encode_uri_component(&self.frame_log.export()));
+        if let Err(err) = spot_image::download(&url, "sparksdr-frames.json") {
+            self.show_error(format!("unable to export frames: {:?}", err));
+        }
+    }
+
+    pub fn read_replay_file(&mut self, file: File) {
+        let callback = self.link.callback(Msg::ReplayFileLoaded);
+        match self.reader.read_file(file, callback) {
+            Ok(task) => self.tasks.push(task),
+            Err(err) => self.show_error(format!("unable to read recording: {}", err)),
+        }
+    }
+
+    pub fn load_replay_file(&mut self, data: FileData) {
+        match serde_json::from_slice::<Vec<Frame>>(&data.content) {
+            Ok(frames) => self.replay.load(frames),
+            Err(err) => self.show_error(format!("invalid recording {}: {}", data.name, err)),
+        }
+    }
+
+    pub fn cancel_settings_import(&mut self) {
+        self.settings_import = None;
+    }
+
+    pub fn read_file(&mut self, file: File) {
+        let task = {
+            let callback = self.link.callback(|data| Msg::Loaded(data));
+            self.reader.read_file(file, callback).unwrap()
+        };
+        self.tasks.push(task);
+    }
+
+    // Parsed in the worker, the entries arrive as Msg::WorkerDone
+    pub fn load_adif_data(&mut self, data: FileData) {
+        self.worker.send(WorkerRequest::ImportAdif(String::from_utf8_lossy(&data.content).to_string()));
+    }
+
+    pub fn worker_done(&mut self, response: WorkerResponse) {
+        match response {
+            WorkerResponse::AdifImported { entries, skipped } => {
+                if skipped > 0 {
+                    ConsoleService::log(&format!("skipped {} adif records that didn't parse", skipped));
+                }
+                self.spots.add_worked_log(&entries);
+                self.import = Some(entries);
+                self.storage.store(LOGBOOK_KEY, Json(&self.import));
+                self.update_state_map_overlay();
+                self.update_dupe_keys();
+            },
+            WorkerResponse::Failed(err) => {
+                ConsoleService::error(&err);
+            }
+        }
+    }
+
+    pub fn clear_adif_data(&mut self) {
+        self.import = None;
+        self.storage.store(LOGBOOK_KEY, Json(&self.import));
+        self.update_state_map_overlay();
+        self.update_dupe_keys();
+    }
+
+    pub fn get_radio_power_state(&self, radio_id: u32) -> Option<bool> {
+        if let Some(index) = self.radios.iter().position(|i| i.id == radio_id) {
+            Some(self.radios[index].running)
+        } else {
+            None
+        }
+    }
+
+    pub fn toggle_receivers_button(&self) -> Html {
+        let cls = if self.show_receiver_list == true {
+            "fa-chevron-up"
+        } else {
+            "fa-chevron-down"
+        };
+        html! {
+            <button class="button is-text" onclick=self.link.callback(move |_| Msg::ToggleReceiverList)>
+                <span>{ format!("{} {}", self.receivers.len(), self.t("receivers")) }</span>
+                <span class="icon is-small">
+                    <i class=("fas", cls)></i>
+                </span>
+            </button>
+        }
+    }
+
+    // Default receiver and the latest few spots, without the
+    // navbar, spectrum, map and settings
+    pub fn mini_view(&self) -> Html {
+        html! {
+            <div class="mini">
+                { self.audio_prompt_view() }
+                { self.toasts_view() }
+                { self.dupe_warning_view() }
+                {
+                    match self.default_receiver() {
+                        Some(receiver) => self.receiver(&receiver),
+                        None if self.receivers.is_empty() => html! { <p class="has-text-grey">{ self.t("no_radios") }</p> },
+                        None => html! { <p class="has-text-grey">{ self.t("no_default_receiver") }</p> },
+                    }
+                }
+                <ul class="spot-ticker">
+                    {
+                        for self.spots.newest_first().into_iter().take(MINI_SPOTS).map(|spot| {
+                            let (call, frequency, mode) = (spot.call.call(), spot.tuned_frequency, spot.mode.clone());
+                            html! {
+                                <li key=SpotKey::new(spot).id() class="clickable" onclick=self.link.callback(move |_| Msg::SpotClicked(call.clone(), frequency, mode.clone()))>
+                                    <strong>{ spot.call.call() }</strong>
+                                    <span>{ format!(" {} {} ", format_snr(spot.snr as f64), spot.mode.mode()) }</span>
+                                    <span>{ spot.msg.clone().unwrap_or_default() }</span>
+                                </li>
+                            }
+                        })
+                    }
+                </ul>
+                { self.spot_menu_view() }
+                { self.qso_form_view() }
+                { self.note_form_view() }
+                { self.name_form_view() }
+                { self.keypad_view() }
+            </div>
+        }
+    }
+
+    pub fn toggle_spot_selection(&mut self, key: SpotKey) {
+        if !self.selected_spots.remove(&key) {
+            self.selected_spots.insert(key);
+        }
+    }
+
+    // Selected spots still in the table, newest first
+    fn selected_spots(&self) -> Vec<&Spot> {
+        self.spots.newest_first().into_iter()
+            .filter(|spot| self.selected_spots.contains(&SpotKey::new(spot)))
+            .collect()
+    }
+
+    pub fn log_selected_spots(&mut self) {
+        let selected = self.selected_spots();
+        let entries: Vec<QsoEntry> = selected.iter().map(|spot| QsoEntry::from_spot(spot)).collect();
+        let calls: Vec<Call> = selected.iter().map(|spot| spot.call.clone()).collect();
+        for call in &calls {
+            self.spots.add_worked_call(call);
+        }
+        ConsoleService::log(&format!("logged {} contacts from selected spots", entries.len()));
+        self.log_entries.extend(entries);
+        store_qsos(&mut self.storage, &self.log_entries);
+        self.update_dupe_keys();
+        self.selected_spots.clear();
+    }
+
+    // Logged contacts as ADIF, built on click rather than on every
+    // render of the settings page
+    pub fn export_log(&mut self) {
+        let url = format!("data:text/plain;charset=utf-8,{}", js_sys::encode_uri_component(&export_adif(&self.log_entries)));
+        if let Err(err) = spot_image::download(&url, "sparksdr-log.adi") {
+            self.show_error(format!("unable to export log: {:?}", err));
+        }
+    }
+
+    // ADIF for the selected spots, built on click since the spot table
+    // re-renders several times a second
+    pub fn export_selected_spots(&mut self) {
+        let entries: Vec<QsoEntry> = self.selected_spots().iter().map(|spot| QsoEntry::from_spot(spot)).collect();
+        let url = format!("data:text/plain;charset=utf-8,{}", js_sys::encode_uri_component(&export_adif(&entries)));
+        if let Err(err) = spot_image::download(&url, "selected-spots.adi") {
+            self.show_error(format!("unable to export spots: {:?}", err));
+        }
+    }
+
+    // Actions for the selected spots, shown above the table
+    fn spot_selection_view(&self) -> Html {
+        let selected = self.selected_spots();
+        if selected.is_empty() {
+            return html! {};
+        }
+        html! {
+            <div class="notification is-info is-light spot-selection">
+                <span>{ self.t("spots_selected").replace("{}", &selected.len().to_string()) }</span>
+                <button class="button is-small" onclick=self.link.callback(|_| Msg::ExportSelectedSpots)>{ self.t("export_log") }</button>
+                <button class="button is-small" onclick=self.link.callback(|_| Msg::LogSelectedSpots)>{ self.t("log_selected") }</button>
+                <button class="button is-small" onclick=self.link.callback(|_| Msg::ClearSpotSelection)>{ self.t("clear_selection") }</button>
+            </div>
+        }
+    }
+
+    // Newest spots drawn to a PNG headed with my call, the default
+    // receiver's band and the time
+    pub fn export_spots_image(&mut self) {
+        let band = self.default_receiver()
+            .and_then(|receiver| Band::new(receiver.frequency as i32).band())
+            .map(|band| band.to_string())
+            .unwrap_or_default();
+        let title = format!("{} {} {}", self.settings.my_call, band, Utc::now().format("%Y-%m-%d %H:%M UTC")).trim().to_string();
+
+        let mut rows = vec![vec![
+            self.t("col_utc"), self.t("col_call"), self.t("col_db"), self.t("col_freq"), self.t("col_mode"),
+            self.t("col_dist"), self.t("col_message"), self.t("col_country"),
+        ]];
+        rows.extend(self.spots.newest_first().into_iter().take(SPOT_IMAGE_ROWS).map(|spot| vec![
+            spot.time.format("%H%M%S").to_string(),
+            spot.call.call(),
+            format_snr(spot.snr as f64),
+            format!("{:.0}", spot.frequency),
+            spot.mode.mode().to_string(),
+            spot.distance.map(|dist| format!("{}", dist)).unwrap_or_default(),
+            spot.msg.clone().unwrap_or_default(),
+            spot.call.country().map(|country| self.i18n.country(&country.code(), &country.name())).unwrap_or_default(),
+        ]));
+
+        let name = format!("spots-{}.png", Utc::now().format("%Y%m%d-%H%M%S"));
+        let exported = spot_image::render_png(&title, &rows).and_then(|url| spot_image::download(&url, &name));
+        if let Err(err) = exported {
+            self.show_error(format!("unable to export spots: {:?}", err));
+        }
+    }
+
+    pub fn receiver_list_control(&self) -> Html {
+        html! {
             <div class="receivers">
             {
                 for self.receivers.iter().map(|r| {
@@ -562,6 +2838,57 @@ impl Model {
         }
     }
 
+    // Band scope drawn from the spectrum frames, with markers at the
+    // frequencies of spots inside the displayed span
+    pub fn band_scope_view(&self) -> Html {
+        let markers: Vec<(f64, &Spot)> = self.spots.newest_first().into_iter()
+            .filter_map(|s| self.spectrum.position(s.frequency as f64).map(|p| (p, s)))
+            .take(SCOPE_MARKERS)
+            .collect();
+        let cursor =
+            match self.default_receiver().and_then(|r| self.spectrum.position(r.frequency as f64)) {
+                Some(position) => html! { <div class="scope-cursor" style=format!("left:{:.2}%", position * 100.0)></div> },
+                None => html! {},
+            };
+        // click to tune, drag with the button held to fine tune
+        let scope_fraction = |e: &MouseEvent| -> Option<f64> {
+            let rect = e.current_target()?.dyn_into::<Element>().ok()?.get_bounding_client_rect();
+            match rect.width() > 0.0 {
+                true => Some((e.client_x() as f64 - rect.left()) / rect.width()),
+                false => None,
+            }
+        };
+        html! {
+            <div class="band-scope" title=self.t("scope_tune_help")
+                onmousedown=self.link.callback(move |e: MouseEvent| {
+                    scope_fraction(&e).map(Msg::ScopeTune).unwrap_or(Msg::None)
+                })
+                onmousemove=self.link.callback(move |e: MouseEvent| {
+                    match e.buttons() & 1 {
+                        1 => scope_fraction(&e).map(Msg::ScopeTune).unwrap_or(Msg::None),
+                        _ => Msg::None,
+                    }
+                })
+                onmouseup=self.link.callback(|_| Msg::ScopeTuneDone)
+                onmouseleave=self.link.callback(|_| Msg::ScopeTuneDone)>
+                <canvas ref=self.spectrum.scope_node_ref.clone() width="2048" height="80" />
+                { cursor }
+                { for markers.iter().map(|(position, spot)| {
+                    let (call, frequency, mode) = (spot.call.call(), spot.tuned_frequency, spot.mode.clone());
+                    html! {
+                        <div key=SpotKey::new(spot).id() class="scope-spot" style=format!("left:{:.2}%", position * 100.0)
+                            title=format!("{} {:.0} Hz {}", call, spot.frequency, spot.mode.mode())
+                            onmousedown=self.link.callback(|e: MouseEvent| { e.stop_propagation(); Msg::None })
+                            onclick=self.link.callback(move |_| Msg::SpotClicked(call.clone(), frequency, mode.clone()))>
+                            <span>{ spot.call.call() }</span>
+                        </div>
+                    }
+                  })
+                }
+            </div>
+        }
+    }
+
     pub fn spots_view(&self) -> Html {
         let table_class =
             match self.default_receiver() {
@@ -570,37 +2897,90 @@ impl Model {
                 },
                 _ => "table is-narrow is-fullwidth",
             };
+        let table_class = format!("{} density-{}", table_class, self.settings.table_density.name());
+        let spots: Vec<&Spot> = self.spots.newest_first().into_iter().filter(|s| self.on_band_filter(s)).collect();
 
         html! {
             <>
                 <div style="text-align:right;margin-top:10px">
-                    <button class="button" onclick=self.link.callback(move |_| Msg::ClearSpots)>
-                        <span class="icon is-small">
-                            <i class="far fa-trash-alt"></i>
-                        </span>
-                    </button>
+                    {
+                        if self.confirm_clear_spots {
+                            html! {
+                                <div class="field is-grouped clear-spots">
+                                    <span>{ self.t("clear_spots_confirm").replace("{}", &self.spots.spots().len().to_string()) }</span>
+                                    <button class="button is-danger" onclick=self.link.callback(|_| Msg::ConfirmClearSpots(false))>{ self.t("clear_all") }</button>
+                                    {
+                                        if self.spots.has_pinned() {
+                                            html! { <button class="button is-link" onclick=self.link.callback(|_| Msg::ConfirmClearSpots(true))>{ self.t("keep_pinned") }</button> }
+                                        } else {
+                                            html! {}
+                                        }
+                                    }
+                                    <button class="button" onclick=self.link.callback(|_| Msg::CancelClearSpots)>{ self.t("cancel") }</button>
+                                </div>
+                            }
+                        } else {
+                            html! {
+                                <>
+                                { self.receiver_filter_view() }
+                                <button class="button" title=self.t("export_spots_image") onclick=self.link.callback(|_| Msg::ExportSpotsImage)>
+                                    <span class="icon is-small">
+                                        <i class="far fa-image"></i>
+                                    </span>
+                                </button>
+                                <button class="button" title=self.t("clear_spots") onclick=self.link.callback(move |_| Msg::ClearSpots)>
+                                    <span class="icon is-small">
+                                        <i class="far fa-trash-alt"></i>
+                                    </span>
+                                </button>
+                                </>
+                            }
+                        }
+                    }
                 </div>
+                { self.spot_selection_view() }
+                { self.trim_notice_view() }
                 <div class="s">
-                    <table class=table_class>
+                    <table class=table_class ref=self.spot_table.clone()>
                         <tr>
-                            <th>{ "UTC" }</th>
-                            <th>{ "dB" }</th>
-                            <th>{ "DT" }</th>
-                            <th class="freqc">{ "Freq" }</th>
-                            <th class="modec">{ "Mode" }</th>
-                            <th>{ "Dist" }</th>
-                            <th>{ "Message" }</th>
+                            <th></th>
+                            <th>{ match self.settings.time_display {
+                                    TimeDisplay::Utc => self.t("col_utc"),
+                                    TimeDisplay::Local => self.t("col_local"),
+                                } }</th>
+                            <th>{ self.t("col_db") }</th>
+                            <th>{ self.t("col_dt") }</th>
+                            <th class="freqc">{ self.t("col_freq") }</th>
+                            <th class="modec">{ self.t("col_mode") }</th>
+                            <th>{ self.t("col_rx") }</th>
+                            <th>{ self.t("col_dist") }</th>
+                            <th>{ self.t("col_message") }</th>
+                            {
+                                match self.settings.show_dxcc {
+                                    true => html! { <th>{ self.t("col_dxcc") }</th> },
+                                    false => html! {}
+                                }
+                            }
                             <th></th>
                             <th></th>
                             <th></th>
                             {
                                 match self.spots.has_lotw_users() {
-                                    true => html! { <th>{ "LoTW" }</th> },
+                                    true => html! { <th>{ self.t("lotw") }</th> },
                                     false => html! {}
                                 }
                             }
                         </tr>
-                        { for self.spots.spots().iter().rev().map(|s| {
+                        // pinned spots then stations calling me float to the top
+                        { for spots.iter().filter(|s| self.spots.is_pinned(s)).map(|s| {
+                            self.spot(&s)
+                          })
+                        }
+                        { for spots.iter().filter(|s| !self.spots.is_pinned(s) && is_calling(s, &self.settings.my_call)).map(|s| {
+                            self.spot(&s)
+                          })
+                        }
+                        { for spots.iter().filter(|s| !self.spots.is_pinned(s) && !is_calling(s, &self.settings.my_call)).map(|s| {
                             self.spot(&s)
                           })
                         }
@@ -622,90 +3002,860 @@ impl Model {
                 <table class="table is-fullwidth">
                     <thead>
                         <tr>
-                            <th colspan="2">{ "Spot Filters" }</th>
+                            <th colspan="2">{ self.t("spot_filters") }</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        <tr>
+                            <td>{ self.t("cq_only") }</td>
+                            <td style="text-align:right">
+                                <label class="switch">
+                                    <input id="switchColorDefault" type="checkbox" name="switchColorDefault" checked=self.spots.cq_only_spot_filter_enabled() onclick=self.link.callback(move |_| Msg::ToggleCQSpotFilter ) />
+                                    <span class="slider"></span>
+                                </label>
+                            </td>
+                        </tr>
+                        { if default_receiver_has_spots {
+                            html! {
+                                <tr>
+                                    <td>{ self.t("current_rx") }</td>
+                                    <td style="text-align:right">
+                                        <label class="switch">
+                                            <input id="switchColorDefault" type="checkbox" name="switchColorDefault" checked=self.spots.current_receiver_spot_filter_enabled() onclick=self.link.callback(move |_| Msg::ToggleCurrentReceiverSpotFilter ) />
+                                            <span class="slider"></span>
+                                        </label>
+                                    </td>
+                                </tr>
+                            } } else {
+                                html! {}
+                            }
+                        }
+                        { if self.spots.has_lotw_users() {
+                            html! {
+                                <tr>
+                                    <td>{ self.t("lotw") }</td>
+                                    <td style="text-align:right">
+                                        <label class="switch">
+                                            <input id="switchColorDefault" type="checkbox" name="switchColorDefault" checked=self.spots.lotw_spot_filter_enabled() onclick=self.link.callback(move |_| Msg::ToggleLoTWSpotFilter ) />
+                                            <span class="slider"></span>
+                                        </label>
+                                    </td>
+                                </tr>
+                            } } else {
+                                html! {}
+                            }
+                        }
+                        { if self.import.is_some() || !self.spots.worked().is_empty() {
+                              html! {
+                                <>
+                                <tr>
+                                    <td>{ self.t("new_state") }</td>
+                                    <td style="text-align:right">
+                                        <label class="switch">
+                                            <input id="switchColorDefault" type="checkbox" name="switchColorDefault" checked=self.spots.state_spot_filter_enabled() onclick=self.link.callback(move |_| Msg::ToggleStateSpotFilter ) />
+                                            <span class="slider"></span>
+                                        </label>
+                                    </td>
+                                </tr>
+                                <tr>
+                                    <td>{ self.t("new_country") }</td>
+                                    <td style="text-align:right">
+                                        <label class="switch">
+                                            <input id="switchColorDefault" type="checkbox" name="switchColorDefault" checked=self.spots.country_spot_filter_enabled() onclick=self.link.callback(move |_| Msg::ToggleCountrySpotFilter ) />
+                                            <span class="slider"></span>
+                                        </label>
+                                    </td>
+                                </tr>
+                                </>
+                              }
+                          } else {
+                              html! {}
+                        }}
+                    </tbody>
+                    <thead>
+                        <tr>
+                            <th colspan="2">{ self.t("log_file") }</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        <tr>
+                            <td colspan="2">{ self.import_adif_form() }</td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("worked_entities")
+                                    .replacen("{}", &self.spots.worked().countries().len().to_string(), 1)
+                                    .replacen("{}", &self.spots.worked().states().len().to_string(), 1) }</td>
+                            <td style="text-align:right">
+                                <button class="button is-small" title=self.t("clear_worked") onclick=self.link.callback(|_| Msg::ClearWorked)>
+                                    <span class="icon is-small">
+                                        <i class="far fa-trash-alt"></i>
+                                    </span>
+                                </button>
+                            </td>
+                        </tr>
+                        {
+                            if self.spots.worked().is_empty() {
+                                html! {}
+                            } else {
+                                html! {
+                                    <tr>
+                                        <td colspan="2">
+                                            <div class="tags worked">
+                                                {
+                                                    for self.spots.worked().countries().iter().map(|code| {
+                                                        let code = code.clone();
+                                                        html! {
+                                                            <span class="tag">
+                                                                { &code }
+                                                                <button class="delete is-small" title=self.t("unmark_worked") onclick=self.link.callback(move |_| Msg::UnmarkWorkedCountry(code.clone()))></button>
+                                                            </span>
+                                                        }
+                                                    })
+                                                }
+                                                {
+                                                    for self.spots.worked().states().iter().map(|state| {
+                                                        let state = state.clone();
+                                                        html! {
+                                                            <span class="tag is-info is-light">
+                                                                { &state }
+                                                                <button class="delete is-small" title=self.t("unmark_worked") onclick=self.link.callback(move |_| Msg::UnmarkWorkedState(state.clone()))></button>
+                                                            </span>
+                                                        }
+                                                    })
+                                                }
+                                            </div>
+                                        </td>
+                                    </tr>
+                                }
+                            }
+                        }
+                        <tr>
+                            <td>{ self.t("logged_contacts").replace("{}", &self.log_entries.len().to_string()) }</td>
+                            <td style="text-align:right">
+                                {
+                                    if self.log_entries.is_empty() {
+                                        html! {}
+                                    } else {
+                                        html! {
+                                            <button class="button is-small" title=self.t("export_log") onclick=self.link.callback(|_| Msg::ExportLog)>
+                                                <span class="icon is-small">
+                                                    <i class="fas fa-download"></i>
+                                                </span>
+                                            </button>
+                                        }
+                                    }
+                                }
+                            </td>
+                        </tr>
+                    </tbody>
+                </table>
+            </div>
+        }
+    }
+    pub fn settings_view(&self) -> Html {
+        let (new_name, new_url) = self.new_lookup_provider.clone();
+        let mut mode_colors: Vec<(String, String)> = self.settings.mode_colors.iter().map(|(m, c)| (m.clone(), c.clone())).collect();
+        mode_colors.sort();
+        html! {
+            <div class="settings">
+                <table class="table is-fullwidth">
+                    <thead>
+                        <tr>
+                            <th colspan="2">{ self.t("display") }</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        <tr>
+                            <td>{ self.t("frequency_format") }</td>
+                            <td style="text-align:right">
+                                <select class="select"
+                                    onchange=self.link.callback(|e: ChangeData|
+                                        match e {
+                                            ChangeData::Select(sel) => Msg::SetNumberFormat(NumberFormat::from_name(&sel.value())),
+                                            _ => Msg::None,
+                                        } )>
+                                    {
+                                        for NUMBER_FORMATS.iter().map(|format| {
+                                            html! { <option value=format.name() selected=*format == self.settings.number_format>{ format.example() }</option> }
+                                        })
+                                    }
+                                </select>
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("language") }</td>
+                            <td style="text-align:right">
+                                <select class="select"
+                                    onchange=self.link.callback(|e: ChangeData|
+                                        match e {
+                                            ChangeData::Select(sel) => Msg::SetLocale(Locale::from_code(&sel.value())),
+                                            _ => Msg::None,
+                                        } )>
+                                    {
+                                        for LOCALES.iter().map(|locale| {
+                                            html! { <option value=locale.code() selected=*locale == self.i18n.locale()>{ locale.name() }</option> }
+                                        })
+                                    }
+                                </select>
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("refresh_interval") }</td>
+                            <td style="text-align:right">
+                                <select class="select"
+                                    onchange=self.link.callback(|e: ChangeData|
+                                        match e {
+                                            ChangeData::Select(sel) => Msg::SetTickInterval(sel.value().parse().unwrap_or(1)),
+                                            _ => Msg::None,
+                                        } )>
+                                    {
+                                        for [0, 1, 5, 10, 30].iter().map(|seconds| {
+                                            let label = if *seconds == 0 { self.t("off") } else { format!("{}s", seconds) };
+                                            html! { <option value=seconds.to_string() selected=*seconds == self.settings.tick_interval>{ label }</option> }
+                                        })
+                                    }
+                                </select>
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("spot_times") }</td>
+                            <td style="text-align:right">
+                                <select class="select"
+                                    onchange=self.link.callback(|e: ChangeData|
+                                        match e {
+                                            ChangeData::Select(sel) => Msg::SetTimeDisplay(TimeDisplay::from_name(&sel.value())),
+                                            _ => Msg::None,
+                                        } )>
+                                    <option value=TimeDisplay::Utc.name() selected=self.settings.time_display == TimeDisplay::Utc>{ self.t("utc") }</option>
+                                    <option value=TimeDisplay::Local.name() selected=self.settings.time_display == TimeDisplay::Local>{ self.t("local_time") }</option>
+                                </select>
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("highlight_words") }</td>
+                            <td style="text-align:right">
+                                <input class="input" type="text"
+                                    value=self.settings.highlight_tokens.join(", ")
+                                    onchange=self.link.callback(|e: ChangeData|
+                                        match e {
+                                            ChangeData::Value(value) => Msg::SetHighlightTokens(value),
+                                            _ => Msg::None,
+                                        } ) />
+                            </td>
+                        </tr>
+                    </tbody>
+                    <thead>
+                        <tr>
+                            <th colspan="2">{ self.t("station") }</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        <tr>
+                            <td>{ self.t("my_call") }</td>
+                            <td style="text-align:right">
+                                <input class="input" type="text"
+                                    value=&self.settings.my_call
+                                    onchange=self.link.callback(|e: ChangeData|
+                                        match e {
+                                            ChangeData::Value(value) => Msg::SetMyCall(value),
+                                            _ => Msg::None,
+                                        } ) />
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("my_grid") }</td>
+                            <td style="text-align:right">
+                                <input class=if self.settings.my_grid.is_empty() || grid_to_lat_lon(&self.settings.my_grid).is_some() { "input" } else { "input is-danger" }
+                                    type="text" placeholder="EN52"
+                                    value=&self.settings.my_grid
+                                    onchange=self.link.callback(|e: ChangeData|
+                                        match e {
+                                            ChangeData::Value(value) => Msg::SetMyGrid(value),
+                                            _ => Msg::None,
+                                        } ) />
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("band_plan") }</td>
+                            <td style="text-align:right">
+                                <select class="select"
+                                    onchange=self.link.callback(|e: ChangeData|
+                                        match e {
+                                            ChangeData::Select(sel) => Msg::SetBandPlanRegion(Region::from_code(&sel.value())),
+                                            _ => Msg::None,
+                                        } )>
+                                    {
+                                        for REGIONS.iter().map(|region| {
+                                            html! { <option value=region.code() selected=*region == self.settings.band_plan_region>{ region.name() }</option> }
+                                        })
+                                    }
+                                </select>
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("license_class") }</td>
+                            <td style="text-align:right">
+                                <select class="select"
+                                    onchange=self.link.callback(|e: ChangeData|
+                                        match e {
+                                            ChangeData::Select(sel) => Msg::SetLicenseClass(LicenseClass::from_name(&sel.value())),
+                                            _ => Msg::None,
+                                        } )>
+                                    {
+                                        for LICENSE_CLASSES.iter().map(|class| {
+                                            html! { <option value=class.name() selected=*class == self.settings.license_class>{ self.t(&format!("license_{}", class.name())) }</option> }
+                                        })
+                                    }
+                                </select>
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("privilege_override") }</td>
+                            <td style="text-align:right">
+                                <label class="switch">
+                                    <input type="checkbox" checked=self.settings.privilege_override onclick=self.link.callback(move |_| Msg::TogglePrivilegeOverride ) />
+                                    <span class="slider"></span>
+                                </label>
+                            </td>
+                        </tr>
+                        {
+                            for self.receivers.iter().map(|receiver| {
+                                let receiver_id = receiver.id;
+                                html! {
+                                    <tr>
+                                        <td>{ format!("{} ({})", self.t("transverter_offset"), self.receiver_label(receiver_id)) }</td>
+                                        <td style="text-align:right">
+                                            <input class="input merge-tolerance" type="number" step="any"
+                                                value=(self.settings.transverter_offset(receiver_id) / 1_000_000.0).to_string()
+                                                onchange=self.link.callback(move |e: ChangeData|
+                                                    match e {
+                                                        ChangeData::Value(value) => match value.parse::<f64>() {
+                                                            Ok(mhz) => Msg::SetTransverterOffset(receiver_id, mhz * 1_000_000.0),
+                                                            Err(_) => Msg::None,
+                                                        },
+                                                        _ => Msg::None,
+                                                    } ) />
+                                            <span>{ " MHz" }</span>
+                                        </td>
+                                    </tr>
+                                }
+                            })
+                        }
+                        <tr>
+                            <td>{ self.t("alert_on_call") }</td>
+                            <td style="text-align:right">
+                                <label class="switch">
+                                    <input type="checkbox" checked=self.settings.alert_on_call onclick=self.link.callback(move |_| Msg::ToggleAlertOnCall ) />
+                                    <span class="slider"></span>
+                                </label>
+                            </td>
+                        </tr>
+                    </tbody>
+                    <thead>
+                        <tr>
+                            <th colspan="2">{ self.t("audio") }</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        <tr>
+                            <td>{ self.t("no_audio") }</td>
+                            <td style="text-align:right">
+                                <label class="switch">
+                                    <input type="checkbox" checked=self.settings.no_audio onclick=self.link.callback(move |_| Msg::ToggleNoAudio ) />
+                                    <span class="slider"></span>
+                                </label>
+                                {
+                                    match self.settings.no_audio != self.no_audio {
+                                        true => html! { <p class="help">{ self.t("reload_to_apply") }</p> },
+                                        false => html! {},
+                                    }
+                                }
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("output_device") }</td>
+                            <td style="text-align:right">
+                                <select class="select"
+                                    onchange=self.link.callback(|e: ChangeData|
+                                        match e {
+                                            ChangeData::Select(sel) => Msg::SetAudioOutputDevice(sel.value()),
+                                            _ => Msg::None,
+                                        } )>
+                                    <option value="" selected=self.settings.audio_output_device.is_none()>{ self.t("default_device") }</option>
+                                    {
+                                        for self.audio.output_devices().iter().map(|device| {
+                                            let selected = self.settings.audio_output_device.as_ref() == Some(&device.id);
+                                            html! { <option value=&device.id selected=selected>{ &device.label }</option> }
+                                        })
+                                    }
+                                </select>
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("squelch") }</td>
+                            <td style="text-align:right">
+                                <label class="switch">
+                                    <input type="checkbox" checked=self.settings.squelch_enabled onclick=self.link.callback(move |_| Msg::ToggleSquelch ) />
+                                    <span class="slider"></span>
+                                </label>
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("squelch_level") }</td>
+                            <td style="text-align:right">
+                                <input type="range" min="-100" max="0" step="1"
+                                    value=self.settings.squelch_level.to_string()
+                                    onchange=self.link.callback(|e: ChangeData|
+                                        match e {
+                                            ChangeData::Value(value) => Msg::SetSquelchLevel(value.parse().unwrap_or(-60.0)),
+                                            _ => Msg::None,
+                                        } ) />
+                                <span>{ format!(" {} dB", self.settings.squelch_level) }</span>
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("target_latency") }</td>
+                            <td style="text-align:right">
+                                <select class="select"
+                                    onchange=self.link.callback(|e: ChangeData|
+                                        match e {
+                                            ChangeData::Select(sel) => Msg::SetTargetLatency(sel.value().parse().unwrap_or(0.1)),
+                                            _ => Msg::None,
+                                        } )>
+                                    {
+                                        for TARGET_LATENCIES.iter().map(|seconds| {
+                                            html! { <option value=seconds.to_string() selected=*seconds == self.settings.target_latency>{ format!("{:.0} ms", seconds * 1000.0) }</option> }
+                                        })
+                                    }
+                                </select>
+                                {
+                                    match self.audio.frequent_underruns() {
+                                        true => html! { <p class="help is-danger">{ self.t("audio_underruns") }</p> },
+                                        false => html! {},
+                                    }
+                                }
+                            </td>
+                        </tr>
+                    </tbody>
+                    <thead>
+                        <tr>
+                            <th colspan="2">{ self.t("spots") }</th>
                         </tr>
                     </thead>
                     <tbody>
                         <tr>
-                            <td>{ "CQ Only" }</td>
+                            <td>{ self.t("spot_limit") }</td>
+                            <td style="text-align:right">
+                                <select class="select"
+                                    onchange=self.link.callback(|e: ChangeData|
+                                        match e {
+                                            ChangeData::Select(sel) => Msg::SetSpotLimit(sel.value().parse().unwrap_or(100)),
+                                            _ => Msg::None,
+                                        } )>
+                                    {
+                                        for SPOT_LIMITS.iter().map(|limit| {
+                                            let label = if *limit == 0 { self.t("no_limit") } else { limit.to_string() };
+                                            html! { <option value=limit.to_string() selected=*limit == self.settings.spot_limit>{ label }</option> }
+                                        })
+                                    }
+                                </select>
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("spot_max_age") }</td>
+                            <td style="text-align:right">
+                                <select class="select"
+                                    onchange=self.link.callback(|e: ChangeData|
+                                        match e {
+                                            ChangeData::Select(sel) => Msg::SetSpotMaxAge(sel.value().parse().unwrap_or(0)),
+                                            _ => Msg::None,
+                                        } )>
+                                    {
+                                        for [0, 5, 15, 30, 60].iter().map(|minutes| {
+                                            let label = if *minutes == 0 { self.t("off") } else { format!("{} min", minutes) };
+                                            html! { <option value=minutes.to_string() selected=*minutes == self.settings.spot_max_age>{ label }</option> }
+                                        })
+                                    }
+                                </select>
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("blocklist") }</td>
+                            <td style="text-align:right">
+                                <div class="tags blocklist">
+                                    {
+                                        for self.spots.blocklist().into_iter().map(|entry| {
+                                            let entry = entry.clone();
+                                            html! {
+                                                <span class="tag">
+                                                    { &entry }
+                                                    <button class="delete is-small" title=self.t("unblock") onclick=self.link.callback(move |_| Msg::UnblockCall(entry.clone()))></button>
+                                                </span>
+                                            }
+                                        })
+                                    }
+                                </div>
+                                <div class="field has-addons" style="justify-content:flex-end">
+                                    <input class="input" type="text" placeholder="VK*" value=&self.new_block
+                                        oninput=self.link.callback(|e: InputData| Msg::UpdateNewBlock(e.value)) />
+                                    <button class="button" onclick=self.link.callback(|_| Msg::BlockCall(String::new()))>{ self.t("add") }</button>
+                                </div>
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("merge_spots") }</td>
+                            <td style="text-align:right">
+                                <input class="input merge-tolerance" type="number" min="0" step="10"
+                                    value=self.settings.merge_frequency.to_string()
+                                    onchange=self.link.callback(|e: ChangeData|
+                                        match e {
+                                            ChangeData::Value(value) => Msg::SetMergeFrequency(value.parse().unwrap_or(50.0)),
+                                            _ => Msg::None,
+                                        } ) />
+                                <span>{ " Hz " }</span>
+                                <input class="input merge-tolerance" type="number" min="0" step="1"
+                                    value=self.settings.merge_seconds.to_string()
+                                    onchange=self.link.callback(|e: ChangeData|
+                                        match e {
+                                            ChangeData::Value(value) => Msg::SetMergeSeconds(value.parse().unwrap_or(5)),
+                                            _ => Msg::None,
+                                        } ) />
+                                <span>{ " s" }</span>
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("distance_colors") }</td>
+                            <td style="text-align:right">
+                                {
+                                    for self.settings.distance_thresholds.iter().enumerate().map(|(bucket, km)| {
+                                        html! {
+                                            <>
+                                                <span class=format!("distance-{}", DISTANCE_BUCKETS[bucket])>{ format!(" {} < ", self.t(DISTANCE_BUCKETS[bucket])) }</span>
+                                                <input class="input merge-tolerance" type="number" min="0" step="100"
+                                                    value=km.to_string()
+                                                    onchange=self.link.callback(move |e: ChangeData|
+                                                        match e {
+                                                            ChangeData::Value(value) => match value.parse() {
+                                                                Ok(km) => Msg::SetDistanceThreshold(bucket, km),
+                                                                Err(_) => Msg::None,
+                                                            },
+                                                            _ => Msg::None,
+                                                        } ) />
+                                            </>
+                                        }
+                                    })
+                                }
+                                <span class="distance-dx">{ format!(" km, {}", self.t(DISTANCE_BUCKETS[3])) }</span>
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("dt_colors") }</td>
+                            <td style="text-align:right">
+                                {
+                                    for self.settings.dt_thresholds.iter().enumerate().map(|(bucket, seconds)| {
+                                        html! {
+                                            <>
+                                                <span class=format!("dt-{}", DT_BUCKETS[bucket])>{ format!(" {} ≤ ", self.t(&format!("dt_{}", DT_BUCKETS[bucket]))) }</span>
+                                                <input class="input merge-tolerance" type="number" min="0" step="0.1"
+                                                    value=seconds.to_string()
+                                                    onchange=self.link.callback(move |e: ChangeData|
+                                                        match e {
+                                                            ChangeData::Value(value) => match value.parse() {
+                                                                Ok(seconds) => Msg::SetDtThreshold(bucket, seconds),
+                                                                Err(_) => Msg::None,
+                                                            },
+                                                            _ => Msg::None,
+                                                        } ) />
+                                            </>
+                                        }
+                                    })
+                                }
+                                <span class="dt-poor">{ format!(" s, {}", self.t("dt_poor")) }</span>
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("col_dxcc") }</td>
                             <td style="text-align:right">
                                 <label class="switch">
-                                    <input id="switchColorDefault" type="checkbox" name="switchColorDefault" checked=self.spots.cq_only_spot_filter_enabled() onclick=self.link.callback(move |_| Msg::ToggleCQSpotFilter ) />
+                                    <input type="checkbox" checked=self.settings.show_dxcc onclick=self.link.callback(move |_| Msg::ToggleDxccColumn ) />
                                     <span class="slider"></span>
                                 </label>
                             </td>
                         </tr>
-                        { if default_receiver_has_spots {
-                            html! {
-                                <tr>
-                                    <td>{ "Current Rx" }</td>
-                                    <td style="text-align:right">
-                                        <label class="switch">
-                                            <input id="switchColorDefault" type="checkbox" name="switchColorDefault" checked=self.spots.current_receiver_spot_filter_enabled() onclick=self.link.callback(move |_| Msg::ToggleCurrentReceiverSpotFilter ) />
-                                            <span class="slider"></span>
-                                        </label>
-                                    </td>
-                                </tr>
-                            } } else {
-                                html! {}
-                            }
+                        <tr>
+                            <td>{ self.t("spot_click_action") }</td>
+                            <td style="text-align:right">
+                                <select class="select"
+                                    onchange=self.link.callback(|e: ChangeData|
+                                        match e {
+                                            ChangeData::Select(sel) => Msg::SetSpotClickAction(SpotClickAction::from_name(&sel.value())),
+                                            _ => Msg::None,
+                                        } )>
+                                    {
+                                        for SPOT_CLICK_ACTIONS.iter().map(|action| {
+                                            html! { <option value=action.name() selected=*action == self.settings.spot_click_action>{ self.t(&format!("action_{}", action.name())) }</option> }
+                                        })
+                                    }
+                                </select>
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("table_density") }</td>
+                            <td style="text-align:right">
+                                <select class="select"
+                                    onchange=self.link.callback(|e: ChangeData|
+                                        match e {
+                                            ChangeData::Select(sel) => Msg::SetTableDensity(TableDensity::from_name(&sel.value())),
+                                            _ => Msg::None,
+                                        } )>
+                                    {
+                                        for TABLE_DENSITIES.iter().map(|density| {
+                                            html! { <option value=density.name() selected=*density == self.settings.table_density>{ self.t(&format!("density_{}", density.name())) }</option> }
+                                        })
+                                    }
+                                </select>
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("spot_fade") }</td>
+                            <td style="text-align:right">
+                                <label class="switch">
+                                    <input type="checkbox" checked=self.settings.spot_fade onclick=self.link.callback(move |_| Msg::ToggleSpotFade ) />
+                                    <span class="slider"></span>
+                                </label>
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("flag_source") }</td>
+                            <td style="text-align:right">
+                                <select class="select"
+                                    onchange=self.link.callback(|e: ChangeData|
+                                        match e {
+                                            ChangeData::Select(sel) => Msg::SetFlagSource(FlagSource::from_name(&sel.value())),
+                                            _ => Msg::None,
+                                        } )>
+                                    {
+                                        for FLAG_SOURCES.iter().map(|source| {
+                                            html! { <option value=source.name() selected=*source == self.settings.flag_source>{ self.t(&format!("flags_{}", source.name())) }</option> }
+                                        })
+                                    }
+                                </select>
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("lookup_provider") }</td>
+                            <td style="text-align:right">
+                                <select class="select"
+                                    onchange=self.link.callback(|e: ChangeData|
+                                        match e {
+                                            ChangeData::Select(sel) => Msg::SetLookupProvider(sel.selected_index().max(0) as usize),
+                                            _ => Msg::None,
+                                        } )>
+                                    {
+                                        for self.settings.lookup_providers.iter().enumerate().map(|(i, provider)| {
+                                            html! { <option selected=i == self.settings.lookup_provider>{ &provider.name }</option> }
+                                        })
+                                    }
+                                </select>
+                            </td>
+                        </tr>
+                        {
+                            for self.settings.lookup_providers.iter().enumerate().map(|(i, provider)| {
+                                html! {
+                                    <tr>
+                                        <td>{ &provider.name }</td>
+                                        <td style="text-align:right">
+                                            <span>{ &provider.url }</span>
+                                            <button class="button is-text" onclick=self.link.callback(move |_| Msg::RemoveLookupProvider(i))>
+                                                <span class="icon is-small">
+                                                    <i class="far fa-trash-alt"></i>
+                                                </span>
+                                            </button>
+                                        </td>
+                                    </tr>
+                                }
+                            })
                         }
-                        { if self.spots.has_lotw_users() {
-                            html! {
-                                <tr>
-                                    <td>{ "LoTW" }</td>
-                                    <td style="text-align:right">
-                                        <label class="switch">
-                                            <input id="switchColorDefault" type="checkbox" name="switchColorDefault" checked=self.spots.lotw_spot_filter_enabled() onclick=self.link.callback(move |_| Msg::ToggleLoTWSpotFilter ) />
-                                            <span class="slider"></span>
-                                        </label>
-                                    </td>
-                                </tr>
-                            } } else {
-                                html! {}
-                            }
+                        <tr>
+                            <td>{ self.t("add_lookup_provider") }</td>
+                            <td style="text-align:right">
+                                <div class="field is-grouped">
+                                    <input class="input" type="text" placeholder=self.t("lookup_name")
+                                        value=&self.new_lookup_provider.0
+                                        oninput=self.link.callback(move |e: InputData| Msg::UpdateNewLookupProvider(e.value, new_url.clone())) />
+                                    <input class="input" type="text" placeholder=self.t("lookup_url_help")
+                                        value=&self.new_lookup_provider.1
+                                        oninput=self.link.callback(move |e: InputData| Msg::UpdateNewLookupProvider(new_name.clone(), e.value)) />
+                                    <button class="button" onclick=self.link.callback(|_| Msg::AddLookupProvider)>{ self.t("add") }</button>
+                                </div>
+                            </td>
+                        </tr>
+                    </tbody>
+                    <thead>
+                        <tr>
+                            <th colspan="2">{ self.t("shortcuts") }</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {
+                            for ACTIONS.iter().map(|action| {
+                                let action = *action;
+                                html! {
+                                    <tr>
+                                        <td>{ self.t(action.name()) }</td>
+                                        <td style="text-align:right">
+                                            <input class="input shortcut-key" type="text" maxlength="1"
+                                                value=keymap::key_for(&self.settings.keymap, action)
+                                                onchange=self.link.callback(move |e: ChangeData|
+                                                    match e {
+                                                        ChangeData::Value(value) => Msg::SetShortcut(action, value),
+                                                        _ => Msg::None,
+                                                    } ) />
+                                        </td>
+                                    </tr>
+                                }
+                            })
                         }
-                        { if let Some(_) = self.import { 
-                              html! {
-                                <>
+                    </tbody>
+                    <thead>
+                        <tr>
+                            <th colspan="2">{ self.t("macros") }</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {
+                            for self.macros.iter().enumerate().map(|(i, m)| html! {
                                 <tr>
-                                    <td>{ "New State" }</td>
-                                    <td style="text-align:right">
-                                        <label class="switch">
-                                            <input id="switchColorDefault" type="checkbox" name="switchColorDefault" checked=self.spots.state_spot_filter_enabled() onclick=self.link.callback(move |_| Msg::ToggleStateSpotFilter ) />
-                                            <span class="slider"></span>
-                                        </label>
+                                    <td>
+                                        <input class="input" type="text" value=&m.name
+                                            onchange=self.link.callback(move |e: ChangeData|
+                                                match e {
+                                                    ChangeData::Value(value) => Msg::RenameMacro(i, value),
+                                                    _ => Msg::None,
+                                                } ) />
                                     </td>
-                                </tr>
-                                <tr>
-                                    <td>{ "New Country" }</td>
                                     <td style="text-align:right">
-                                        <label class="switch">
-                                            <input id="switchColorDefault" type="checkbox" name="switchColorDefault" checked=self.spots.country_spot_filter_enabled() onclick=self.link.callback(move |_| Msg::ToggleCountrySpotFilter ) />
-                                            <span class="slider"></span>
-                                        </label>
+                                        <span class="has-text-grey">{ format!("{} {} ", m.steps.len(), self.t("macro_steps")) }</span>
+                                        <button class="button" onclick=self.link.callback(move |_| Msg::PlayMacro(i))>
+                                            <span class="icon is-small"><i class="fas fa-play"></i></span>
+                                        </button>
+                                        <button class="button" onclick=self.link.callback(move |_| Msg::DeleteMacro(i))>
+                                            <span class="icon is-small"><i class="far fa-trash-alt"></i></span>
+                                        </button>
                                     </td>
                                 </tr>
-                                </>
-                              }
-                          } else {
-                              html! {}
-                        }}
+                            })
+                        }
+                        <tr>
+                            <td colspan="2" class="has-text-grey">{ self.t("macros_help") }</td>
+                        </tr>
                     </tbody>
                     <thead>
                         <tr>
-                            <th colspan="2">{ "Log File" }</th>
+                            <th colspan="2">{ self.t("backup") }</th>
                         </tr>
                     </thead>
                     <tbody>
                         <tr>
-                            <td colspan="2">{ self.import_adif_form() }</td>
+                            <td>{ self.t("export_settings") }</td>
+                            <td style="text-align:right">
+                                <button class="button" onclick=self.link.callback(|_| Msg::ExportSettings)>
+                                    <span class="icon is-small">
+                                        <i class="fas fa-download"></i>
+                                    </span>
+                                </button>
+                            </td>
+                        </tr>
+                        <tr>
+                            <td>{ self.t("import_settings") }</td>
+                            <td style="text-align:right">
+                                {
+                                    match self.settings_import {
+                                        Some(_) => html! {
+                                            <div class="field is-grouped" style="justify-content:flex-end">
+                                                <button class="button is-link" onclick=self.link.callback(|_| Msg::ApplySettingsImport(true))>{ self.t("merge") }</button>
+                                                <button class="button is-danger" onclick=self.link.callback(|_| Msg::ApplySettingsImport(false))>{ self.t("replace") }</button>
+                                                <button class="button" onclick=self.link.callback(|_| Msg::CancelSettingsImport)>{ self.t("cancel") }</button>
+                                            </div>
+                                        },
+                                        None => html! {
+                                            <input class="file-import" type="file" accept=".json,application/json"
+                                                onchange=self.link.callback(|value| {
+                                                    if let ChangeData::Files(files) = value {
+                                                        if let Some(file) = files.get(0) {
+                                                            return Msg::SettingsFile(file);
+                                                        }
+                                                    }
+                                                    Msg::None
+                                                }) />
+                                        },
+                                    }
+                                }
+                            </td>
+                        </tr>
+                    </tbody>
+                    <thead>
+                        <tr>
+                            <th colspan="2">{ self.t("mode_colors") }</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {
+                            for mode_colors.into_iter().map(|(mode, color)| {
+                                let key = mode.clone();
+                                html! {
+                                    <tr>
+                                        <td>{ mode }</td>
+                                        <td style="text-align:right">
+                                            <input type="color" value=color
+                                                onchange=self.link.callback(move |e: ChangeData|
+                                                    match e {
+                                                        ChangeData::Value(color) => Msg::SetModeColor(key.clone(), color),
+                                                        _ => Msg::None,
+                                                    }) />
+                                        </td>
+                                    </tr>
+                                }
+                            })
+                        }
+                    </tbody>
+                    <thead>
+                        <tr>
+                            <th colspan="2">{ self.t("band_modes") }</th>
                         </tr>
+                    </thead>
+                    <tbody>
+                        {
+                            for self.settings.band_modes.iter().map(|band_mode| {
+                                let band = band_mode.band.clone();
+                                html! {
+                                    <tr>
+                                        <td>{ &band_mode.band }</td>
+                                        <td style="text-align:right">
+                                            <select class="select"
+                                                onchange=self.link.callback(move |e: ChangeData|
+                                                    match e {
+                                                        ChangeData::Select(sel) => Msg::SetBandMode(band.clone(), sel.value()),
+                                                        _ => Msg::None,
+                                                    } )>
+                                                <option value="" selected=band_mode.mode.is_empty()>{ self.t("none") }</option>
+                                                {
+                                                    for RECEIVER_MODES.iter().map(|mode| {
+                                                        html! { <option selected=mode.mode().to_string() == band_mode.mode>{ mode.mode() }</option> }
+                                                    })
+                                                }
+                                            </select>
+                                        </td>
+                                    </tr>
+                                }
+                            })
+                        }
                     </tbody>
                 </table>
             </div>
         }
     }
+
     pub fn version_html(&self) -> Html {
         match &self.version {
             Some(version) => {
@@ -714,8 +3864,37 @@ impl Model {
                         "SparkSDR" => "http://www.ihopper.org/radio/",
                         _ => "",
                     };
-                html! { 
-                    <p class="version"><a href=host_url>{ version.host.to_string() }</a>{ format!(" {} [Protocol Version: {}]", version.host_version, version.protocol_version) }</p> 
+                // the protocol only reports host, version and protocol
+                // version, the rest is what we know about the connection
+                let details =
+                    match self.show_server_info {
+                        true => html! {
+                            <table class="table is-narrow server-info">
+                                <tbody>
+                                    <tr><td>{ self.t("server_host") }</td><td><a href=host_url>{ version.host.to_string() }</a></td></tr>
+                                    <tr><td>{ self.t("server_version") }</td><td>{ version.host_version.to_string() }</td></tr>
+                                    <tr><td>{ self.t("server_protocol") }</td><td>{ version.protocol_version.to_string() }</td></tr>
+                                    <tr><td>{ self.t("server_address") }</td><td>{ &self.ws_location }</td></tr>
+                                    <tr>
+                                        <td>{ self.t("server_radios") }</td>
+                                        <td>{ self.radios.iter().map(|r| r.name.to_string()).collect::<Vec<String>>().join(", ") }</td>
+                                    </tr>
+                                    <tr><td>{ self.t("receivers") }</td><td>{ self.receivers.len() }</td></tr>
+                                </tbody>
+                            </table>
+                        },
+                        false => html! {},
+                    };
+                html! {
+                    <div class="version">
+                        <p>
+                            <a href=host_url>{ version.host.to_string() }</a>{ format!(" {} [Protocol Version: {}] ", version.host_version, version.protocol_version) }
+                            <a class="server-info-toggle" onclick=self.link.callback(|_| Msg::ToggleServerInfo)>
+                                { if self.show_server_info { self.t("hide_server_info") } else { self.t("about_server") } }
+                            </a>
+                        </p>
+                        { details }
+                    </div>
                 }
             },
             None => html! {},
@@ -729,7 +3908,7 @@ impl Model {
                         match &self.import {
                             None => html! {
                                 <>
-                    <p>{"Compare spots to log file to find new countries and states."}</p>
+                    <p>{ self.t("import_help") }</p>
                     <input class="file-import" type="file" multiple=true onchange=self.link.callback(move |value| {
                             let mut result = Vec::new();
                             if let ChangeData::Files(files) = value {
@@ -742,14 +3921,14 @@ impl Model {
                             }
                             Msg::Files(result, false)
                         })/>
-                    <p><i>{ "(adif only)" }</i></p>
+                    <p><i>{ self.t("adif_only") }</i></p>
                                 </>
                             },
                             Some(import) => html! {
                                 <>
-                                    <p>{ format!("Loaded {} contacts", import.len()) }</p>
+                                    <p>{ self.t("loaded_contacts").replace("{}", &import.len().to_string()) }</p>
                                     <p>
-                                        <input type="button" class="button" value="Cancel Import" onclick=self.link.callback(|_| Msg::CancelImport) />
+                                        <input type="button" class="button" value=self.t("cancel_import") onclick=self.link.callback(|_| Msg::CancelImport) />
                                     </p>
                                 </>
                             },
@@ -763,96 +3942,420 @@ impl Model {
         let (country_icon, state_class) =
             match spot.call.country() {
                 Ok(country) => {
+                    let worked = self.spots.worked();
                     let (new_country, new_state) =
-                        match &self.import {
-                            Some(import) => {
-                                let new_country =
-                                    if let Some(_index) = import.iter().position(|i| i.call.country() == Ok(country.clone())) {
-                                        ""
-                                    } else {
-                                        "has-text-success"
-                                    };
-                                let new_state =
-                                    match spot.call.state() {
-                                        Some(state) => {
-                                            if let Some(_index) = import.iter().position(|i| i.call.state() == Some(state.to_string())) {
-                                                ""
-                                            } else {
-                                                "has-text-success"
-                                            }
-                                        },
-                                        _ => "",
-                                    };
-                                (new_country, new_state)
-                            },
-                            None => ("", ""),
+                        match worked.is_empty() {
+                            false => (
+                                if worked.new_country(&spot.call) { "has-text-success" } else { "" },
+                                if worked.new_state(&spot.call) { "has-text-success" } else { "" },
+                            ),
+                            true => ("", ""),
                         };
-                    (html! { <><i class=format!("flag-icon flag-icon-{}", country.code())></i> <span class=new_country>{ country.name() }</span></> }, new_state)
+                    (html! { <>{ flags::flag(self.settings.flag_source, &flags::flag_code(&country.name(), &country.code())) } <span class=new_country>{ self.i18n.country(&country.code(), &country.name()) }</span></> }, new_state)
                 },
                 Err(_) => (html! {}, ""),
             };
 
+        let dxcc =
+            match spot.call.country().ok().and_then(|country| dxcc_entity(&country.code())) {
+                Some(entity) => format!("{} ({})", call_prefix(&spot.call.call()), entity),
+                None => call_prefix(&spot.call.call()),
+            };
+
         let (lotw_enabled, uses_lotw) =
             match spot.call.lotw() {
-                LoTWStatus::LastUpload(_) | LoTWStatus::Registered => (true, html! { <span class="has-text-success">{ "Yes" }</span> }),
-                LoTWStatus::Unregistered => (true, html! { { "No" } }),
+                LoTWStatus::LastUpload(_) | LoTWStatus::Registered => (true, html! { <span class="has-text-success">{ self.t("yes") }</span> }),
+                LoTWStatus::Unregistered => (true, html! { { self.t("no") } }),
                 LoTWStatus::Unknown => (false, html! {})
             };
 
-        let spot_receiver_id =
-            if let Some(index) = self.receivers.iter().position(|i| i.frequency == spot.tuned_frequency && i.mode == spot.mode ) {
-                Some(self.receivers[index].id)
-            } else {
-                None
+        let row_class =
+            match (is_calling(spot, &self.settings.my_call), self.settings.spot_click_action) {
+                (true, SpotClickAction::Nothing) => "calling-me",
+                (true, _) => "calling-me clickable",
+                (false, SpotClickAction::Nothing) => "",
+                (false, _) => "clickable",
+            };
+        let (call, frequency, mode) = (spot.call.call(), spot.tuned_frequency, spot.mode.clone());
+        let signal = spot.frequency;
+        let (menu_call, menu_mode) = (call.clone(), mode.clone());
+        let dupe =
+            match self.is_dupe(&call, frequency, &mode) {
+                true => html! { <span class="tag is-danger dupe">{ "DUPE" }</span> },
+                false => html! {},
+            };
+        let dupe =
+            match self.spots.source_count(spot) {
+                1 => dupe,
+                count => html! { <>{ dupe }<span class="tag is-info merged" title=self.t("merged_help")>{ format!("x{}", count) }</span></> },
+            };
+        let dupe =
+            match self.call_notes.get(&call) {
+                Some(note) => html! { <>{ dupe }<span class="tag is-warning is-light call-note">{ note }</span></> },
+                None => dupe,
+            };
+
+        let (key, pinned) = (SpotKey::new(spot), self.spots.is_pinned(spot));
+        let row_key = key.id();
+        let dt_quality = dt_class(spot.dt as f64, &self.settings.dt_thresholds);
+        let dt_help = if dt_quality.is_empty() { String::new() } else { self.t(&format!("{}_help", dt_quality.replace('-', "_"))) };
+        let row_class =
+            match self.selected_spots.contains(&key) {
+                true => format!("{} is-selected", row_class),
+                false => row_class.to_string(),
+            };
+        // age classes are updated on each tick, the css transition
+        // does the fading
+        let row_class =
+            match (self.settings.spot_fade, self.now - spot.time) {
+                (true, age) if age.num_seconds() <= NEW_SPOT_SECONDS => format!("{} spot-new", row_class),
+                (true, age) if age.num_minutes() >= OLD_SPOT_MINUTES => format!("{} spot-old", row_class),
+                (true, _) => format!("{} spot-fading", row_class),
+                (false, _) => row_class,
+            };
+        let select_key = key.clone();
+        let row_style =
+            match self.settings.mode_color(&spot.mode.mode().to_string()) {
+                Some(color) => format!("border-left:4px solid {}", color),
+                None => "".to_string(),
+            };
+
+        html! {
+            <tr key=row_key.clone() data-key=row_key class=row_class style=row_style
+                onclick=self.link.callback(move |e: MouseEvent| {
+                    match e.ctrl_key() || e.meta_key() || e.shift_key() {
+                        true => Msg::ToggleSpotSelection(select_key.clone()),
+                        false => Msg::SpotClicked(call.clone(), frequency, mode.clone()),
+                    }
+                })
+                oncontextmenu=self.link.callback(move |e: MouseEvent| {
+                    e.prevent_default();
+                    Msg::ShowSpotMenu(menu_call.clone(), frequency, signal, menu_mode.clone(), e.client_x(), e.client_y())
+                })>
+                <td>
+                    <a class=if pinned { "pin has-text-link" } else { "pin has-text-grey-lighter" } title=self.t("pin_spot")
+                        onclick=self.link.callback(move |e: MouseEvent| { e.stop_propagation(); Msg::TogglePinSpot(key.clone()) })>
+                        <span class="icon is-small">
+                            <i class="fas fa-thumbtack"></i>
+                        </span>
+                    </a>
+                </td>
+                <td>{ match self.settings.time_display {
+                         TimeDisplay::Utc => spot.time.format("%H%M%S").to_string(),
+                         TimeDisplay::Local => spot.time.with_timezone(&Local).format("%H%M%S").to_string(),
+                      }
+                    }</td>
+                <td>{ format_snr(spot.snr as f64) }</td>
+                <td class=dt_quality title=dt_help>{ format_dt(spot.dt as f64) }</td>
+                <td class="freqc"><span>{ format!("{} (", spot.tuned_frequency) }</span>{ format!("{:+.0} Hz", spot.frequency - spot.tuned_frequency) }<span>{ ")" }</span></td>
+                <th class="modec">{ spot.mode.mode() }</th>
+                <td class="heard-by">{ self.spots.heard_by(spot).map(|id| self.receiver_label(id)).unwrap_or_default() }</td>
+                { match spot.distance {
+                      Some(dist) => html! { <td class=distance_class(f64::from(dist), &self.settings.distance_thresholds)>{ format!("{}", dist) }</td> },
+                      None => html! { <td></td> },
+                  }
+                }
+                {
+                    if let Some(msg) = &spot.msg {
+                        let highlighted = msg.split_whitespace().any(|word| self.settings.is_highlight_token(word));
+                        match highlighted {
+                            true => html! { <th>{ dupe }{ self.message_html(msg) }</th> },
+                            false => html! { <td>{ dupe }{ msg.to_string() }</td> }
+                        }
+                    } else {
+                        html! { <td>{ dupe }{ "--" }</td> }
+                    }
+                }
+                {
+                    match self.settings.show_dxcc {
+                        true => html! { <td class="dxcc">{ dxcc }</td> },
+                        false => html! {}
+                    }
+                }
+                <td>{ country_icon }</td>
+                <td class=state_class>{ match spot.call.state() {
+                          Some(state) => format!("{}", state),
+                          None => format!("")
+                      } }</td>
+                <td>{ match spot.call.op() {
+                          Some(op) => format!("{}", op),
+                          None => format!("")
+                      } }</td>
+                {
+                    match lotw_enabled {
+                        true => html! { <td>{ uses_lotw }</td> },
+                        false => html! {}
+                    }
+                }
+            </tr>
+        }
+    }
+
+    // Context menu for the spot that was right clicked
+    pub fn spot_menu_view(&self) -> Html {
+        match &self.spot_menu {
+            Some(menu) => {
+                let style = format!("left:{}px;top:{}px", menu.x, menu.y);
+                let (log_call, log_frequency, log_mode) = (menu.call.clone(), menu.frequency, menu.mode.clone());
+                let (signal, note_call, block_call) = (menu.signal, menu.call.clone(), menu.call.clone());
+                let highlight_call = menu.call.clone();
+                let highlight_label = if self.settings.is_highlight_token(&menu.call) { "unhighlight_call" } else { "highlight_call" };
+                html! {
+                    <>
+                        <div class="spot-menu-backdrop"
+                            onclick=self.link.callback(|_| Msg::HideSpotMenu)
+                            oncontextmenu=self.link.callback(|e: MouseEvent| { e.prevent_default(); Msg::HideSpotMenu }) />
+                        <div class="spot-menu box" style=style>
+                            <p class="spot-menu-title">{ &menu.call }</p>
+                            {
+                                for [SpotClickAction::Tune, SpotClickAction::Copy, SpotClickAction::Lookup].iter().map(|action| {
+                                    let (action, call, frequency, mode) = (*action, menu.call.clone(), menu.frequency, menu.mode.clone());
+                                    html! {
+                                        <a class="spot-menu-item" onclick=self.link.callback(move |_| Msg::SpotAction(action, call.clone(), frequency, mode.clone()))>
+                                            { self.t(&format!("action_{}", action.name())) }
+                                        </a>
+                                    }
+                                })
+                            }
+                            <a class="spot-menu-item" onclick=self.link.callback(move |_| Msg::OpenQsoForm(log_call.clone(), log_frequency, log_mode.clone()))>
+                                { self.t("log_qso") }
+                            </a>
+                            <a class="spot-menu-item" onclick=self.link.callback(move |_| Msg::EditCallNote(note_call.clone()))>
+                                { self.t("edit_note") }
+                            </a>
+                            <a class="spot-menu-item" onclick=self.link.callback(move |_| Msg::ToggleHighlightCall(highlight_call.clone()))>
+                                { self.t(highlight_label) }
+                            </a>
+                            <a class="spot-menu-item" onclick=self.link.callback(move |_| Msg::BlockCall(block_call.clone()))>
+                                { self.t("hide_call") }
+                            </a>
+                            <a class="spot-menu-item" onclick=self.link.callback(move |_| Msg::SplitToSpot(signal))>
+                                { self.t("split_to_spot") }
+                            </a>
+                        </div>
+                    </>
+                }
+            },
+            None => html! {},
+        }
+    }
+
+    // Quick log form shown as a modal
+    pub fn qso_form_view(&self) -> Html {
+        match &self.qso_form {
+            Some(form) => {
+                html! {
+                    <div class="modal is-active">
+                        <div class="modal-background" onclick=self.link.callback(|_| Msg::CancelQso)></div>
+                        <div class="modal-content">
+                            <div class="box qso-form">
+                                <p class="title is-5">{ self.t("log_qso") }</p>
+                                <div class="field">
+                                    <label class="label">{ self.t("call") }</label>
+                                    <input class="input" type="text" value=&form.call
+                                        oninput=self.link.callback(|e: InputData| Msg::UpdateQsoForm(QsoField::Call, e.value)) />
+                                </div>
+                                {
+                                    if self.is_dupe(&form.call, form.frequency, &form.mode) {
+                                        html! { <div class="notification is-danger dupe-warning">{ format!("DUPE: {}", self.t("dupe_help")) }</div> }
+                                    } else {
+                                        html! {}
+                                    }
+                                }
+                                <p>{ format!("{} {} {} UTC", form.frequency, form.mode.mode(), form.time.format("%Y-%m-%d %H:%M:%S")) }</p>
+                                <div class="field is-grouped">
+                                    <div class="control">
+                                        <label class="label">{ self.t("rst_sent") }</label>
+                                        <input class="input" type="text" value=&form.rst_sent
+                                            oninput=self.link.callback(|e: InputData| Msg::UpdateQsoForm(QsoField::RstSent, e.value)) />
+                                    </div>
+                                    <div class="control">
+                                        <label class="label">{ self.t("rst_rcvd") }</label>
+                                        <input class="input" type="text" value=&form.rst_rcvd
+                                            oninput=self.link.callback(|e: InputData| Msg::UpdateQsoForm(QsoField::RstRcvd, e.value)) />
+                                    </div>
+                                </div>
+                                <div class="field is-grouped">
+                                    <button class="button is-link" onclick=self.link.callback(|_| Msg::LogQso)>{ self.t("log_qso") }</button>
+                                    <button class="button" onclick=self.link.callback(|_| Msg::CancelQso)>{ self.t("cancel") }</button>
+                                </div>
+                            </div>
+                        </div>
+                    </div>
+                }
+            },
+            None => html! {},
+        }
+    }
+
+    // Edit the note for a callsign, saving an empty note removes it
+    pub fn note_form_view(&self) -> Html {
+        match &self.note_form {
+            Some((call, note)) => html! {
+                <div class="modal is-active">
+                    <div class="modal-background" onclick=self.link.callback(|_| Msg::CancelCallNote)></div>
+                    <div class="modal-content">
+                        <div class="box">
+                            <p class="title is-5">{ format!("{} {}", self.t("edit_note"), call) }</p>
+                            <div class="field">
+                                <input class="input" type="text" value=note placeholder=self.t("note_help")
+                                    oninput=self.link.callback(|e: InputData| Msg::UpdateCallNote(e.value))
+                                    onkeypress=self.link.callback(|e: KeyboardEvent| {
+                                        if e.key() == "Enter" { Msg::SaveCallNote } else { Msg::None }
+                                    }) />
+                            </div>
+                            <div class="field is-grouped">
+                                <button class="button is-link" onclick=self.link.callback(|_| Msg::SaveCallNote)>{ self.t("save") }</button>
+                                <button class="button" onclick=self.link.callback(|_| Msg::CancelCallNote)>{ self.t("cancel") }</button>
+                            </div>
+                        </div>
+                    </div>
+                </div>
+            },
+            None => html! {},
+        }
+    }
+
+    // Edit a receiver's nickname, saving an empty name removes it
+    pub fn name_form_view(&self) -> Html {
+        match &self.name_form {
+            Some((receiver_id, name)) => html! {
+                <div class="modal is-active">
+                    <div class="modal-background" onclick=self.link.callback(|_| Msg::CancelReceiverName)></div>
+                    <div class="modal-content">
+                        <div class="box">
+                            <p class="title is-5">{ format!("{} {}", self.t("rename_receiver"), receiver_id) }</p>
+                            <div class="field">
+                                <input class="input" type="text" value=name placeholder=self.t("receiver_name_help")
+                                    oninput=self.link.callback(|e: InputData| Msg::UpdateReceiverName(e.value))
+                                    onkeypress=self.link.callback(|e: KeyboardEvent| {
+                                        if e.key() == "Enter" { Msg::SaveReceiverName } else { Msg::None }
+                                    }) />
+                            </div>
+                            <div class="field is-grouped">
+                                <button class="button is-link" onclick=self.link.callback(|_| Msg::SaveReceiverName)>{ self.t("save") }</button>
+                                <button class="button" onclick=self.link.callback(|_| Msg::CancelReceiverName)>{ self.t("cancel") }</button>
+                            </div>
+                        </div>
+                    </div>
+                </div>
+            },
+            None => html! {},
+        }
+    }
+
+    pub fn keypad_view(&self) -> Html {
+        let keypad =
+            match &self.keypad {
+                Some(keypad) => keypad,
+                None => return html! {},
             };
-
+        let locked = self.is_locked(keypad.receiver_id);
+        let button = |label: &str, key: fn() -> KeypadKey| html! {
+            <button class="button is-medium" onclick=self.link.callback(move |_| Msg::KeypadPress(key()))>{ label }</button>
+        };
         html! {
-            <tr>
-                <td>{ spot.time.format("%H%M%S") }</td>
-                <td>{ spot.snr }</td>
-                <td>{ spot.dt }</td>
-                <td class="freqc"><span>{ format!("{} (+", spot.tuned_frequency) }</span>{ format!("{}", (spot.frequency - spot.tuned_frequency)) }<span>{ ")" }</span></td>
-                <th class="modec">{ spot.mode.mode() }</th>
-                <td>{ match spot.distance {
-                         Some(dist) => format!("{}", dist),
-                         None => format!(""),
-                      }
-                    }</td>
-                {
-                    if let Some(msg) = &spot.msg {
-                        match (msg.contains("CQ"), spot_receiver_id) {
-                            (true, Some(receiver_id)) => html! { <th><a onclick=self.link.callback(move |_| Msg::SetDefaultReceiver(receiver_id) )>{ msg.to_string() }</a></th> },
-                            (true, None) => html! { <th>{ msg.to_string() }</th> },
-                            (false, _) => html! { <td>{ msg.to_string() }</td> }
+            <div class="modal is-active">
+                <div class="modal-background" onclick=self.link.callback(|_| Msg::CloseKeypad)></div>
+                <div class="modal-content keypad">
+                    <div class="box">
+                        <p class="keypad-entry">
+                            { if keypad.entry.is_empty() { "0" } else { keypad.entry.as_str() } }
+                            <span class="keypad-unit">{ if keypad.mhz { " MHz" } else { " kHz" } }</span>
+                        </p>
+                        <div class="keypad-keys">
+                            {
+                                for "123456789".chars().map(|digit| html! {
+                                    <button class="button is-medium" onclick=self.link.callback(move |_| Msg::KeypadPress(KeypadKey::Digit(digit)))>{ digit }</button>
+                                })
+                            }
+                            { button(".", || KeypadKey::Point) }
+                            <button class="button is-medium" onclick=self.link.callback(|_| Msg::KeypadPress(KeypadKey::Digit('0')))>{ "0" }</button>
+                            { button("⌫", || KeypadKey::Backspace) }
+                            { button(&self.t("keypad_clear"), || KeypadKey::Clear) }
+                            { button(if keypad.mhz { "kHz" } else { "MHz" }, || KeypadKey::ToggleUnit) }
+                            <button class="button is-medium is-link" disabled=locked onclick=self.link.callback(|_| Msg::ConfirmKeypad)>{ self.t("action_tune") }</button>
+                        </div>
+                        {
+                            match locked {
+                                true => html! { <p class="help is-danger">{ self.t("frequency_locked") }</p> },
+                                false => html! {},
+                            }
                         }
+                        <button class="button is-fullwidth" onclick=self.link.callback(|_| Msg::CloseKeypad)>{ self.t("cancel") }</button>
+                    </div>
+                </div>
+            </div>
+        }
+    }
+
+    // Spot message with any configured highlight words wrapped
+    fn message_html(&self, msg: &str) -> Html {
+        html! {
+            {
+                for msg.split_whitespace().enumerate().map(|(i, word)| {
+                    let space = if i > 0 { " " } else { "" };
+                    if self.settings.is_highlight_token(word) {
+                        html! { <>{ space }<span class="highlight">{ word }</span></> }
                     } else {
-                        html! { <td>{ "--" }</td> }
-                    }
-                }
-                <td>{ country_icon }</td>
-                <td class=state_class>{ match spot.call.state() {
-                          Some(state) => format!("{}", state),
-                          None => format!("")
-                      } }</td>
-                <td>{ match spot.call.op() {
-                          Some(op) => format!("{}", op),
-                          None => format!("")
-                      } }</td>
-                {
-                    match lotw_enabled {
-                        true => html! { <td>{ uses_lotw }</td> },
-                        false => html! {}
+                        html! { <>{ space }{ word }</> }
                     }
-                }
-            </tr>
+                })
+            }
+        }
+    }
+
+    // Filter edges as last reported by SparkSDR. The protocol has no
+    // commands for filter width, AGC or noise blanker yet, so this
+    // is read only.
+    fn filter_view(&self, receiver: &Receiver) -> Html {
+        let width = receiver.filter_high - receiver.filter_low;
+        if width <= 0.0 {
+            return html! {};
+        }
+        html! {
+            <>
+                { self.passband_view(receiver.filter_low, receiver.filter_high) }
+                <span class="tag filter-width" title=format!("{:.0} / {:+.0} Hz", receiver.filter_low, receiver.filter_high)>
+                    { format!("{} {:.2} kHz", self.t("filter_width"), width / 1000.0) }
+                </span>
+            </>
+        }
+    }
+
+    // Passband relative to the carrier (center line), the scale
+    // grows to fit wide filters
+    fn passband_view(&self, filter_low: f32, filter_high: f32) -> Html {
+        let span = filter_low.abs().max(filter_high.abs()).max(PASSBAND_MIN_SPAN_HZ) * 1.1;
+        let x = |hz: f32| (hz / span + 1.0) * 60.0;
+        html! {
+            <svg class="passband" width="120" height="24" viewBox="0 0 120 24">
+                <line class="passband-axis" x1="0" y1="22" x2="120" y2="22" />
+                <rect class="passband-filter" x=x(filter_low).to_string() y="4"
+                    width=(x(filter_high) - x(filter_low)).to_string() height="18" />
+                <line class="passband-carrier" x1="60" y1="0" x2="60" y2="24" />
+            </svg>
         }
     }
 
     pub fn receiver(&self, receiver: &Receiver) -> Html {
-        let frequency_string = format!("{:0>9}", receiver.frequency.to_string());
-        let tmp = self.decimal_mark(frequency_string);
-        let mut inactive = true;
+        let separator = self.settings.number_format.separator();
         let receiver_id = receiver.id;
+        let (frequency, mode) = (receiver.frequency, receiver.mode.clone());
+        let operating = self.operating_frequency(receiver_id, frequency);
+        let current_band = Band::new(frequency as i32).band().map(|b| b.to_string());
+        let frequency_class =
+            match (self.is_locked(receiver_id), self.outside_privileges(receiver_id, frequency)) {
+                (true, true) => "frequency locked out-of-privilege",
+                (false, true) => "frequency out-of-privilege",
+                (true, _) => "frequency locked",
+                (false, _) => "frequency",
+            };
+        let frequency_class =
+            match self.is_change_pending(receiver_id) {
+                true => format!("{} pending", frequency_class),
+                false => frequency_class.to_string(),
+            };
         let (class_name, is_default) = 
             if Some(receiver.id) == self.default_receiver {
                 if !self.show_receiver_list {
@@ -869,25 +4372,40 @@ impl Model {
                 None => "icon is-small has-text-danger",
             };
 
+        let record_class =
+            match self.recorder.recording() {
+                Some(_) => "icon is-small has-text-danger",
+                None => "icon is-small",
+            };
+
+        // digits whose step would leave the range SparkSDR accepts are
+        // greyed out
+        let (min_frequency, max_frequency) = (commands::MIN_FREQUENCY, commands::MAX_FREQUENCY);
+        let step = |digit: i32| 10f32.powi(8 - digit);
+
         if self.show_receiver_list || is_default {
         html! {
             <div class=class_name onclick=self.link.callback(move |_| Msg::SetDefaultReceiver(receiver_id))>
+                <div class="receiver-name">
+                    <span>{ self.receiver_label(receiver_id) }</span>
+                    <a title=self.t("rename_receiver") onclick=self.link.callback(move |e: MouseEvent| { e.stop_propagation(); Msg::EditReceiverName(receiver_id) })>
+                        <span class="icon is-small"><i class="fas fa-pen"></i></span>
+                    </a>
+                </div>
                 <div class="up-controls">
                     {
                         for (0..9).map(|digit| {
-                            html! { <><a onclick=self.link.callback(move |_| Msg::FrequencyUp(receiver_id, digit))>{ "0" }</a>{ if digit == 2 || digit == 5 { "," } else { "" } }</> }
+                            let class = if frequency + step(digit) > max_frequency { "out-of-range" } else { "" };
+                            html! { <><a class=class onclick=self.link.callback(move |_| Msg::FrequencyUp(receiver_id, digit))>{ "0" }</a>{ self.digit_separator(digit) }</> }
                         })
                     }
                 </div>
-                <div id="frequency" class="frequency">
+                <div id="frequency" class=frequency_class onclick=self.link.callback(move |_| Msg::OpenKeypad(receiver_id))>
                     {
-                        for tmp.chars().map(|c| {
-                            if ((c != '0' && c != ',' && inactive == true)) {
-                                inactive = false;
-                            }
+                        for frequency_digits(frequency, separator).into_iter().map(|(c, active)| {
                             match c {
-                                ',' => html! { { "," } },
-                                _ if inactive => html! { <span>{ c.to_string() }</span> },
+                                _ if c == separator => html! { { c.to_string() } },
+                                _ if !active => html! { <span>{ c.to_string() }</span> },
                                 _ => html! { <span class="active">{ c.to_string() }</span> }
                             }
                         })
@@ -896,10 +4414,41 @@ impl Model {
                 <div class="down-controls">
                     {
                         for (0..9).map(|digit| {
-                            html! { <><a onclick=self.link.callback(move |_| Msg::FrequencyDown(receiver_id, digit))>{ "0" }</a>{ if digit == 2 || digit == 5 { "," } else { "" } }</> }
+                            let class = if frequency - step(digit) < min_frequency { "out-of-range" } else { "" };
+                            html! { <><a class=class onclick=self.link.callback(move |_| Msg::FrequencyDown(receiver_id, digit))>{ "0" }</a>{ self.digit_separator(digit) }</> }
                         })
                     }
                 </div>
+                { self.filter_view(receiver) }
+                {
+                    match self.split {
+                        Some((split_id, tx_frequency)) if split_id == receiver_id => html! {
+                            <span class="tag is-info split">
+                                { format!("{} {:.3} kHz ({:+.0} Hz)", self.t("split_tx"), tx_frequency / 1000.0, tx_frequency - frequency) }
+                                <button class="delete is-small" onclick=self.link.callback(|e: MouseEvent| { e.stop_propagation(); Msg::ClearSplit })></button>
+                            </span>
+                        },
+                        _ => html! {},
+                    }
+                }
+                { self.transverter_view(receiver) }
+                {
+                    match segment(self.settings.band_plan_region, operating) {
+                        Some(segment) => html! { <span class="tag band-segment">{ segment.kind }</span> },
+                        None if !in_plan(operating) => html! {},
+                        None => html! { <span class="tag is-warning band-segment">{ self.t("out_of_band") }</span> },
+                    }
+                }
+                {
+                    match self.outside_privileges(receiver_id, frequency) {
+                        true => html! {
+                            <span class="tag is-danger is-light privilege-warning">
+                                { self.t("privilege_warning").replace("{}", &format!("{:.3} kHz", operating / 1000.0)) }
+                            </span>
+                        },
+                        false => html! {},
+                    }
+                }
                 <div class="mode control" style="margin-top:-0.5em;z-index:50">
                     {
                         if self.show_receiver_list {
@@ -914,18 +4463,139 @@ impl Model {
                             html! {}
                         }
                     }
+                    {
+                        match (self.marked_receiver == Some(receiver_id), self.show_receiver_list) {
+                            (true, _) => html! {
+                                <button style="float:right" class="button is-text marked-receiver" title=self.t("marked_receiver")
+                                    onclick=self.link.callback(move |e: MouseEvent| { e.stop_propagation(); Msg::ToggleMarkedReceiver(receiver_id) })>
+                                    <span class="tag is-warning">
+                                        <i class="fas fa-bookmark"></i>
+                                    </span>
+                                </button>
+                            },
+                            (false, true) => html! {
+                                <button style="float:right" class="button is-text marked-receiver" title=self.t("mark_receiver")
+                                    onclick=self.link.callback(move |e: MouseEvent| { e.stop_propagation(); Msg::ToggleMarkedReceiver(receiver_id) })>
+                                    <span class="tag is-light">
+                                        <i class="far fa-bookmark"></i>
+                                    </span>
+                                </button>
+                            },
+                            (false, false) => html! {},
+                        }
+                    }
+                    {
+                        if self.show_receiver_list {
+                            let (audio_class, audio_receiver) =
+                                match self.is_audio_receiver(receiver_id) {
+                                    true => ("icon is-small has-text-link", None),
+                                    false => ("icon is-small", Some(receiver_id)),
+                                };
+                            html! {
+                                <button style="float:right" class="button is-text" title=self.t("audio_source")
+                                    onclick=self.link.callback(move |e: MouseEvent| { e.stop_propagation(); Msg::SetAudioReceiver(audio_receiver) })>
+                                    <span class=audio_class>
+                                        <i class="fas fa-headphones"></i>
+                                    </span>
+                                </button>
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
                     { if is_default {
                             html! {
-                                <button style="float:right" class="button is-text" onclick=self.link.callback(move |_| Msg::EnableAudio )>
-                                    <span class=mute_unmute_main_class>
-                                        <i class="fas fa-volume-up"></i>
+                                <>
+                                {
+                                    match self.no_audio {
+                                        true => html! {},
+                                        false => html! {
+                                            <>
+                                            { self.audio_buffer_view() }
+                                            <button style="float:right" class="button is-text" onclick=self.link.callback(move |_| Msg::EnableAudio )>
+                                                <span class=mute_unmute_main_class>
+                                                    <i class="fas fa-volume-up"></i>
+                                                </span>
+                                            </button>
+                                            </>
+                                        },
+                                    }
+                                }
+                                <button style="float:right" class="button is-text" title=self.t("log_qso") onclick=self.link.callback(move |_| Msg::OpenQsoForm(String::new(), frequency, mode.clone()) )>
+                                    <span class="icon is-small">
+                                        <i class="fas fa-book"></i>
+                                    </span>
+                                </button>
+                                <button style="float:right" class="button is-text" title="Record" onclick=self.link.callback(move |_| Msg::ToggleRecording )>
+                                    <span class=record_class>
+                                        <i class="fas fa-circle"></i>
                                     </span>
                                 </button>
+                                {
+                                    match self.recorder.download() {
+                                        Some((url, name)) => html! {
+                                            <a style="float:right" class="button is-text" href=url download=name title="Download Recording">
+                                                <span class="icon is-small">
+                                                    <i class="fas fa-download"></i>
+                                                </span>
+                                            </a>
+                                        },
+                                        None => html! {},
+                                    }
+                                }
+                                </>
                             }
                         } else {
                             html! { }
                         }
                     }
+                    <input class="pan" type="range" min="-1" max="1" step="0.1" title="Pan"
+                        value=self.audio.pan(receiver_id).to_string()
+                        onclick=self.link.callback(|e: MouseEvent| { e.stop_propagation(); Msg::None })
+                        oninput=self.link.callback(move |e: InputData| Msg::SetPan(receiver_id, e.value.parse().unwrap_or(0.0))) />
+                    {
+                        match dial_frequency(receiver.frequency, &receiver.mode) {
+                            Some(dial) if dial != receiver.frequency => html! {
+                                <button class="button is-text" title=self.t("snap_to_dial")
+                                    onclick=self.link.callback(move |e: MouseEvent| { e.stop_propagation(); Msg::SnapToDial(receiver_id) })>
+                                    <span class="icon is-small">
+                                        <i class="fas fa-magnet"></i>
+                                    </span>
+                                </button>
+                            },
+                            _ => html! {},
+                        }
+                    }
+                    <button class="button is-text" title=self.t("lock_frequency")
+                        onclick=self.link.callback(move |e: MouseEvent| { e.stop_propagation(); Msg::ToggleFreqLock(receiver_id) })>
+                        <span class=if self.is_locked(receiver_id) { "icon is-small has-text-danger" } else { "icon is-small has-text-grey-light" }>
+                            <i class=if self.is_locked(receiver_id) { "fas fa-lock" } else { "fas fa-lock-open" }></i>
+                        </span>
+                    </button>
+                    <button class="button is-text" title=self.t("pin_mode")
+                        onclick=self.link.callback(move |e: MouseEvent| { e.stop_propagation(); Msg::TogglePinnedMode(receiver_id) })>
+                        <span class=if self.is_mode_pinned(receiver_id) { "icon is-small has-text-link" } else { "icon is-small has-text-grey-light" }>
+                            <i class="fas fa-thumbtack"></i>
+                        </span>
+                    </button>
+                    <select class="select band-select" title=self.t("select_band")
+                        onchange=self.link.callback(move |e: ChangeData|
+                            match e {
+                                ChangeData::Select(sel) => Msg::SelectBand(receiver_id, sel.value()),
+                                _ => Msg::None,
+                            } )>
+                        {
+                            for self.settings.band_modes.iter().map(|band_mode| {
+                                html! { <option selected=Some(band_mode.band.clone()) == current_band>{ &band_mode.band }</option> }
+                            })
+                        }
+                        {
+                            match &current_band {
+                                Some(_) => html! {},
+                                None => html! { <option value="" selected=true>{ "-" }</option> },
+                            }
+                        }
+                    </select>
                     <select id="mode" class="select" 
                         onchange=self.link.callback(move |e:ChangeData| 
                             match e {
@@ -948,11 +4618,14 @@ impl Model {
         }
     }
 
-    fn decimal_mark(&self, s: String) -> String {
-        let bytes: Vec<_> = s.bytes().rev().collect();
-        let chunks: Vec<_> = bytes.chunks(3).map(|chunk| str::from_utf8(chunk).unwrap()).collect();
-        let result: Vec<_> = chunks.join(",").bytes().rev().collect();
-        String::from_utf8(result).unwrap()
+    // Separator to place after `digit` (0 - 8) in the up/down tuning
+    // rows so they line up with the grouping used by `frequency_digits`
+    fn digit_separator(&self, digit: i32) -> String {
+        if digit < 8 && (8 - digit) % 3 == 0 {
+            self.settings.number_format.separator().to_string()
+        } else {
+            "".to_string()
+        }
     }
 
     fn radio_navbar_controls(&self, radio: &Radio) -> Html {
@@ -971,14 +4644,14 @@ impl Model {
                 <div class="navbar-item">
                     <div class="field has-addons">
                         <p class="control">
-                            <button class="button" title="Power" onclick=self.link.callback(move |_| Msg::TogglePower(radio_id))>
+                            <button class="button" title=self.t("power") onclick=self.link.callback(move |_| Msg::TogglePower(radio_id))>
                                 <span class=power_class>
                                 <i class="fas fa-power-off fa-lg"></i>
                                 </span>
                             </button>
                         </p>
                         <p class="control">
-                            <button class="button" onclick=self.link.callback(move |_| Msg::AddReceiver(radio_id) ) title="Add Receiver">
+                            <button class="button" onclick=self.link.callback(move |_| Msg::AddReceiver(radio_id) ) title=self.t("add_receiver")>
                                 <span class="icon is-small">
                                 <i class="fas fa-plus fa-lg"></i>
                                 </span>
@@ -996,22 +4669,35 @@ impl Model {
         } else {
             "fa-chevron-down"
         };
-        let (spot_class, map_class) =
+        let (spot_class, map_class, settings_class) =
             match AppRoute::switch(self.route.clone()) {
-                Some(AppRoute::Index) => ("navbar-item is-active", "navbar-item"),
-                Some(AppRoute::Map) => ("navbar-item", "navbar-item is-active"),
-                None => ("navbar-item is-active","navbar-item"),
+                Some(AppRoute::Index) => ("navbar-item is-active", "navbar-item", "navbar-item"),
+                Some(AppRoute::Map) => ("navbar-item", "navbar-item is-active", "navbar-item"),
+                Some(AppRoute::Settings) => ("navbar-item", "navbar-item", "navbar-item is-active"),
+                None => ("navbar-item is-active","navbar-item", "navbar-item"),
             };
             
         html! {
             <nav class="navbar is-light" role="navigation" aria-label="main navigation">
                 <div class="navbar-brand">
-                    { for self.radios.iter().map(|r| {
-                        self.radio_navbar_controls(&r)
-                      })
+                    {
+                        match self.settings.show_radio_list {
+                            true => html! {
+                                <>
+                                    { for self.radios.iter().map(|r| {
+                                        self.radio_navbar_controls(&r)
+                                      })
+                                    }
+                                    <a class="navbar-item" title=self.t("hide_radios") onclick=self.link.callback(|_| Msg::ToggleRadioList)>
+                                        <span class="icon is-small"><i class="fas fa-chevron-left"></i></span>
+                                    </a>
+                                </>
+                            },
+                            false => self.radio_summary(),
+                        }
                     }
                     <a class="navbar-item" onclick=self.link.callback(move |_| Msg::ToggleReceiverList)>
-                        <span>{ format!("{} Receivers ", self.receivers.len()) }</span>
+                        <span>{ format!("{} {} ", self.receivers.len(), self.t("receivers")) }</span>
                         <span class="icon is-small">
                             <i class=("fas", cls)></i>
                         </span>
@@ -1028,13 +4714,50 @@ impl Model {
 
 
                         <a class=spot_class onclick=self.link.callback(|_| Msg::ChangeRoute(AppRoute::Index))>
-                            { "Spots" }
+                            { self.t("spots") }
                         </a>
 
                         <a class=map_class onclick=self.link.callback(|_| Msg::ChangeRoute(AppRoute::Map))>
-                            { "Map" }
+                            { self.t("map") }
+                        </a>
+
+                        <a class=settings_class onclick=self.link.callback(|_| Msg::ChangeRoute(AppRoute::Settings))>
+                            { self.t("settings") }
                         </a>
 
+                        { self.band_counts_view() }
+
+                    </div>
+                    <div class="navbar-end">
+                        <a class="navbar-item" title=self.t("refresh") onclick=self.link.callback(|_| Msg::Refresh)>
+                            {
+                                match self.command_log.is_syncing() {
+                                    true => html! {
+                                        <>
+                                            <span class="icon is-small"><i class="fas fa-sync-alt fa-spin"></i></span>
+                                            <span class="refreshing">{ self.t("refreshing") }</span>
+                                        </>
+                                    },
+                                    false => html! { <span class="icon is-small"><i class="fas fa-sync-alt"></i></span> },
+                                }
+                            }
+                        </a>
+                        { self.macros_menu() }
+                        <a class="navbar-item" title=self.t("disconnect") onclick=self.link.callback(|_| Msg::Disconnect)>
+                            <span class="icon is-small">
+                                <i class="fas fa-plug"></i>
+                            </span>
+                        </a>
+                        <a class=if self.show_command_log { "navbar-item is-active" } else { "navbar-item" } title=self.t("command_log")
+                            onclick=self.link.callback(|_| Msg::ToggleCommandLog)>
+                            <span class="icon is-small">
+                                <i class="fas fa-bug"></i>
+                            </span>
+                        </a>
+                        { self.sun_view() }
+                        <span class="navbar-item clock">
+                            { format!("{} UTC", self.now.format("%H:%M:%S")) }
+                        </span>
                     </div>
                 </div>
             </nav>
@@ -1052,12 +4775,342 @@ impl Model {
         }
     }
 
+    pub fn toasts_view(&self) -> Html {
+        html! {
+            <div class="toasts">
+                {
+                    for self.toasts.iter().enumerate().map(|(i, (_, message, _))| {
+                        html! {
+                            <div class="notification is-danger">
+                                <button class="delete" onclick=self.link.callback(move |_| Msg::DismissToast(i))></button>
+                                { message }
+                            </div>
+                        }
+                    })
+                }
+            </div>
+        }
+    }
+
+    // Debug panel pairing sent commands with their responses
+    pub fn command_log_view(&self) -> Html {
+        if !self.show_command_log {
+            return html! {};
+        }
+        html! {
+            <div class="command-log box">
+                <p>
+                    <strong>{ self.t("command_log") }</strong>
+                    <button class="button is-small is-text" style="float:right" onclick=self.link.callback(|_| Msg::ClearCommandLog)>
+                        <span class="icon is-small">
+                            <i class="far fa-trash-alt"></i>
+                        </span>
+                    </button>
+                </p>
+                { self.replay_view() }
+                <table class="table is-narrow is-fullwidth">
+                    {
+                        for self.command_log.entries().iter().rev().map(|entry| {
+                            let (response, elapsed) =
+                                match (&entry.response, entry.elapsed) {
+                                    (Some(response), Some(elapsed)) => (response.to_string(), format!("{:.0} ms", elapsed)),
+                                    _ if entry.expects_response() => (self.t("waiting"), "".to_string()),
+                                    _ => ("--".to_string(), "".to_string()),
+                                };
+                            html! {
+                                <tr>
+                                    <td><code>{ &entry.command }</code></td>
+                                    <td>{ response }</td>
+                                    <td>{ elapsed }</td>
+                                </tr>
+                            }
+                        })
+                    }
+                </table>
+            </div>
+        }
+    }
+
+    // Export received frames and play back a recording of them
+    pub fn replay_view(&self) -> Html {
+        let controls =
+            match (self.replay.is_loaded(), self.replay.is_playing()) {
+                (false, _) => html! {
+                    <input class="file-import" type="file" accept=".json,application/json" title=self.t("load_recording")
+                        onchange=self.link.callback(|value| {
+                            if let ChangeData::Files(files) = value {
+                                if let Some(file) = files.get(0) {
+                                    return Msg::ReplayFile(file);
+                                }
+                            }
+                            Msg::None
+                        }) />
+                },
+                (true, playing) => {
+                    let (played, total) = self.replay.progress();
+                    html! {
+                        <>
+                            <span>{ format!("{} / {} ", played, total) }</span>
+                            {
+                                match playing {
+                                    true => html! { <button class="button is-small" onclick=self.link.callback(|_| Msg::PauseReplay)>{ self.t("pause") }</button> },
+                                    false => html! {
+                                        <>
+                                            <button class="button is-small" onclick=self.link.callback(|_| Msg::PlayReplay(1.0))>{ "1x" }</button>
+                                            <button class="button is-small" onclick=self.link.callback(|_| Msg::PlayReplay(4.0))>{ "4x" }</button>
+                                            <button class="button is-small" onclick=self.link.callback(|_| Msg::PlayReplay(0.0))>{ self.t("replay_instant") }</button>
+                                        </>
+                                    },
+                                }
+                            }
+                            <button class="button is-small is-text" onclick=self.link.callback(|_| Msg::UnloadReplay)>{ self.t("cancel") }</button>
+                        </>
+                    }
+                },
+            };
+        html! {
+            <div class="replay field is-grouped">
+                <button class="button is-small" title=self.t("export_frames") onclick=self.link.callback(|_| Msg::ExportFrames)>
+                    <span class="icon is-small">
+                        <i class="fas fa-download"></i>
+                    </span>
+                    <span>{ format!("{} ({})", self.t("export_frames"), self.frame_log.len()) }</span>
+                </button>
+                <span>{ self.t("replay") }</span>
+                { controls }
+            </div>
+        }
+    }
+
+    // SparkSDR is running but has no radio configured, nothing can be
+    // tuned or added until one is
+    pub fn no_radios_view(&self) -> Html {
+        if !(self.is_connected() && self.radios_received && self.radios.is_empty()) {
+            return html! {};
+        }
+        html! {
+            <div class="notification is-warning no-radios">
+                <span>{ self.t("no_radios") }</span>
+                <button class="button is-small is-link" onclick=self.link.callback(|_| Msg::RefreshRadios)>
+                    { self.t("refresh") }
+                </button>
+            </div>
+        }
+    }
+
+    // Banner shown over stale data until the connection is restored
+    pub fn stale_view(&self) -> Html {
+        match (self.is_stale(), self.connection_lost_at) {
+            (true, Some(lost_at)) => html! {
+                <div class="notification is-warning stale-warning">
+                    <span>{ format!("{} ({}s)", self.t("stale_data"), (self.now - lost_at).num_seconds().max(0)) }</span>
+                    {
+                        match self.reconnect_at {
+                            Some(at) => html! {
+                                <span class="reconnecting">{ self.t("reconnecting_in").replace("{}", &(at - self.now).num_seconds().max(0).to_string()) }</span>
+                            },
+                            None => html! {},
+                        }
+                    }
+                    <button class="button is-small is-link" onclick=self.link.callback(|_| Msg::Connect)>
+                        { self.t("connect") }
+                    </button>
+                </div>
+            },
+            _ => html! {},
+        }
+    }
+
+    // Shown after tuning to a station already worked on the band/mode
+    pub fn dupe_warning_view(&self) -> Html {
+        match &self.dupe_warning {
+            Some(call) => html! {
+                <div class="notification is-danger dupe-warning">
+                    <button class="delete" onclick=self.link.callback(|_| Msg::DismissDupeWarning)></button>
+                    { format!("DUPE {}: {}", call, self.t("dupe_help")) }
+                </div>
+            },
+            None => html! {},
+        }
+    }
+
+    // Keyboard shortcuts from the keymap, so rebound keys show up here
+    pub fn shortcut_help_view(&self) -> Html {
+        if !self.show_shortcut_help {
+            return html! {};
+        }
+        html! {
+            <div class="modal is-active shortcut-help">
+                <div class="modal-background" onclick=self.link.callback(|_| Msg::ToggleShortcutHelp)></div>
+                <div class="modal-content">
+                    <div class="box">
+                        <p class="title is-5">{ self.t("shortcuts") }</p>
+                        <table class="table is-fullwidth">
+                            <tbody>
+                                {
+                                    for ACTIONS.iter().map(|action| {
+                                        html! {
+                                            <tr>
+                                                <td><kbd>{ keymap::key_for(&self.settings.keymap, *action) }</kbd></td>
+                                                <td>{ self.t(action.name()) }</td>
+                                            </tr>
+                                        }
+                                    })
+                                }
+                                <tr>
+                                    <td><kbd>{ "Esc" }</kbd></td>
+                                    <td>{ self.t("shortcut_close") }</td>
+                                </tr>
+                            </tbody>
+                        </table>
+                    </div>
+                </div>
+                <button class="modal-close is-large" onclick=self.link.callback(|_| Msg::ToggleShortcutHelp)></button>
+            </div>
+        }
+    }
+
+    // Audio scheduling lead against the target latency, with recent
+    // underruns (ran dry) and overruns (queued audio dropped)
+    fn audio_buffer_view(&self) -> Html {
+        let buffered =
+            match self.audio.buffered() {
+                Some(buffered) => buffered,
+                None => return html! {},
+            };
+        let target = self.audio.target_latency();
+        let class =
+            if buffered <= 0.0 {
+                "progress is-small is-danger"
+            } else if buffered < target / 2.0 {
+                "progress is-small is-warning"
+            } else {
+                "progress is-small is-success"
+            };
+        html! {
+            <span class="audio-buffer" title=self.t("audio_buffer")>
+                <progress class=class value=buffered.min(target * 2.0).to_string() max=(target * 2.0).to_string()></progress>
+                <span>{ format!("{:.0} ms", buffered * 1000.0) }</span>
+                <span class="has-text-danger" title=self.t("underruns")>{ format!("↓{}", self.audio.recent_underruns()) }</span>
+                <span class="has-text-warning-dark" title=self.t("overruns")>{ format!("↑{}", self.audio.recent_overruns()) }</span>
+            </span>
+        }
+    }
+
+    // Prompt shown while the browser keeps audio suspended
+    pub fn audio_prompt_view(&self) -> Html {
+        if self.no_audio {
+            return html! {};
+        }
+        if self.audio.unavailable().is_some() {
+            return html! {
+                <div class="notification is-warning audio-prompt">
+                    <span class="icon is-small">
+                        <i class="fas fa-volume-mute"></i>
+                    </span>
+                    <span>{ self.t("audio_unavailable") }</span>
+                </div>
+            };
+        }
+        match (self.audio.audio_ctx(), self.audio.audio_enabled()) {
+            (Some(_), false) => html! {
+                <div class="notification is-warning audio-prompt" onclick=self.link.callback(|_| Msg::ResumeAudio)>
+                    <span class="icon is-small">
+                        <i class="fas fa-volume-mute"></i>
+                    </span>
+                    <span>{ self.t("enable_audio") }</span>
+                </div>
+            },
+            _ => html! {},
+        }
+    }
+
+    fn profiles_view(&self) -> Html {
+        let form = &self.profile_form;
+        html! {
+            <div class="profiles">
+                <h2 class="subtitle">{ self.t("profiles") }</h2>
+                <table class="table is-fullwidth">
+                    {
+                        for self.settings.profiles.iter().enumerate().map(|(i, profile)| {
+                            let last = self.settings.last_profile == Some(i);
+                            html! {
+                                <tr class=if last { "is-selected" } else { "" }>
+                                    <td>{ &profile.label }</td>
+                                    <td>{ &profile.url }</td>
+                                    <td>{ match (&profile.frequency, &profile.mode) {
+                                            (Some(frequency), Some(mode)) => format!("{} kHz {}", frequency / 1000.0, mode),
+                                            (Some(frequency), None) => format!("{} kHz", frequency / 1000.0),
+                                            (None, Some(mode)) => mode.to_string(),
+                                            (None, None) => "".to_string(),
+                                        } }</td>
+                                    <td style="text-align:right">
+                                        <button class="button is-small is-link" onclick=self.link.callback(move |_| Msg::ConnectProfile(i))>{ self.t("connect") }</button>
+                                        <button class="button is-small is-text" onclick=self.link.callback(move |_| Msg::EditProfile(i))>
+                                            <span class="icon is-small">
+                                                <i class="fas fa-edit"></i>
+                                            </span>
+                                        </button>
+                                        <button class="button is-small is-text" onclick=self.link.callback(move |_| Msg::DeleteProfile(i))>
+                                            <span class="icon is-small">
+                                                <i class="far fa-trash-alt"></i>
+                                            </span>
+                                        </button>
+                                    </td>
+                                </tr>
+                            }
+                        })
+                    }
+                </table>
+                <div class="field is-grouped">
+                    <input class="input" type="text" placeholder=self.t("profile_label") value=&form.label
+                        oninput=self.link.callback(|e: InputData| Msg::UpdateProfileForm(ProfileField::Label, e.value)) />
+                    <input class="input" type="text" placeholder=&self.ws_location value=&form.url
+                        oninput=self.link.callback(|e: InputData| Msg::UpdateProfileForm(ProfileField::Url, e.value)) />
+                    <input class="input" type="text" placeholder=self.t("profile_frequency")
+                        value=form.frequency.map(|f| (f / 1000.0).to_string()).unwrap_or_default()
+                        oninput=self.link.callback(|e: InputData| Msg::UpdateProfileForm(ProfileField::Frequency, e.value)) />
+                    <select class="select"
+                        onchange=self.link.callback(|e: ChangeData|
+                            match e {
+                                ChangeData::Select(sel) => Msg::UpdateProfileForm(ProfileField::Mode, sel.value()),
+                                _ => Msg::None,
+                            } )>
+                        <option value="" selected=form.mode.is_none()>{ self.t("col_mode") }</option>
+                        {
+                            for RECEIVER_MODES.iter().map(|mode| {
+                                html! { <option selected=form.mode == Some(mode.mode().to_string())>{ mode.mode() }</option> }
+                            })
+                        }
+                    </select>
+                    <button class="button" onclick=self.link.callback(|_| Msg::SaveProfile)>
+                        { if self.editing_profile.is_some() { self.t("save") } else { self.t("add") } }
+                    </button>
+                </div>
+            </div>
+        }
+    }
+
     pub fn disconnected_view(&self) -> Html {
         html! {
             <>
+                { self.toasts_view() }
                 <div class="container">
-                    <h1 class="title">{ "Disconnected" }</h1>
-                    <p>{ "Make sure SparkSDR has Web Sockets enabled, and hostname is correct "}</p>
+                    <h1 class="title">{ self.t("disconnected") }</h1>
+                    <p>{ self.t("disconnected_help") }</p>
+                    {
+                        match &self.certificate_hint {
+                            Some(url) => html! {
+                                <div class="notification is-warning">
+                                    <p>{ self.t("certificate_help") }</p>
+                                    <a class="button is-small" href=url target="_blank">
+                                        { format!("{} {}", self.t("accept_certificate"), url) }
+                                    </a>
+                                </div>
+                            },
+                            None => html! {},
+                        }
+                    }
                     <div class="field is-grouped ws-connection">
                     <input class="input"
                         value=&self.ws_location
@@ -1066,9 +5119,11 @@ impl Model {
                             if e.key() == "Enter" { Msg::Connect } else { Msg::None }
                         }) />
                     <button class="button is-link" onclick=self.link.callback(move |_| Msg::Connect )>
-                        { "Connect" }
+                        { self.t("connect") }
                     </button>
                     </div>
+                    { self.profiles_view() }
+                    { self.replay_view() }
                 </div>
                 { self.footer_view() }
             </>
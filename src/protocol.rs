@@ -0,0 +1,196 @@
+// Decoding of SparkSDR response frames. CommandResponse and the types
+// in it come from sparkplug, so rather than serde attributes on them
+// frames are adjusted here when they don't decode as sent: fields a
+// newer SparkSDR renamed are mapped back, nulls are dropped so missing
+// optional fields decode as None, and a list response with entries
+// that don't decode keeps the ones that do.
+use anyhow::Error;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use yew::services::{ConsoleService};
+
+use sparkplug::{CommandResponse,Receiver,Radio,Spot};
+
+// (name sent by SparkSDR, name sparkplug expects), add an entry when
+// a SparkSDR release renames a field
+const FIELD_ALIASES: &[(&str, &str)] = &[];
+
+thread_local! {
+    // Decoding problems already logged, so each is only logged once
+    static LOGGED: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+fn log_once(message: String) {
+    LOGGED.with(|logged| {
+        if logged.borrow_mut().insert(message.clone()) {
+            ConsoleService::log(&message);
+        }
+    });
+}
+
+pub fn parse_response(text: &str) -> Result<CommandResponse, Error> {
+    // the common case, a frame that decodes as sent
+    if let Ok(response) = serde_json::from_str::<CommandResponse>(text) {
+        return Ok(response);
+    }
+    let mut notes = Vec::new();
+    let response = decode(text, FIELD_ALIASES, &mut notes);
+    notes.into_iter().for_each(log_once);
+    response
+}
+
+// Decode a frame that didn't decode as sent, decoding problems worth
+// logging are added to `notes`
+fn decode(text: &str, aliases: &[(&str, &str)], notes: &mut Vec<String>) -> Result<CommandResponse, Error> {
+    let mut value: Value = serde_json::from_str(text)?;
+    upgrade(&mut value, aliases, notes);
+    match CommandResponse::deserialize(&value) {
+        Ok(response) => Ok(response),
+        Err(err) => {
+            notes.push(format!("response only partly decoded ({}), fields: {}", err, field_names(&value)));
+            partial(&value, notes).ok_or_else(|| Error::from(err))
+        }
+    }
+}
+
+// Rename aliased fields and drop null fields, at every level
+fn upgrade(value: &mut Value, aliases: &[(&str, &str)], notes: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            for (alias, field) in aliases {
+                if !map.contains_key(*field) {
+                    if let Some(renamed) = map.remove(*alias) {
+                        notes.push(format!("response field {} read as {}", alias, field));
+                        map.insert(field.to_string(), renamed);
+                    }
+                }
+            }
+            let nulls: Vec<String> = map.iter().filter(|(_, v)| v.is_null()).map(|(k, _)| k.to_string()).collect();
+            for key in nulls {
+                map.remove(&key);
+            }
+            map.values_mut().for_each(|v| upgrade(v, aliases, notes));
+        },
+        Value::Array(items) => items.iter_mut().for_each(|v| upgrade(v, aliases, notes)),
+        _ => (),
+    }
+}
+
+fn field_names(value: &Value) -> String {
+    match value {
+        Value::Object(map) => map.keys().cloned().collect::<Vec<String>>().join(", "),
+        _ => String::new(),
+    }
+}
+
+// List field of the response named by a frame's `cmd` tag
+fn list_field(cmd: &str) -> Option<&'static str> {
+    match cmd {
+        "getReceiversResponse" => Some("receivers"),
+        "getRadiosResponse" => Some("radios"),
+        "spotResponse" => Some("spots"),
+        _ => None,
+    }
+}
+
+// Entries of the `field` list that decode as `T`, None if none do
+fn entries<T: DeserializeOwned>(map: &Map<String, Value>, field: &str, notes: &mut Vec<String>) -> Option<Vec<T>> {
+    let items = map.get(field)?.as_array()?;
+    let decoded: Vec<T> = items.iter().filter_map(|item| T::deserialize(item).ok()).collect();
+    if decoded.is_empty() {
+        return None;
+    }
+    if decoded.len() < items.len() {
+        notes.push(format!("skipped {} of {} {} entries that didn't decode", items.len() - decoded.len(), items.len(), field));
+    }
+    Some(decoded)
+}
+
+// List responses rebuilt from the entries that decode, picked by the
+// frame's `cmd` tag
+fn partial(value: &Value, notes: &mut Vec<String>) -> Option<CommandResponse> {
+    let map = value.as_object()?;
+    let field = list_field(map.get("cmd")?.as_str()?)?;
+    match field {
+        "receivers" => entries::<Receiver>(map, field, notes).map(|receivers| CommandResponse::Receivers { receivers }),
+        "radios" => entries::<Radio>(map, field, notes).map(|radios| CommandResponse::Radios { radios }),
+        _ => entries::<Spot>(map, field, notes).map(|spots| CommandResponse::Spots { spots }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Entry {
+        name: String,
+        snr: Option<i32>,
+    }
+
+    fn upgraded(json: &str, aliases: &[(&str, &str)]) -> (Value, Vec<String>) {
+        let mut value: Value = serde_json::from_str(json).unwrap();
+        let mut notes = Vec::new();
+        upgrade(&mut value, aliases, &mut notes);
+        (value, notes)
+    }
+
+    #[test]
+    fn renamed_field_read_under_its_old_name() {
+        let (value, notes) = upgraded(r#"{"entries":[{"callsign":"KD9QZO"}]}"#, &[("callsign", "name")]);
+        assert_eq!(value["entries"][0]["name"], "KD9QZO");
+        assert!(value["entries"][0].get("callsign").is_none());
+        assert_eq!(notes, vec!["response field callsign read as name".to_string()]);
+    }
+
+    #[test]
+    fn renamed_field_ignored_when_both_are_sent() {
+        let (value, notes) = upgraded(r#"{"name":"new","callsign":"old"}"#, &[("callsign", "name")]);
+        assert_eq!(value["name"], "new");
+        assert!(notes.is_empty());
+    }
+
+    #[test]
+    fn null_field_decodes_as_none() {
+        let (value, _) = upgraded(r#"{"name":"KD9QZO","snr":null}"#, &[]);
+        assert!(value.get("snr").is_none());
+        assert_eq!(Entry::deserialize(&value).unwrap(), Entry { name: "KD9QZO".to_string(), snr: None });
+    }
+
+    #[test]
+    fn partly_bad_list_keeps_good_entries() {
+        let value: Value = serde_json::from_str(r#"{"spots":[{"name":"A1","snr":3},{"snr":"bad"},{"name":"B2"}]}"#).unwrap();
+        let mut notes = Vec::new();
+        let decoded: Vec<Entry> = entries(value.as_object().unwrap(), "spots", &mut notes).unwrap();
+        assert_eq!(decoded, vec![
+            Entry { name: "A1".to_string(), snr: Some(3) },
+            Entry { name: "B2".to_string(), snr: None },
+        ]);
+        assert_eq!(notes, vec!["skipped 1 of 3 spots entries that didn't decode".to_string()]);
+    }
+
+    #[test]
+    fn list_with_no_good_entries_is_none() {
+        let value: Value = serde_json::from_str(r#"{"spots":[{"snr":1}]}"#).unwrap();
+        let mut notes = Vec::new();
+        assert_eq!(entries::<Entry>(value.as_object().unwrap(), "spots", &mut notes), None);
+    }
+
+    #[test]
+    fn list_picked_by_cmd_tag() {
+        assert_eq!(list_field("getReceiversResponse"), Some("receivers"));
+        assert_eq!(list_field("getRadiosResponse"), Some("radios"));
+        assert_eq!(list_field("spotResponse"), Some("spots"));
+        assert_eq!(list_field("getVersionResponse"), None);
+    }
+
+    #[test]
+    fn untagged_frame_not_rebuilt() {
+        let value: Value = serde_json::from_str(r#"{"radios":[{}]}"#).unwrap();
+        let mut notes = Vec::new();
+        assert!(partial(&value, &mut notes).is_none());
+    }
+}
@@ -0,0 +1,153 @@
+use web_sys::{AudioContext, GainNode, Blob, BlobEvent, BlobPropertyBag, MediaRecorder, MediaStreamAudioDestinationNode, Url};
+use yew::services::{ConsoleService};
+use yew::{ComponentLink};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use chrono::Utc;
+
+use crate::model::{Model,Msg};
+
+const RECORDING_MIME_TYPE: &str = "audio/webm";
+
+// Records the decoded receiver audio (tapped off the gain node)
+// into a downloadable webm file
+pub struct AudioRecorder {
+    recorder: Option<MediaRecorder>,
+    destination: Option<MediaStreamAudioDestinationNode>,
+    gain: Option<GainNode>,
+    // Receiver being recorded
+    recording: Option<u32>,
+    chunks: Vec<Blob>,
+    // Object url and file name of the last finished recording
+    download: Option<(String, String)>,
+    // Event handlers, kept alive for the life of the recorder
+    ondataavailable: Option<Closure<dyn FnMut(BlobEvent)>>,
+    onstop: Option<Closure<dyn FnMut(JsValue)>>,
+}
+
+impl AudioRecorder {
+    pub fn new() -> AudioRecorder {
+        AudioRecorder {
+            recorder: None,
+            destination: None,
+            gain: None,
+            recording: None,
+            chunks: Vec::new(),
+            download: None,
+            ondataavailable: None,
+            onstop: None,
+        }
+    }
+
+    pub fn recording(&self) -> Option<u32> {
+        self.recording
+    }
+
+    pub fn download(&self) -> Option<&(String, String)> {
+        self.download.as_ref()
+    }
+
+    pub fn start(&mut self, link: &ComponentLink<Model>, audio_ctx: &AudioContext, gain: &GainNode, receiver_id: u32) {
+        if self.recording.is_some() {
+            return;
+        }
+
+        let destination =
+            match audio_ctx.create_media_stream_destination() {
+                Ok(destination) => destination,
+                Err(err) => {
+                    ConsoleService::error(&format!("unable to create recording destination: {:?}", err));
+                    return;
+                }
+            };
+        let recorder =
+            match MediaRecorder::new_with_media_stream(&destination.stream()) {
+                Ok(recorder) => recorder,
+                Err(err) => {
+                    ConsoleService::error(&format!("unable to create media recorder: {:?}", err));
+                    return;
+                }
+            };
+        if let Err(err) = gain.connect_with_audio_node(&destination) {
+            ConsoleService::error(&format!("unable to connect recorder: {:?}", err));
+            return;
+        }
+
+        let notify = link.callback(Msg::RecordedAudio);
+        let ondataavailable = Closure::wrap(Box::new(move |e: BlobEvent| {
+            if let Some(blob) = e.data() {
+                notify.emit(blob);
+            }
+        }) as Box<dyn FnMut(BlobEvent)>);
+        recorder.set_ondataavailable(Some(ondataavailable.as_ref().unchecked_ref()));
+
+        let notify = link.callback(|_| Msg::RecordingStopped);
+        let onstop = Closure::wrap(Box::new(move |_| {
+            notify.emit(());
+        }) as Box<dyn FnMut(JsValue)>);
+        recorder.set_onstop(Some(onstop.as_ref().unchecked_ref()));
+
+        match recorder.start() {
+            Ok(_) => {
+                ConsoleService::log(&format!("recording audio from receiver: {}", receiver_id));
+                self.chunks = Vec::new();
+                self.recorder = Some(recorder);
+                self.destination = Some(destination);
+                self.gain = Some(gain.clone());
+                self.recording = Some(receiver_id);
+                self.ondataavailable = Some(ondataavailable);
+                self.onstop = Some(onstop);
+            },
+            Err(err) => {
+                gain.disconnect_with_audio_node(&destination).unwrap_or(());
+                ConsoleService::error(&format!("unable to start recording: {:?}", err));
+            }
+        }
+    }
+
+    // Stop recording, the file is assembled once the recorder
+    // reports Msg::RecordingStopped
+    pub fn stop(&mut self) {
+        if let Some(recorder) = &self.recorder {
+            if let Err(err) = recorder.stop() {
+                ConsoleService::error(&format!("unable to stop recording: {:?}", err));
+            }
+        }
+        if let (Some(gain), Some(destination)) = (&self.gain, &self.destination) {
+            gain.disconnect_with_audio_node(destination).unwrap_or(());
+        }
+        self.destination = None;
+        self.gain = None;
+    }
+
+    pub fn add_chunk(&mut self, blob: Blob) {
+        self.chunks.push(blob);
+    }
+
+    // Build the downloadable file from the recorded chunks
+    pub fn finish(&mut self) {
+        let parts = js_sys::Array::new();
+        for chunk in self.chunks.drain(..) {
+            parts.push(&chunk);
+        }
+
+        let blob = Blob::new_with_blob_sequence_and_options(&parts, BlobPropertyBag::new().type_(RECORDING_MIME_TYPE));
+        match blob.and_then(|blob| Url::create_object_url_with_blob(&blob)) {
+            Ok(url) => {
+                if let Some((previous, _)) = self.download.take() {
+                    Url::revoke_object_url(&previous).unwrap_or(());
+                }
+                let name = format!("rx{}-{}.webm", self.recording.unwrap_or(0), Utc::now().format("%Y%m%d-%H%M%S"));
+                self.download = Some((url, name));
+            },
+            Err(err) => {
+                ConsoleService::error(&format!("unable to save recording: {:?}", err));
+            }
+        }
+
+        self.recorder = None;
+        self.recording = None;
+        self.ondataavailable = None;
+        self.onstop = None;
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+use yew::{ComponentLink};
+use yew::services::timeout::{TimeoutService, TimeoutTask};
+
+use crate::model::{Model,Msg};
+
+// Received text frames kept for export
+const FRAME_LOG_LIMIT: usize = 2000;
+
+// A text frame received from SparkSDR, `t` is milliseconds since the
+// first frame in the recording
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Frame {
+    pub t: f64,
+    pub data: String,
+}
+
+// Text frames received on the live connection
+pub struct FrameLog {
+    frames: VecDeque<(f64, String)>,
+}
+
+impl FrameLog {
+    pub fn new() -> FrameLog {
+        FrameLog { frames: VecDeque::new() }
+    }
+
+    pub fn received(&mut self, data: &str) {
+        self.frames.push_back((js_sys::Date::now(), data.to_string()));
+        while self.frames.len() > FRAME_LOG_LIMIT {
+            self.frames.pop_front();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    // Json recording that ReplayPlayer can load
+    pub fn export(&self) -> String {
+        let start = self.frames.front().map(|(t, _)| *t).unwrap_or(0.0);
+        let frames: Vec<Frame> = self.frames.iter()
+            .map(|(t, data)| Frame { t: t - start, data: data.clone() })
+            .collect();
+        serde_json::to_string(&frames).unwrap_or("[]".to_string())
+    }
+}
+
+// Plays a recording back through Msg::ReplayFrame, keeping the
+// original spacing between frames divided by `speed` (0 plays every
+// frame without waiting)
+pub struct ReplayPlayer {
+    frames: VecDeque<Frame>,
+    loaded: usize,
+    speed: f64,
+    task: Option<TimeoutTask>,
+}
+
+impl ReplayPlayer {
+    pub fn new() -> ReplayPlayer {
+        ReplayPlayer { frames: VecDeque::new(), loaded: 0, speed: 1.0, task: None }
+    }
+
+    pub fn load(&mut self, frames: Vec<Frame>) {
+        self.task = None;
+        self.loaded = frames.len();
+        self.frames = frames.into_iter().collect();
+    }
+
+    pub fn unload(&mut self) {
+        self.task = None;
+        self.loaded = 0;
+        self.frames.clear();
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.loaded > 0
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.task.is_some()
+    }
+
+    // (frames played, frames loaded)
+    pub fn progress(&self) -> (usize, usize) {
+        (self.loaded - self.frames.len(), self.loaded)
+    }
+
+    pub fn play(&mut self, link: &ComponentLink<Model>, speed: f64) {
+        self.speed = speed;
+        self.schedule(link, 0.0);
+    }
+
+    pub fn pause(&mut self) {
+        self.task = None;
+    }
+
+    // Next frame to replay, scheduling the one after it
+    pub fn next(&mut self, link: &ComponentLink<Model>) -> Option<String> {
+        self.task = None;
+        let frame = self.frames.pop_front()?;
+        if let Some(following) = self.frames.front() {
+            let delay = if self.speed > 0.0 { (following.t - frame.t) / self.speed } else { 0.0 };
+            self.schedule(link, delay);
+        }
+        Some(frame.data)
+    }
+
+    fn schedule(&mut self, link: &ComponentLink<Model>, delay: f64) {
+        if self.frames.is_empty() {
+            return;
+        }
+        let callback = link.callback(|_| Msg::ReplayNext);
+        self.task = Some(TimeoutService::spawn(Duration::from_millis(delay.max(0.0) as u64), callback));
+    }
+}
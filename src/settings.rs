@@ -0,0 +1,529 @@
+use std::collections::HashMap;
+use yew::format::{Json};
+use yew::services::storage::{StorageService};
+use yew::services::{ConsoleService};
+
+use crate::i18n::{Locale};
+use crate::band_plan::{Region,LicenseClass};
+use crate::keymap::{KeyBinding};
+
+const SETTINGS_KEY: &str = "radio.settings";
+
+// How groups of three digits are separated when displaying
+// a frequency (e.g. 14,074,000 vs 14.074.000)
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum NumberFormat {
+    Comma,
+    Period,
+    Space,
+}
+
+pub const NUMBER_FORMATS: [NumberFormat; 3] = [
+    NumberFormat::Comma,
+    NumberFormat::Period,
+    NumberFormat::Space,
+];
+
+impl NumberFormat {
+    pub fn separator(&self) -> char {
+        match self {
+            NumberFormat::Comma => ',',
+            NumberFormat::Period => '.',
+            NumberFormat::Space => ' ',
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NumberFormat::Comma => "comma",
+            NumberFormat::Period => "period",
+            NumberFormat::Space => "space",
+        }
+    }
+
+    pub fn from_name(name: &str) -> NumberFormat {
+        match name {
+            "period" => NumberFormat::Period,
+            "space" => NumberFormat::Space,
+            _ => NumberFormat::Comma,
+        }
+    }
+
+    // Sample frequency used to label the format in the settings view
+    pub fn example(&self) -> String {
+        let sep = self.separator();
+        format!("14{}074{}000", sep, sep)
+    }
+}
+
+// Timezone used when displaying spot times
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TimeDisplay {
+    Utc,
+    Local,
+}
+
+impl TimeDisplay {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimeDisplay::Utc => "utc",
+            TimeDisplay::Local => "local",
+        }
+    }
+
+    pub fn from_name(name: &str) -> TimeDisplay {
+        match name {
+            "local" => TimeDisplay::Local,
+            _ => TimeDisplay::Utc,
+        }
+    }
+}
+
+// Spot table row spacing
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TableDensity {
+    Comfortable,
+    Compact,
+}
+
+pub const TABLE_DENSITIES: [TableDensity; 2] = [
+    TableDensity::Comfortable,
+    TableDensity::Compact,
+];
+
+impl TableDensity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TableDensity::Comfortable => "comfortable",
+            TableDensity::Compact => "compact",
+        }
+    }
+
+    pub fn from_name(name: &str) -> TableDensity {
+        match name {
+            "compact" => TableDensity::Compact,
+            _ => TableDensity::Comfortable,
+        }
+    }
+}
+
+// Where spot row flags come from
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum FlagSource {
+    // flag-icon stylesheet and SVGs bundled under static/
+    Icons,
+    Emoji,
+    Off,
+}
+
+pub const FLAG_SOURCES: [FlagSource; 3] = [
+    FlagSource::Icons,
+    FlagSource::Emoji,
+    FlagSource::Off,
+];
+
+impl FlagSource {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FlagSource::Icons => "icons",
+            FlagSource::Emoji => "emoji",
+            FlagSource::Off => "off",
+        }
+    }
+
+    pub fn from_name(name: &str) -> FlagSource {
+        match name {
+            "emoji" => FlagSource::Emoji,
+            "off" => FlagSource::Off,
+            _ => FlagSource::Icons,
+        }
+    }
+}
+
+// What happens when a spot row is clicked
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SpotClickAction {
+    Tune,
+    Copy,
+    Lookup,
+    Nothing,
+}
+
+pub const SPOT_CLICK_ACTIONS: [SpotClickAction; 4] = [
+    SpotClickAction::Tune,
+    SpotClickAction::Copy,
+    SpotClickAction::Lookup,
+    SpotClickAction::Nothing,
+];
+
+impl SpotClickAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpotClickAction::Tune => "tune",
+            SpotClickAction::Copy => "copy",
+            SpotClickAction::Lookup => "lookup",
+            SpotClickAction::Nothing => "none",
+        }
+    }
+
+    pub fn from_name(name: &str) -> SpotClickAction {
+        match name {
+            "copy" => SpotClickAction::Copy,
+            "lookup" => SpotClickAction::Lookup,
+            "none" => SpotClickAction::Nothing,
+            _ => SpotClickAction::Tune,
+        }
+    }
+}
+
+// Callsign lookup website, `{call}` in the url is replaced
+// with the callsign
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LookupProvider {
+    pub name: String,
+    pub url: String,
+}
+
+impl LookupProvider {
+    pub fn new(name: &str, url: &str) -> LookupProvider {
+        LookupProvider { name: name.to_string(), url: url.to_string() }
+    }
+
+    pub fn url_for(&self, call: &str) -> String {
+        self.url.replace("{call}", call)
+    }
+}
+
+fn default_lookup_providers() -> Vec<LookupProvider> {
+    vec![
+        LookupProvider::new("QRZ.com", "https://www.qrz.com/db/{call}"),
+        LookupProvider::new("HamQTH", "https://www.hamqth.com/{call}"),
+    ]
+}
+
+// Mode a band implies (e.g. LSB on 40m), applied when a receiver
+// moves onto the band
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BandMode {
+    pub band: String,
+    pub mode: String,
+}
+
+fn default_band_modes() -> Vec<BandMode> {
+    [("160m", "LSB"), ("80m", "LSB"), ("60m", "USB"), ("40m", "LSB"), ("30m", "CW"),
+     ("20m", "USB"), ("17m", "USB"), ("15m", "USB"), ("12m", "USB"), ("10m", "USB"), ("6m", "USB")]
+        .iter()
+        .map(|(band, mode)| BandMode { band: band.to_string(), mode: mode.to_string() })
+        .collect()
+}
+
+// Saved SparkSDR connection, optionally tuning the first receiver
+// to a frequency/mode once connected
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionProfile {
+    pub label: String,
+    pub url: String,
+    pub frequency: Option<f32>,
+    pub mode: Option<String>,
+}
+
+fn default_mode_colors() -> HashMap<String, String> {
+    [("FT8", "#3273dc"), ("FT4", "#00d1b2"), ("JT65", "#b86bff"), ("JT9", "#b86bff"),
+     ("WSPR", "#7a7a7a"), ("CW", "#ffdd57"), ("USB", "#ff3860"), ("LSB", "#ff3860")]
+        .iter()
+        .map(|(mode, color)| (mode.to_string(), color.to_string()))
+        .collect()
+}
+
+// User preferences persisted to local storage. New fields should
+// have a sensible Default so older stored settings still restore.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub number_format: NumberFormat,
+    pub locale: Locale,
+    pub time_display: TimeDisplay,
+    // Words in a spot message to highlight (e.g. CQ, QRZ, POTA)
+    pub highlight_tokens: Vec<String>,
+    // Station callsign, used to spot stations calling me
+    pub my_call: String,
+    // Station Maidenhead locator, for the grey-line indicator
+    pub my_grid: String,
+    // Play a tone when a new spot is calling my_call
+    pub alert_on_call: bool,
+    // Audio output device id (None for the system default)
+    pub audio_output_device: Option<String>,
+    // Mute audio when the signal level drops below squelch_level (dBFS)
+    pub squelch_enabled: bool,
+    pub squelch_level: f32,
+    // Seconds of audio scheduled ahead of playback
+    pub target_latency: f64,
+    // Skip Web Audio entirely (rig and spot control only), takes
+    // effect on the next load
+    pub no_audio: bool,
+    pub spot_click_action: SpotClickAction,
+    pub flag_source: FlagSource,
+    pub table_density: TableDensity,
+    // Highlight just decoded spot rows, fading out, and dim old ones
+    pub spot_fade: bool,
+    pub lookup_providers: Vec<LookupProvider>,
+    // Index into lookup_providers used for callsign lookups
+    pub lookup_provider: usize,
+    pub band_modes: Vec<BandMode>,
+    // Seconds between local ui refreshes (clock, stale data age),
+    // 0 disables them
+    pub tick_interval: u64,
+    pub profiles: Vec<ConnectionProfile>,
+    // Index into profiles of the last one connected to
+    pub last_profile: Option<usize>,
+    // Spot row accent color by mode name (e.g. "FT8" => "#3273dc")
+    pub mode_colors: HashMap<String, String>,
+    // Local nicknames by receiver id (e.g. "20m FT8 monitor")
+    pub receiver_names: HashMap<u32, String>,
+    // Transverter LO offsets (Hz) by receiver id, added to the IF the
+    // radio is tuned to for the operating frequency
+    pub transverter_offsets: HashMap<u32, f64>,
+    // Band stacking: the last frequency and mode used on each band,
+    // returned to when the band is selected
+    pub band_stack: HashMap<String, (f32, String)>,
+    // Band plan shown next to the receiver frequency
+    pub band_plan_region: Region,
+    pub license_class: LicenseClass,
+    // Special permission (e.g. MARS) to operate outside the license
+    // class privileges, turns off the out of privilege warning
+    pub privilege_override: bool,
+    // Most spots kept in the table, 0 for no limit
+    pub spot_limit: usize,
+    // Minutes before a spot is removed, 0 keeps spots until trimmed
+    // by spot_limit
+    pub spot_max_age: i64,
+    // Show the DXCC prefix/entity column in the spot table
+    pub show_dxcc: bool,
+    // Reports of the same callsign within this many Hz and seconds
+    // are merged into one spot
+    pub merge_frequency: f32,
+    pub merge_seconds: i64,
+    // Upper bounds (km) of the local, regional and continental
+    // distance colors, anything further is DX
+    pub distance_thresholds: [f64; 3],
+    // Largest time offsets (seconds) shown as tight and loose sync,
+    // anything further is flagged as poor
+    pub dt_thresholds: [f64; 2],
+    // Show the radio power/add receiver controls in the navbar,
+    // otherwise just a summary
+    pub show_radio_list: bool,
+    // Keyboard shortcuts changed from their defaults
+    pub keymap: Vec<KeyBinding>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            number_format: NumberFormat::Comma,
+            locale: Locale::English,
+            time_display: TimeDisplay::Utc,
+            highlight_tokens: vec!["CQ".to_string()],
+            my_call: String::new(),
+            my_grid: String::new(),
+            alert_on_call: true,
+            audio_output_device: None,
+            squelch_enabled: false,
+            squelch_level: -60.0,
+            target_latency: 0.1,
+            no_audio: false,
+            spot_click_action: SpotClickAction::Tune,
+            flag_source: FlagSource::Icons,
+            table_density: TableDensity::Comfortable,
+            spot_fade: true,
+            lookup_providers: default_lookup_providers(),
+            lookup_provider: 0,
+            band_modes: default_band_modes(),
+            tick_interval: 1,
+            profiles: Vec::new(),
+            last_profile: None,
+            mode_colors: default_mode_colors(),
+            receiver_names: HashMap::new(),
+            transverter_offsets: HashMap::new(),
+            band_stack: HashMap::new(),
+            band_plan_region: Region::R2,
+            license_class: LicenseClass::NotSet,
+            privilege_override: false,
+            spot_limit: 100,
+            spot_max_age: 30,
+            show_dxcc: false,
+            merge_frequency: 50.0,
+            merge_seconds: 5,
+            distance_thresholds: [500.0, 2000.0, 5000.0],
+            dt_thresholds: [0.5, 1.5],
+            show_radio_list: true,
+            keymap: Vec::new(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn restore(storage: &StorageService) -> Settings {
+        match storage.restore(SETTINGS_KEY) {
+            Json(Ok(settings)) => settings,
+            Json(Err(_)) => {
+                ConsoleService::log("no stored settings found, using defaults");
+                Settings::default()
+            }
+        }
+    }
+
+    pub fn store(&self, storage: &mut StorageService) {
+        storage.store(SETTINGS_KEY, Json(self));
+    }
+
+    // Url to look up `call` with the selected provider
+    pub fn lookup_url(&self, call: &str) -> Option<String> {
+        self.lookup_providers.get(self.lookup_provider)
+            .or(self.lookup_providers.first())
+            .map(|provider| provider.url_for(call))
+    }
+
+    pub fn add_lookup_provider(&mut self, name: &str, url: &str) {
+        if !name.trim().is_empty() && url.contains("{call}") {
+            self.lookup_providers.push(LookupProvider::new(name.trim(), url.trim()));
+        }
+    }
+
+    pub fn remove_lookup_provider(&mut self, index: usize) {
+        if index < self.lookup_providers.len() {
+            self.lookup_providers.remove(index);
+            if index < self.lookup_provider {
+                self.lookup_provider -= 1;
+            } else if self.lookup_provider >= self.lookup_providers.len() {
+                self.lookup_provider = self.lookup_providers.len().saturating_sub(1);
+            }
+        }
+    }
+
+    // Mode name to use on `band`, if one is configured
+    pub fn band_mode(&self, band: &str) -> Option<&str> {
+        self.band_modes.iter()
+            .find(|b| b.band == band && !b.mode.is_empty())
+            .map(|b| b.mode.as_str())
+    }
+
+    // Empty `mode` leaves the mode unchanged on that band
+    pub fn set_band_mode(&mut self, band: &str, mode: &str) {
+        match self.band_modes.iter().position(|b| b.band == band) {
+            Some(index) => self.band_modes[index].mode = mode.to_string(),
+            None => self.band_modes.push(BandMode { band: band.to_string(), mode: mode.to_string() }),
+        }
+    }
+
+    pub fn last_profile(&self) -> Option<&ConnectionProfile> {
+        self.last_profile.and_then(|index| self.profiles.get(index))
+    }
+
+    pub fn remove_profile(&mut self, index: usize) {
+        if index < self.profiles.len() {
+            self.profiles.remove(index);
+            self.last_profile =
+                match self.last_profile {
+                    Some(last) if last == index => None,
+                    Some(last) if last > index => Some(last - 1),
+                    last => last,
+                };
+        }
+    }
+
+    // Add anything from `other` this configuration doesn't have yet,
+    // keeping current values where both have one
+    pub fn merge(&mut self, other: Settings) {
+        for token in other.highlight_tokens {
+            if !self.is_highlight_token(&token) {
+                self.highlight_tokens.push(token);
+            }
+        }
+        for provider in other.lookup_providers {
+            if !self.lookup_providers.contains(&provider) {
+                self.lookup_providers.push(provider);
+            }
+        }
+        for band_mode in other.band_modes {
+            if self.band_mode(&band_mode.band).is_none() {
+                self.set_band_mode(&band_mode.band, &band_mode.mode);
+            }
+        }
+        for profile in other.profiles {
+            if !self.profiles.contains(&profile) {
+                self.profiles.push(profile);
+            }
+        }
+        if self.my_call.is_empty() {
+            self.my_call = other.my_call;
+        }
+        if self.my_grid.is_empty() {
+            self.my_grid = other.my_grid;
+        }
+    }
+
+    pub fn mode_color(&self, mode: &str) -> Option<&String> {
+        self.mode_colors.get(&mode.to_uppercase())
+    }
+
+    pub fn receiver_name(&self, id: u32) -> Option<&String> {
+        self.receiver_names.get(&id)
+    }
+
+    pub fn transverter_offset(&self, id: u32) -> f64 {
+        self.transverter_offsets.get(&id).cloned().unwrap_or(0.0)
+    }
+
+    // A zero `offset` removes it
+    pub fn set_transverter_offset(&mut self, id: u32, offset: f64) {
+        if offset == 0.0 || !offset.is_finite() {
+            self.transverter_offsets.remove(&id);
+        } else {
+            self.transverter_offsets.insert(id, offset);
+        }
+    }
+
+    // Last (frequency, mode) used on `band`
+    pub fn band_stack(&self, band: &str) -> Option<(f32, &str)> {
+        self.band_stack.get(band).map(|(frequency, mode)| (*frequency, mode.as_str()))
+    }
+
+    pub fn set_band_stack(&mut self, band: &str, frequency: f32, mode: &str) {
+        self.band_stack.insert(band.to_string(), (frequency, mode.to_string()));
+    }
+
+    // Empty `name` removes the nickname
+    pub fn set_receiver_name(&mut self, id: u32, name: &str) {
+        match name.trim() {
+            "" => self.receiver_names.remove(&id),
+            name => self.receiver_names.insert(id, name.to_string()),
+        };
+    }
+
+    // Squelch threshold if enabled
+    pub fn squelch(&self) -> Option<f32> {
+        if self.squelch_enabled { Some(self.squelch_level) } else { None }
+    }
+
+    // Whole word match so "CQ" doesn't highlight inside a callsign
+    pub fn is_highlight_token(&self, word: &str) -> bool {
+        self.highlight_tokens.iter().any(|token| token.eq_ignore_ascii_case(word))
+    }
+
+    // Add `word` to the highlight words, or remove it if it's there
+    pub fn toggle_highlight_token(&mut self, word: &str) {
+        match self.highlight_tokens.iter().position(|token| token.eq_ignore_ascii_case(word)) {
+            Some(index) => { self.highlight_tokens.remove(index); },
+            None => self.highlight_tokens.push(word.to_string()),
+        }
+    }
+
+    // Parse a comma/space separated list of highlight words
+    pub fn set_highlight_tokens(&mut self, tokens: &str) {
+        self.highlight_tokens = tokens
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|t| !t.is_empty())
+            .map(|t| t.to_uppercase())
+            .collect();
+    }
+}
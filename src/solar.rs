@@ -0,0 +1,84 @@
+// Sunrise/sunset for the station location, for the grey-line
+// indicator. Uses the sunrise equation, accurate to a minute or two
+// which is plenty for propagation.
+use chrono::{DateTime, TimeZone, Utc};
+
+// Julian date of the unix epoch and of J2000
+const JD_UNIX_EPOCH: f64 = 2440587.5;
+const JD_2000: f64 = 2451545.0;
+
+// Minutes either side of sunrise/sunset counted as grey-line
+pub const GREY_LINE_MINUTES: i64 = 30;
+
+pub enum SunTimes {
+    // Sunrise and sunset (UTC) on the current day
+    RiseSet(DateTime<Utc>, DateTime<Utc>),
+    // The sun doesn't set or rise today (polar day/night)
+    AlwaysUp,
+    AlwaysDown,
+}
+
+// Latitude/longitude (degrees, north and east positive) of the
+// center of a 4 or 6 character Maidenhead locator
+pub fn grid_to_lat_lon(grid: &str) -> Option<(f64, f64)> {
+    let grid: Vec<char> = grid.trim().to_uppercase().chars().collect();
+    if grid.len() != 4 && grid.len() != 6 {
+        return None;
+    }
+    let field = |c: char, max: char| match ('A'..=max).contains(&c) {
+        true => Some((c as u8 - b'A') as f64),
+        false => None,
+    };
+    let square = |c: char| c.to_digit(10).map(f64::from);
+
+    let mut lon = field(grid[0], 'R')? * 20.0 - 180.0 + square(grid[2])? * 2.0;
+    let mut lat = field(grid[1], 'R')? * 10.0 - 90.0 + square(grid[3])?;
+    if grid.len() == 6 {
+        lon += field(grid[4], 'X')? * 5.0 / 60.0 + 2.5 / 60.0;
+        lat += field(grid[5], 'X')? * 2.5 / 60.0 + 1.25 / 60.0;
+    } else {
+        lon += 1.0;
+        lat += 0.5;
+    }
+    Some((lat, lon))
+}
+
+fn to_time(jd: f64) -> DateTime<Utc> {
+    let seconds = ((jd - JD_UNIX_EPOCH) * 86400.0).round() as i64;
+    Utc.timestamp(seconds, 0)
+}
+
+// Sunrise and sunset around the solar noon of the UTC day of `now`
+pub fn sun_times(now: DateTime<Utc>, lat: f64, lon: f64) -> SunTimes {
+    let day = now.timestamp().div_euclid(86400) as f64;
+    let n = (day + JD_UNIX_EPOCH + 0.5 - JD_2000).round();
+    let mean_noon = n - lon / 360.0;
+
+    let anomaly = (357.5291 + 0.98560028 * mean_noon).rem_euclid(360.0).to_radians();
+    let center = 1.9148 * anomaly.sin() + 0.02 * (2.0 * anomaly).sin() + 0.0003 * (3.0 * anomaly).sin();
+    let ecliptic_lon = (anomaly.to_degrees() + center + 180.0 + 102.9372).rem_euclid(360.0).to_radians();
+    let transit = JD_2000 + mean_noon + 0.0053 * anomaly.sin() - 0.0069 * (2.0 * ecliptic_lon).sin();
+
+    let declination = (ecliptic_lon.sin() * 23.4397f64.to_radians().sin()).asin();
+    let lat = lat.to_radians();
+    let cos_hour_angle =
+        ((-0.833f64).to_radians().sin() - lat.sin() * declination.sin()) / (lat.cos() * declination.cos());
+    if cos_hour_angle < -1.0 {
+        return SunTimes::AlwaysUp;
+    }
+    if cos_hour_angle > 1.0 {
+        return SunTimes::AlwaysDown;
+    }
+    let hour_angle = cos_hour_angle.acos().to_degrees() / 360.0;
+    SunTimes::RiseSet(to_time(transit - hour_angle), to_time(transit + hour_angle))
+}
+
+// Whether `now` is within GREY_LINE_MINUTES of sunrise or sunset
+pub fn is_grey_line(now: DateTime<Utc>, times: &SunTimes) -> bool {
+    match times {
+        SunTimes::RiseSet(rise, set) => {
+            [rise, set].iter().any(|t| (now - **t).num_minutes().abs() <= GREY_LINE_MINUTES)
+        },
+        _ => false,
+    }
+}
@@ -1,16 +1,24 @@
 use web_sys::{HtmlCanvasElement};
 use yew::prelude::*;
 use yew::services::{ConsoleService};
-use wasm_bindgen::{JsCast,Clamped};
+use wasm_bindgen::{JsCast,JsValue,Clamped};
 use web_sys::{ImageData};
 
 use crate::color::{ColourGradient};
 
+// First byte of a binary frame carrying FFT data, audio frames are 1
+pub const SPECTRUM_FRAME: u8 = 2;
+const SCOPE_WIDTH: f64 = 2048.0;
+const SCOPE_HEIGHT: f64 = 80.0;
+
 pub struct SpectrumProvider {
     pub canvas_node_ref: NodeRef,
     pub tmp_canvas_node_ref: NodeRef,
     pub canvas: Option<HtmlCanvasElement>,
     pub tmp_canvas: Option<HtmlCanvasElement>,
+    // band scope trace, only on the index page
+    pub scope_node_ref: NodeRef,
+    pub scope: Option<HtmlCanvasElement>,
     subscribed_spectrum: Option<u32>,
     freq_start: f64,
     freq_stop: f64,
@@ -29,6 +37,8 @@ impl SpectrumProvider {
             tmp_canvas_node_ref: NodeRef::default(),
             canvas: None,
             tmp_canvas: None,
+            scope_node_ref: NodeRef::default(),
+            scope: None,
             subscribed_spectrum: None,
             freq_start: 0.0,
             freq_stop: 0.0,
@@ -45,6 +55,23 @@ impl SpectrumProvider {
         self.freq_stop
     }
 
+    // Where `frequency` falls across the displayed span (0.0 - 1.0),
+    // None when it's outside it or nothing has been received yet
+    pub fn position(&self, frequency: f64) -> Option<f64> {
+        if self.freq_stop <= self.freq_start || frequency < self.freq_start || frequency > self.freq_stop {
+            return None;
+        }
+        Some((frequency - self.freq_start) / (self.freq_stop - self.freq_start))
+    }
+
+    // Frequency at `fraction` (0.0 - 1.0) across the displayed span
+    pub fn frequency_at(&self, fraction: f64) -> Option<f64> {
+        if self.freq_stop <= self.freq_start {
+            return None;
+        }
+        Some(self.freq_start + fraction.max(0.0).min(1.0) * (self.freq_stop - self.freq_start))
+    }
+
     pub fn receiving_spectrum(&self) -> Option<u32> {
         self.subscribed_spectrum
     }
@@ -53,7 +80,9 @@ impl SpectrumProvider {
         self.subscribed_spectrum = receiver;
     }
 
-    pub fn import_spectrum_data(&mut self, data: js_sys::ArrayBuffer, start: f64, stop: f64) {
+    // Returns true when the displayed span changed
+    pub fn import_spectrum_data(&mut self, data: js_sys::ArrayBuffer, start: f64, stop: f64) -> bool {
+        let mut span_changed = false;
         let data = js_sys::Float32Array::new(&data.slice(1+4+8+8));
         let mut tmp = [0.0; 2048];
         data.copy_to(&mut tmp);
@@ -68,6 +97,7 @@ impl SpectrumProvider {
                     js_sys::eval(&js).unwrap();
                     self.freq_stop = stop;
                     self.freq_start = start;
+                    span_changed = true;
                 }
 
                 // canvas ctx
@@ -79,11 +109,13 @@ impl SpectrumProvider {
 
                 let mut line = vec![0; 8192];
                 let mut iter = line.chunks_exact_mut(4);
+                let mut levels = Vec::with_capacity(2048);
                 for i in 0..2047 {
                     // average pixel value over our buffer array
                     let mut max = self.spectrum_buffer.iter().max_by_key(|b| b[i] as u32 ).unwrap()[i] + 180.0;
                     if max > 255.0 { max = 255.0; }
                     if max < 0.0 { max = 0.0; }
+                    levels.push(max as f64);
                     let color = self.gradient.get_colour(max);
 
                     // Color to ImageData pixel
@@ -107,6 +139,7 @@ impl SpectrumProvider {
                 ctx.set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0).unwrap();
 
                 self.spectrum_buffer = Vec::new();
+                self.draw_scope(&levels);
             },
             (_, None, _) |
             (_, _, None) => {
@@ -114,5 +147,24 @@ impl SpectrumProvider {
             },
             _ => ()
         }
+        span_changed
+    }
+
+    // Band scope: the latest levels (0 - 255) as a trace
+    fn draw_scope(&self, levels: &[f64]) {
+        let ctx =
+            match self.scope.as_ref().and_then(|scope| scope.get_context("2d").ok().flatten()) {
+                Some(ctx) => ctx.dyn_into::<web_sys::CanvasRenderingContext2d>().unwrap(),
+                None => return,
+            };
+        ctx.set_fill_style(&JsValue::from_str("#000000"));
+        ctx.fill_rect(0.0, 0.0, SCOPE_WIDTH, SCOPE_HEIGHT);
+        ctx.set_stroke_style(&JsValue::from_str("#7fdbff"));
+        ctx.begin_path();
+        for (x, level) in levels.iter().enumerate() {
+            let y = SCOPE_HEIGHT - level / 255.0 * SCOPE_HEIGHT;
+            if x == 0 { ctx.move_to(0.0, y) } else { ctx.line_to(x as f64, y) }
+        }
+        ctx.stroke();
     }
 }
\ No newline at end of file
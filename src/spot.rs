@@ -6,17 +6,20 @@ use yew::format::{Json,Text,Nothing};
 use yew::services::fetch::{FetchService, Request, Response};
 use yew::services::storage::{Area, StorageService};
 use yew::services::{ConsoleService};
-use std::collections::HashMap;
+use std::collections::{HashMap,HashSet};
+use chrono::{DateTime,Utc};
 
 use ham_rs::{Call,CountryInfo,Country,LogEntry,Band};
 use ham_rs::lotw::LoTWStatus;
-use sparkplug::Spot;
+use sparkplug::{Spot,Receiver};
 
 use crate::model::{Model,Msg};
+use crate::worked::{WorkedEntities};
 
 const FILTERS_KEY: &str = "radio.spots.filters";
 const LOTW_USERS_KEY: &str = "radio.spots.lotwUsers";
 const STATES_OVERLAY_KEY: &str = "radio.spots.statesOverlay";
+const BLOCKLIST_KEY: &str = "radio.spots.blocklist";
 
 #[derive(Debug, Serialize, Deserialize)]
 enum LoTWUsers {
@@ -30,18 +33,59 @@ enum StatesOverlay {
     GeoJson(String)
 }
 
+// Identifies a spot (call, time, frequency) for pinning
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct SpotKey {
+    call: String,
+    time: i64,
+    frequency: i64,
+}
+
+impl SpotKey {
+    pub fn new(spot: &Spot) -> SpotKey {
+        SpotKey {
+            call: spot.call.call(),
+            time: spot.time.timestamp(),
+            frequency: spot.frequency as i64,
+        }
+    }
+
+    // Stable row key for rendering, merged reports keep the key of
+    // the spot they were merged into
+    pub fn id(&self) -> String {
+        format!("{}-{}-{}", self.call, self.time, self.frequency)
+    }
+}
+
 pub struct SpotDB {
     storage: StorageService,
     // Spots from enabling SubscribeToSpots
     spots: Vec<Spot>,
-    pending_spots: HashMap<String,Vec<Spot>>,
+    pending_spots: HashMap<String,Vec<(Spot,Option<u32>)>>,
     spot_filters: Vec<SpotFilter>,
     // Local callsign cache
     callsigns: HashMap<String,CallsignInfo>,
     lotw_ft: Option<FetchTask>,
     lotw_users: LoTWUsers,
     states_ft: Option<FetchTask>,
-    states_overlay: StatesOverlay
+    states_overlay: StatesOverlay,
+    // Countries/states worked, for the new country/state filters
+    worked: WorkedEntities,
+    // Spots kept at the top and never trimmed
+    pinned: HashSet<SpotKey>,
+    // Spots heard more than once (e.g. by several skimmers) are merged
+    // when within these tolerances (Hz, seconds)
+    merge_frequency: f32,
+    merge_seconds: i64,
+    // Number of reports merged into a spot
+    sources: HashMap<SpotKey, usize>,
+    // Spots dropped by trim_spots since the table was last cleared
+    trimmed: usize,
+    // Receiver each spot was decoded on, by its tuned frequency
+    heard_by: HashMap<SpotKey, u32>,
+    // Callsigns never shown, exact or prefix with a trailing "*"
+    // (e.g. "VK*")
+    blocklist: HashSet<String>,
 }
 
 impl SpotDB {
@@ -71,6 +115,15 @@ impl SpotDB {
             }
         };
 
+        let worked = WorkedEntities::restore(&storage);
+        let blocklist = {
+            if let Json(Ok(blocklist)) = storage.restore(BLOCKLIST_KEY) {
+                blocklist
+            } else {
+                HashSet::new()
+            }
+        };
+
         SpotDB {
             storage,
             spots: Vec::new(),
@@ -80,19 +133,186 @@ impl SpotDB {
             lotw_ft: None,
             lotw_users: lotw_users,
             states_ft: None,
-            states_overlay: states_overlay
+            states_overlay: states_overlay,
+            worked,
+            pinned: HashSet::new(),
+            merge_frequency: 50.0,
+            merge_seconds: 5,
+            sources: HashMap::new(),
+            heard_by: HashMap::new(),
+            trimmed: 0,
+            blocklist,
+        }
+    }
+
+    pub fn blocklist(&self) -> Vec<&String> {
+        let mut blocklist: Vec<&String> = self.blocklist.iter().collect();
+        blocklist.sort();
+        blocklist
+    }
+
+    pub fn is_blocked(&self, call: &str) -> bool {
+        let call = call.to_uppercase();
+        self.blocklist.iter().any(|entry| {
+            match entry.strip_suffix('*') {
+                Some(prefix) => call.starts_with(prefix),
+                None => *entry == call,
+            }
+        })
+    }
+
+    // Block `entry` and drop spots it matches
+    pub fn block(&mut self, entry: &str) {
+        let entry = entry.trim().to_uppercase();
+        if entry.is_empty() || entry == "*" {
+            return;
         }
+        self.blocklist.insert(entry);
+        self.storage.store(BLOCKLIST_KEY, Json(&self.blocklist));
+        let spots = std::mem::take(&mut self.spots);
+        self.spots = spots.into_iter().filter(|spot| !self.is_blocked(&spot.call.call())).collect();
+        self.forget_sources();
+    }
+
+    pub fn unblock(&mut self, entry: &str) {
+        self.blocklist.remove(entry);
+        self.storage.store(BLOCKLIST_KEY, Json(&self.blocklist));
     }
 
-    pub fn clear_spots(&mut self) {
-        self.spots = Vec::new();
+    pub fn set_merge_tolerance(&mut self, frequency: f32, seconds: i64) {
+        self.merge_frequency = frequency;
+        self.merge_seconds = seconds;
+    }
+
+    // Number of reports merged into `spot`
+    pub fn source_count(&self, spot: &Spot) -> usize {
+        self.sources.get(&SpotKey::new(spot)).cloned().unwrap_or(1)
+    }
+
+    // Receiver that decoded `spot`, if it's still tuned there
+    pub fn heard_by(&self, spot: &Spot) -> Option<u32> {
+        self.heard_by.get(&SpotKey::new(spot)).cloned()
+    }
+
+    // Existing spot of the same station close enough in frequency and
+    // time to be the same transmission
+    fn find_merge(&self, spot: &Spot) -> Option<usize> {
+        let call = spot.call.call();
+        self.spots.iter().position(|s| {
+            s.call.call() == call
+                && (s.frequency - spot.frequency).abs() <= self.merge_frequency
+                && (s.time - spot.time).num_seconds().abs() <= self.merge_seconds
+        })
+    }
+
+    // Drop source counts and receivers for spots no longer in the table
+    fn forget_sources(&mut self) {
+        let keys: HashSet<SpotKey> = self.spots.iter().map(SpotKey::new).collect();
+        self.sources.retain(|key, _| keys.contains(key));
+        self.heard_by.retain(|key, _| keys.contains(key));
+    }
+
+    pub fn clear_spots(&mut self, keep_pinned: bool) {
+        if keep_pinned {
+            let pinned = &self.pinned;
+            self.spots.retain(|spot| pinned.contains(&SpotKey::new(spot)));
+        } else {
+            self.spots = Vec::new();
+            self.pinned = HashSet::new();
+        }
         self.pending_spots = HashMap::new();
+        self.trimmed = 0;
+        self.forget_sources();
+    }
+
+    // Number of older spots dropped to stay within the spot limit
+    pub fn trimmed(&self) -> usize {
+        self.trimmed
+    }
+
+    pub fn has_pinned(&self) -> bool {
+        !self.pinned.is_empty()
     }
 
     pub fn spots(&self) -> &Vec<Spot> {
         &self.spots
     }
 
+    // Number of spots on each band, lowest band first
+    pub fn band_counts(&self) -> Vec<(String, usize)> {
+        let mut counts: HashMap<String, (f32, usize)> = HashMap::new();
+        for spot in &self.spots {
+            if let Some(band) = Band::new(spot.tuned_frequency as i32).band() {
+                let entry = counts.entry(band.to_string()).or_insert((spot.tuned_frequency, 0));
+                entry.0 = entry.0.min(spot.tuned_frequency);
+                entry.1 += 1;
+            }
+        }
+        let mut counts: Vec<(String, (f32, usize))> = counts.into_iter().collect();
+        counts.sort_by(|a, b| (a.1).0.partial_cmp(&(b.1).0).unwrap_or(std::cmp::Ordering::Equal));
+        counts.into_iter().map(|(band, (_, count))| (band, count)).collect()
+    }
+
+    // Spots newest first by decode time rather than arrival order,
+    // spots with the same time keep the most recently received first
+    pub fn newest_first(&self) -> Vec<&Spot> {
+        let mut spots: Vec<&Spot> = self.spots.iter().rev().collect();
+        spots.sort_by(|a, b| b.time.cmp(&a.time));
+        spots
+    }
+
+    pub fn is_pinned(&self, spot: &Spot) -> bool {
+        self.pinned.contains(&SpotKey::new(spot))
+    }
+
+    pub fn toggle_pin(&mut self, key: SpotKey) {
+        if !self.pinned.remove(&key) {
+            self.pinned.insert(key);
+        }
+    }
+
+    pub fn worked(&self) -> &WorkedEntities {
+        &self.worked
+    }
+
+    pub fn add_worked_log(&mut self, entries: &Vec<LogEntry>) {
+        self.worked.add_log(entries);
+        self.worked.store(&mut self.storage);
+    }
+
+    pub fn add_worked_call(&mut self, call: &Call) {
+        self.worked.add_call(call);
+        self.worked.store(&mut self.storage);
+    }
+
+    // Blocklist and worked entities from a settings file, added to
+    // the current ones when merging
+    pub fn import_lists(&mut self, blocklist: Vec<String>, worked: WorkedEntities, merge: bool) {
+        if !merge {
+            self.blocklist.clear();
+            self.worked.clear();
+        }
+        self.blocklist.extend(blocklist);
+        self.worked.merge(worked);
+        self.storage.store(BLOCKLIST_KEY, Json(&self.blocklist));
+        self.worked.store(&mut self.storage);
+    }
+
+    pub fn unmark_worked_country(&mut self, code: &str) {
+        self.worked.remove_country(code);
+        self.worked.store(&mut self.storage);
+    }
+
+    pub fn unmark_worked_state(&mut self, state: &str) {
+        self.worked.remove_state(state);
+        self.worked.store(&mut self.storage);
+    }
+
+    pub fn clear_worked(&mut self) {
+        self.worked.clear();
+        self.worked.store(&mut self.storage);
+    }
+
     pub fn has_lotw_users(&self) -> bool {
         match self.lotw_users {
             LoTWUsers::Users(_) => true,
@@ -202,9 +422,46 @@ impl SpotDB {
     }
 
     // CommandResponse: spotResponse
-    pub fn add_spot(&mut self, link: &ComponentLink<Model>, spot: Spot, logs: &Option<Vec<LogEntry>>) {
+    // Adds a batch of spots passing the cq only/current receiver
+    // filters, noting which of `receivers` heard each, and trimming
+    // the list once at the end. Returns true if any spot is a station
+    // calling `my_call`.
+    pub fn add_spots(&mut self, link: &ComponentLink<Model>, spots: Vec<Spot>, my_call: &str, receivers: &[Receiver], current_rx: Option<Receiver>, limit: usize) -> bool {
+        let cq_only = self.cq_only_spot_filter_enabled();
+        let current_rx =
+            match current_rx {
+                Some(receiver) if self.current_receiver_spot_filter_enabled() && receiver.has_spots() => Some(receiver),
+                _ => None,
+            };
+
+        let mut called = false;
+        for spot in spots {
+            if cq_only && !spot.is_cq() {
+                continue;
+            }
+            if let Some(receiver) = &current_rx {
+                if !spot.current_rx(receiver) {
+                    continue;
+                }
+            }
+            let heard_by = receivers.iter().find(|r| r.has_spots() && spot.current_rx(r)).map(|r| r.id);
+            if self.add_spot(link, spot, my_call, heard_by) {
+                called = true;
+            }
+        }
+        self.trim_spots(limit);
+        called
+    }
+
+    // Returns true if the spot is a station calling `my_call`.
+    // `heard_by` is the receiver that decoded it, if known
+    pub fn add_spot(&mut self, link: &ComponentLink<Model>, spot: Spot, my_call: &str, heard_by: Option<u32>) -> bool {
+        if self.is_blocked(&spot.call.call()) {
+            return false;
+        }
         // FIXME: temp fix
         let mut spot = spot;
+        let calling_me = is_calling(&spot, my_call);
 
         let pending =
             match self.has_callsign_info(&spot.call) {
@@ -212,6 +469,9 @@ impl SpotDB {
                     spot.set_call(call);
                     false
                 },
+                // already waiting on info for this call (e.g. earlier
+                // in the same batch), queue behind that request
+                None if self.pending_spots.contains_key(&spot.call.call()) => true,
                 None => {
                     // If a US callsign attempt to fetch additonal callsign
                     // info from server otherwise we are done
@@ -227,17 +487,33 @@ impl SpotDB {
 
         match pending {
             true => {
-                self.pending_spots.entry(spot.call.call()).or_insert(Vec::new()).push(spot);
+                self.pending_spots.entry(spot.call.call()).or_insert(Vec::new()).push((spot, heard_by));
             },
-            false => self.internal_spot_push(spot, logs),
+            false => self.internal_spot_push(spot, heard_by),
         }
+        calling_me
     }
 
-    fn internal_spot_push(&mut self, spot: Spot, logs: &Option<Vec<LogEntry>>) {
-        match (logs, self.state_spot_filter_enabled(), self.country_spot_filter_enabled()) {
-            (Some(logs), true, false) if !spot.new_state(logs) => (),
-            (Some(logs), false, true) if !spot.new_country(logs) => (),
-            (Some(logs), true, true) if !spot.new_country(logs) || !spot.new_state(logs) => (),
+    // Receivers are only recorded for spots that end up in the table,
+    // under the key of the row they're in
+    fn internal_spot_push(&mut self, spot: Spot, heard_by: Option<u32>) {
+        // same transmission reported again, keep the best report
+        if let Some(index) = self.find_merge(&spot) {
+            let key = SpotKey::new(&self.spots[index]);
+            if let Some(receiver_id) = heard_by {
+                self.heard_by.entry(key.clone()).or_insert(receiver_id);
+            }
+            *self.sources.entry(key).or_insert(1) += 1;
+            if spot.snr > self.spots[index].snr {
+                self.spots[index].snr = spot.snr;
+            }
+            return;
+        }
+        let (new_state, new_country) = (self.worked.new_state(&spot.call), self.worked.new_country(&spot.call));
+        match (self.state_spot_filter_enabled(), self.country_spot_filter_enabled()) {
+            (true, false) if !new_state => (),
+            (false, true) if !new_country => (),
+            (true, true) if !new_country || !new_state => (),
             _ => {
                 match self.lotw_spot_filter_enabled() {
                     true if !spot.uses_lotw() => (),
@@ -266,6 +542,9 @@ impl SpotDB {
                             },
                             None => (),
                         }
+                        if let Some(receiver_id) = heard_by {
+                            self.heard_by.insert(SpotKey::new(&spot), receiver_id);
+                        }
                         self.spots.push(spot)
                     }
                 }
@@ -274,29 +553,76 @@ impl SpotDB {
     }
 
     // helper function to remove all except `limit` recent spots
+    // (0 for no limit), pinned spots are never removed
     pub fn trim_spots(&mut self, limit: usize) {
-        if self.spots.len() > limit {
-            let drain = self.spots.len() - limit;
-            self.spots.drain(0..drain);
+        if limit > 0 && self.spots.len() > limit {
+            let mut drain = self.spots.len() - limit;
+            let pinned = &self.pinned;
+            let count = self.spots.len();
+            self.spots.retain(|spot| {
+                if drain > 0 && !pinned.contains(&SpotKey::new(spot)) {
+                    drain -= 1;
+                    false
+                } else {
+                    true
+                }
+            });
+            self.trimmed += count - self.spots.len();
+            self.forget_sources();
         }
     }
 
-    pub fn cache_callsign_info(&mut self, call: Call, logs: &Option<Vec<LogEntry>>) {
+    // Remove spots heard before `cutoff` except pinned ones, true if
+    // any were removed
+    pub fn expire_spots(&mut self, cutoff: DateTime<Utc>) -> bool {
+        let count = self.spots.len();
+        let pinned = &self.pinned;
+        self.spots.retain(|spot| spot.time >= cutoff || pinned.contains(&SpotKey::new(spot)));
+        if count != self.spots.len() {
+            self.forget_sources();
+        }
+        count != self.spots.len()
+    }
+
+    // Abort callsign and LoTW lookups still in progress, spots waiting
+    // on a lookup are dropped
+    pub fn cancel_fetches(&mut self) {
+        self.callsigns.retain(|_, info| match info {
+            CallsignInfo::Requested(_) => false,
+            _ => true,
+        });
+        self.pending_spots = HashMap::new();
+        self.lotw_ft = None;
+    }
+
+    pub fn cache_callsign_info(&mut self, call: Call) {
         self.callsigns.insert(call.call(), CallsignInfo::Found(call.clone()));
 
         // remove spots from pending queue and publish them
         // with callsign info
         match self.pending_spots.remove(&call.call()) {
             Some(mut spots) => {
-                for mut spot in spots.drain(..) {
+                for (mut spot, heard_by) in spots.drain(..) {
                     spot.set_call(call.clone());
-                    self.internal_spot_push(spot, logs);
+                    self.internal_spot_push(spot, heard_by);
                 }
             },
             None => ()
         }
     }
 
+    pub fn filters(&self) -> &Vec<SpotFilter> {
+        &self.spot_filters
+    }
+
+    pub fn set_filters(&mut self, filters: Vec<SpotFilter>) {
+        self.spot_filters = filters;
+        self.spot_filters.sort();
+        self.spot_filters.dedup();
+        self.storage.store(FILTERS_KEY, Json(&self.spot_filters));
+        self.update_states_overlay_js();
+    }
+
     pub fn add_filter(&mut self, filter: SpotFilter) {
         self.spot_filters.push(filter);
         self.spot_filters.sort();
@@ -351,7 +677,22 @@ impl SpotDB {
     }
 }
 
-#[derive(Ord, PartialOrd, Eq, PartialEq, Deserialize, Serialize)]
+// True if the spot message is addressed to `call`, e.g. "W1ABC K1XYZ FN42"
+// or "<W1ABC> K1XYZ R-12" when calling W1ABC
+pub fn is_calling(spot: &Spot, call: &str) -> bool {
+    if call.is_empty() {
+        return false;
+    }
+    match spot.msg.as_ref().and_then(|msg| msg.split_whitespace().next()) {
+        Some(first) => {
+            let first = first.trim_matches(|c| c == '<' || c == '>');
+            first.split('/').any(|part| part.eq_ignore_ascii_case(call))
+        },
+        None => false,
+    }
+}
+
+#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Deserialize, Serialize)]
 pub enum SpotFilter {
     CQOnly,
     NewState,
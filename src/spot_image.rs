@@ -0,0 +1,68 @@
+// Draws the spot table onto a canvas for sharing as a PNG. The
+// table is drawn directly from the spot data rather than copying
+// the DOM, so it looks the same in every browser.
+use wasm_bindgen::JsCast;
+use wasm_bindgen::JsValue;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, HtmlElement};
+
+const ROW_HEIGHT: f64 = 20.0;
+const HEADER_HEIGHT: f64 = 48.0;
+const PADDING: f64 = 10.0;
+const CHAR_WIDTH: f64 = 8.0;
+
+// PNG data url of `rows` under a `title` header line. The first row
+// is the column headings.
+pub fn render_png(title: &str, rows: &[Vec<String>]) -> Result<String, JsValue> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| JsValue::from_str("no document"))?;
+    let canvas: HtmlCanvasElement = document.create_element("canvas")?.dyn_into()?;
+
+    // column widths from the longest cell in each column
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let widths: Vec<f64> = (0..columns)
+        .map(|c| rows.iter().filter_map(|row| row.get(c)).map(|cell| cell.chars().count()).max().unwrap_or(0) as f64 * CHAR_WIDTH + PADDING)
+        .collect();
+    let width = (widths.iter().sum::<f64>() + PADDING * 2.0).max(title.chars().count() as f64 * CHAR_WIDTH + PADDING * 2.0);
+    let height = HEADER_HEIGHT + rows.len() as f64 * ROW_HEIGHT + PADDING;
+    canvas.set_width(width as u32);
+    canvas.set_height(height as u32);
+
+    let ctx: CanvasRenderingContext2d = canvas.get_context("2d")?
+        .ok_or_else(|| JsValue::from_str("no 2d context"))?
+        .dyn_into()?;
+    ctx.set_fill_style(&JsValue::from_str("#ffffff"));
+    ctx.fill_rect(0.0, 0.0, width, height);
+
+    ctx.set_fill_style(&JsValue::from_str("#363636"));
+    ctx.set_font("bold 16px sans-serif");
+    ctx.fill_text(title, PADDING, PADDING + 18.0)?;
+
+    ctx.set_font("13px monospace");
+    for (r, row) in rows.iter().enumerate() {
+        let y = HEADER_HEIGHT + r as f64 * ROW_HEIGHT;
+        if r % 2 == 1 {
+            ctx.set_fill_style(&JsValue::from_str("#f5f5f5"));
+            ctx.fill_rect(0.0, y, width, ROW_HEIGHT);
+        }
+        ctx.set_fill_style(&JsValue::from_str(if r == 0 { "#3273dc" } else { "#363636" }));
+        let mut x = PADDING;
+        for (cell, cell_width) in row.iter().zip(&widths) {
+            ctx.fill_text(cell, x, y + ROW_HEIGHT - 6.0)?;
+            x += cell_width;
+        }
+    }
+    canvas.to_data_url_with_type("image/png")
+}
+
+// Start a download of `url` as `name`
+pub fn download(url: &str, name: &str) -> Result<(), JsValue> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| JsValue::from_str("no document"))?;
+    let link: HtmlElement = document.create_element("a")?.dyn_into()?;
+    link.set_attribute("href", url)?;
+    link.set_attribute("download", name)?;
+    link.click();
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use std::collections::BTreeSet;
+use yew::format::{Json};
+use yew::services::storage::{StorageService};
+use yew::services::{ConsoleService};
+
+use ham_rs::{Call,CountryInfo,LogEntry};
+
+const WORKED_KEY: &str = "radio.worked";
+
+// Countries (by code) and US states worked, accumulated across log
+// imports and contacts logged in the app. Spots are badged "new"
+// against this rather than only the last imported log.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkedEntities {
+    countries: BTreeSet<String>,
+    states: BTreeSet<String>,
+}
+
+impl WorkedEntities {
+    pub fn restore(storage: &StorageService) -> WorkedEntities {
+        match storage.restore(WORKED_KEY) {
+            Json(Ok(worked)) => worked,
+            Json(Err(_)) => {
+                ConsoleService::log("no worked entities found");
+                WorkedEntities::default()
+            }
+        }
+    }
+
+    pub fn store(&self, storage: &mut StorageService) {
+        storage.store(WORKED_KEY, Json(self));
+    }
+
+    pub fn countries(&self) -> &BTreeSet<String> {
+        &self.countries
+    }
+
+    pub fn states(&self) -> &BTreeSet<String> {
+        &self.states
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.countries.is_empty() && self.states.is_empty()
+    }
+
+    pub fn has_country(&self, code: &str) -> bool {
+        self.countries.contains(code)
+    }
+
+    pub fn has_state(&self, state: &str) -> bool {
+        self.states.contains(state)
+    }
+
+    pub fn add_country(&mut self, code: &str) {
+        self.countries.insert(code.to_string());
+    }
+
+    pub fn remove_country(&mut self, code: &str) {
+        self.countries.remove(code);
+    }
+
+    pub fn add_state(&mut self, state: &str) {
+        self.states.insert(state.to_string());
+    }
+
+    pub fn remove_state(&mut self, state: &str) {
+        self.states.remove(state);
+    }
+
+    pub fn merge(&mut self, other: WorkedEntities) {
+        self.countries.extend(other.countries);
+        self.states.extend(other.states);
+    }
+
+    pub fn clear(&mut self) {
+        self.countries.clear();
+        self.states.clear();
+    }
+
+    // Add the country/state of a worked callsign
+    pub fn add_call(&mut self, call: &Call) {
+        if let Ok(country) = call.country() {
+            self.add_country(&country.code().to_string());
+        }
+        if let Some(state) = call.state() {
+            self.add_state(&state);
+        }
+    }
+
+    pub fn add_log(&mut self, entries: &Vec<LogEntry>) {
+        for entry in entries {
+            self.add_call(&entry.call);
+        }
+    }
+
+    // True if the spotted call is in a country not yet worked
+    pub fn new_country(&self, call: &Call) -> bool {
+        match call.country() {
+            Ok(country) => !self.has_country(&country.code().to_string()),
+            Err(_) => false,
+        }
+    }
+
+    // True if the spotted call is in a state not yet worked
+    pub fn new_state(&self, call: &Call) -> bool {
+        match call.state() {
+            Some(state) => !self.has_state(&state),
+            None => false,
+        }
+    }
+}
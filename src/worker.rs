@@ -0,0 +1,175 @@
+// Heavy processing moved off the main thread so the UI stays smooth
+// during large imports. static/js/worker.js loads this same wasm
+// module in a Web Worker and calls `workerMain`; requests and
+// responses cross as JSON strings:
+//
+//   main thread  -> WorkerRequest::ImportAdif(adif text)
+//   worker       -> WorkerResponse::AdifImported { entries, skipped }
+//
+// If workers are unavailable, or the worker fails (e.g. js/worker.js
+// doesn't load), requests are processed inline.
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
+use web_sys::{DedicatedWorkerGlobalScope, Event, MessageEvent, Worker, WorkerOptions, WorkerType};
+use yew::{Callback, ComponentLink};
+use yew::services::{ConsoleService};
+
+use ham_rs::LogEntry;
+
+use crate::model::{Model,Msg};
+
+const WORKER_SCRIPT: &str = "js/worker.js";
+
+#[derive(Serialize, Deserialize)]
+pub enum WorkerRequest {
+    // Parse an ADIF log into entries
+    ImportAdif(String),
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum WorkerResponse {
+    // Parsed log entries and the number of records that didn't parse
+    AdifImported { entries: Vec<LogEntry>, skipped: usize },
+    Failed(String),
+}
+
+// The work itself, run in the worker (or inline as a fallback)
+pub fn process(request: WorkerRequest) -> WorkerResponse {
+    match request {
+        WorkerRequest::ImportAdif(text) => {
+            match ham_rs::adif::adif_parse("import", &mut text.as_bytes()) {
+                Ok(adif) => {
+                    let mut entries = Vec::new();
+                    let mut skipped = 0;
+                    for record in adif.adif_records.as_slice() {
+                        match LogEntry::from_adif_record(&record) {
+                            Ok(entry) => entries.push(entry),
+                            Err(e) => {
+                                ConsoleService::error(&format!("failed to import record [{:?}]: {:?}", e, record));
+                                skipped += 1;
+                            }
+                        }
+                    }
+                    WorkerResponse::AdifImported { entries, skipped }
+                },
+                Err(e) => WorkerResponse::Failed(format!("unable to load adif: {}", e)),
+            }
+        },
+    }
+}
+
+// Entry point inside the worker, answers each request message
+#[wasm_bindgen(js_name = workerMain)]
+pub fn worker_main() {
+    let scope: DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+    let reply = scope.clone();
+    let onmessage = Closure::wrap(Box::new(move |e: MessageEvent| {
+        let response =
+            match e.data().as_string().map(|json| serde_json::from_str::<WorkerRequest>(&json)) {
+                Some(Ok(request)) => process(request),
+                _ => WorkerResponse::Failed("unreadable worker request".to_string()),
+            };
+        match serde_json::to_string(&response) {
+            Ok(json) => {
+                if let Err(err) = reply.post_message(&JsValue::from_str(&json)) {
+                    ConsoleService::error(&format!("worker reply failed: {:?}", err));
+                }
+            },
+            Err(err) => ConsoleService::error(&format!("unable to encode worker reply: {}", err)),
+        }
+    }) as Box<dyn FnMut(MessageEvent)>);
+    scope.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+    onmessage.forget();
+}
+
+// Main thread side of the worker
+pub struct SpotWorker {
+    worker: Option<Worker>,
+    // Kept alive for the life of the worker
+    onmessage: Option<Closure<dyn FnMut(MessageEvent)>>,
+    onerror: Option<Closure<dyn FnMut(Event)>>,
+    // Requests (as posted JSON) not answered yet, oldest first
+    pending: Rc<RefCell<Vec<String>>>,
+    // Set once the worker has failed, later requests run inline
+    dead: Rc<Cell<bool>>,
+    done: Callback<WorkerResponse>,
+}
+
+impl SpotWorker {
+    pub fn new(link: &ComponentLink<Model>) -> SpotWorker {
+        let done = link.callback(Msg::WorkerDone);
+        let pending = Rc::new(RefCell::new(Vec::new()));
+        let dead = Rc::new(Cell::new(false));
+        let mut options = WorkerOptions::new();
+        options.type_(WorkerType::Module);
+        match Worker::new_with_options(WORKER_SCRIPT, &options) {
+            Ok(worker) => {
+                let notify = done.clone();
+                let answered = pending.clone();
+                let onmessage = Closure::wrap(Box::new(move |e: MessageEvent| {
+                    if !answered.borrow().is_empty() {
+                        answered.borrow_mut().remove(0);
+                    }
+                    match e.data().as_string().map(|json| serde_json::from_str::<WorkerResponse>(&json)) {
+                        Some(Ok(response)) => notify.emit(response),
+                        _ => ConsoleService::error("unreadable worker response"),
+                    }
+                }) as Box<dyn FnMut(MessageEvent)>);
+                let notify = done.clone();
+                let unanswered = pending.clone();
+                let failed = dead.clone();
+                let onerror = Closure::wrap(Box::new(move |_: Event| {
+                    ConsoleService::error("web worker failed, processing inline");
+                    failed.set(true);
+                    let requests: Vec<String> = unanswered.borrow_mut().drain(..).collect();
+                    for json in requests {
+                        match serde_json::from_str::<WorkerRequest>(&json) {
+                            Ok(request) => notify.emit(process(request)),
+                            Err(err) => notify.emit(WorkerResponse::Failed(format!("unreadable worker request: {}", err))),
+                        }
+                    }
+                }) as Box<dyn FnMut(Event)>);
+                worker.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+                worker.set_onerror(Some(onerror.as_ref().unchecked_ref()));
+                SpotWorker { worker: Some(worker), onmessage: Some(onmessage), onerror: Some(onerror), pending, dead, done }
+            },
+            Err(err) => {
+                ConsoleService::log(&format!("web worker unavailable, processing inline: {:?}", err));
+                SpotWorker { worker: None, onmessage: None, onerror: None, pending, dead, done }
+            }
+        }
+    }
+
+    // Hand `request` to the worker, the result arrives as Msg::WorkerDone
+    pub fn send(&self, request: WorkerRequest) {
+        let posted =
+            match (&self.worker, self.dead.get(), serde_json::to_string(&request)) {
+                (Some(worker), false, Ok(json)) => {
+                    let posted = worker.post_message(&JsValue::from_str(&json)).is_ok();
+                    if posted {
+                        self.pending.borrow_mut().push(json);
+                    }
+                    posted
+                },
+                _ => false,
+            };
+        if !posted {
+            self.done.emit(process(request));
+        }
+    }
+}
+
+impl Drop for SpotWorker {
+    fn drop(&mut self) {
+        if let Some(worker) = &self.worker {
+            worker.set_onmessage(None);
+            worker.set_onerror(None);
+            worker.terminate();
+        }
+        self.onmessage = None;
+        self.onerror = None;
+    }
+}
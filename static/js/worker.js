@@ -0,0 +1,12 @@
+// Web worker for heavy processing, see src/worker.rs. Requests that
+// arrive while the wasm module loads are handled once it's ready.
+import init, { workerMain } from "../wasm.js";
+
+let pending = [];
+self.onmessage = e => pending.push(e);
+
+init().then(() => {
+    workerMain();
+    pending.forEach(e => self.onmessage(e));
+    pending = [];
+});